@@ -0,0 +1,349 @@
+//! This module provides heap allocations aligned to the cache line:
+//! [`AlignedBox`] and [`AlignedVec`].
+//!
+//! Unlike `Box<CachePadded<T>>`, they don't pad the value, they only align the allocation
+//! to [`PADDING_SIZE`], so the value never shares a cache line with another allocation.
+//!
+//! # Example
+//!
+//! ```rust
+//! use orengine_utils::aligned::{AlignedBox, AlignedVec};
+//! use orengine_utils::cache_padded::PADDING_SIZE;
+//!
+//! let counter = AlignedBox::new(0u64);
+//! let per_core_state = AlignedVec::from_fn(4, |core| core as u32);
+//!
+//! assert_eq!(&*counter as *const u64 as usize % PADDING_SIZE, 0);
+//! assert_eq!(per_core_state.as_ptr() as usize % PADDING_SIZE, 0);
+//! assert_eq!(*per_core_state, [0, 1, 2, 3]);
+//! ```
+use crate::cache_padded::PADDING_SIZE;
+use crate::raw_vec::{RawVec, RawVecAlloc};
+use alloc::alloc::{alloc, dealloc, handle_alloc_error, Layout};
+use core::convert::Infallible;
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+use core::ptr::{self, NonNull};
+
+/// Returns the layout aligned to the cache line.
+///
+/// The size is padded to the alignment, so the end of the allocation
+/// doesn't share a cache line with another allocation either.
+fn aligned_layout(layout: Layout) -> Layout {
+    layout
+        .align_to(PADDING_SIZE)
+        .expect("capacity overflow")
+        .pad_to_align()
+}
+
+/// Allocates memory for the layout aligned to the cache line.
+///
+/// It doesn't allocate if the layout is zero-sized.
+fn allocate(layout: Layout) -> NonNull<u8> {
+    let layout = aligned_layout(layout);
+
+    if layout.size() == 0 {
+        return NonNull::new(ptr::without_provenance_mut(layout.align())).unwrap();
+    }
+
+    let ptr = unsafe { alloc(layout) };
+
+    NonNull::new(ptr).unwrap_or_else(|| handle_alloc_error(layout))
+}
+
+/// Deallocates memory allocated by [`allocate`] with the same layout.
+///
+/// # Safety
+///
+/// `ptr` must be allocated by [`allocate`] with the same layout.
+unsafe fn deallocate(ptr: NonNull<u8>, layout: Layout) {
+    let layout = aligned_layout(layout);
+
+    if layout.size() != 0 {
+        unsafe { dealloc(ptr.as_ptr(), layout) };
+    }
+}
+
+/// Allocates buffers of [`AlignedVec`] aligned to the cache line.
+struct CacheLineAlloc;
+
+impl RawVecAlloc for CacheLineAlloc {
+    type Error = Infallible;
+
+    fn capacity_overflow() -> Infallible {
+        panic!("capacity overflow")
+    }
+
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, Infallible> {
+        Ok(allocate(layout))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { deallocate(ptr, layout) };
+    }
+}
+
+/// A heap-allocated value aligned to the cache line.
+///
+/// Read the [`module-level documentation`](crate::aligned) for more details.
+pub struct AlignedBox<T> {
+    ptr: NonNull<T>,
+    _marker: PhantomData<T>,
+}
+
+impl<T> AlignedBox<T> {
+    /// Allocates the value on the heap at the cache line alignment.
+    pub fn new(value: T) -> Self {
+        let ptr = allocate(Layout::new::<T>()).cast::<T>();
+
+        unsafe { ptr.write(value) };
+
+        Self {
+            ptr,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the inner value and deallocates the memory.
+    pub fn into_inner(self) -> T {
+        let this = core::mem::ManuallyDrop::new(self);
+        let value = unsafe { this.ptr.read() };
+
+        unsafe { deallocate(this.ptr.cast(), Layout::new::<T>()) };
+
+        value
+    }
+
+    /// Returns a raw pointer to the value.
+    pub const fn as_ptr(&self) -> *const T {
+        self.ptr.as_ptr()
+    }
+}
+
+impl<T> Deref for AlignedBox<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T> DerefMut for AlignedBox<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+impl<T: Default> Default for AlignedBox<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Clone> Clone for AlignedBox<T> {
+    fn clone(&self) -> Self {
+        Self::new((**self).clone())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for AlignedBox<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T> Drop for AlignedBox<T> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(self.ptr.as_ptr());
+            deallocate(self.ptr.cast(), Layout::new::<T>());
+        }
+    }
+}
+
+unsafe impl<T: Send> Send for AlignedBox<T> {}
+unsafe impl<T: Sync> Sync for AlignedBox<T> {}
+
+/// A growable array which buffer is aligned to the cache line.
+///
+/// Elements are not padded, only the start of the buffer is aligned.
+/// Use [`CachePadded`](crate::cache_padded::CachePadded) elements
+/// if each of them should have its own cache line.
+///
+/// Read the [`module-level documentation`](crate::aligned) for more details.
+pub struct AlignedVec<T> {
+    raw: RawVec<T, CacheLineAlloc>,
+}
+
+impl<T> AlignedVec<T> {
+    /// Creates a new empty `AlignedVec` without allocating.
+    pub const fn new() -> Self {
+        Self {
+            raw: RawVec::new(CacheLineAlloc),
+        }
+    }
+
+    /// Creates a new empty `AlignedVec` with the provided capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut vec = Self::new();
+
+        vec.reserve(capacity);
+
+        vec
+    }
+
+    /// Creates a new `AlignedVec` with `len` elements created by `f` from their indexes.
+    pub fn from_fn(len: usize, mut f: impl FnMut(usize) -> T) -> Self {
+        let mut vec = Self::with_capacity(len);
+
+        for i in 0..len {
+            vec.push(f(i));
+        }
+
+        vec
+    }
+
+    /// Returns the number of elements.
+    pub const fn len(&self) -> usize {
+        self.raw.len()
+    }
+
+    /// Returns `true` if the `AlignedVec` is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.raw.len() == 0
+    }
+
+    /// Returns the number of elements that can be stored without reallocation.
+    pub const fn capacity(&self) -> usize {
+        self.raw.capacity()
+    }
+
+    /// Returns a raw pointer to the buffer.
+    pub const fn as_ptr(&self) -> *const T {
+        self.raw.as_ptr()
+    }
+
+    /// Reserves the capacity for at least `additional` more elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `isize::MAX` bytes.
+    pub fn reserve(&mut self, additional: usize) {
+        let Ok(()) = self.raw.try_reserve(additional);
+    }
+
+    /// Appends an element to the back.
+    pub fn push(&mut self, value: T) {
+        let Ok(()) = self.raw.try_push(value);
+    }
+
+    /// Removes the last element and returns it, or `None` if it is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        self.raw.pop()
+    }
+
+    /// Drops all elements, keeping the capacity.
+    pub fn clear(&mut self) {
+        self.raw.clear();
+    }
+}
+
+impl<T> Default for AlignedVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Deref for AlignedVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.raw.as_slice()
+    }
+}
+
+impl<T> DerefMut for AlignedVec<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.raw.as_mut_slice()
+    }
+}
+
+impl<T: Clone> Clone for AlignedVec<T> {
+    fn clone(&self) -> Self {
+        Self::from_fn(self.len(), |i| self[i].clone())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for AlignedVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> FromIterator<T> for AlignedVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut vec = Self::with_capacity(iter.size_hint().0);
+
+        for value in iter {
+            vec.push(value);
+        }
+
+        vec
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::rc::Rc;
+
+    #[test]
+    fn test_aligned_vec_grows_and_drops() {
+        let counter = Rc::new(());
+        let mut vec = AlignedVec::new();
+
+        for _ in 0..100 {
+            vec.push(counter.clone());
+
+            assert_eq!(vec.as_ptr() as usize % PADDING_SIZE, 0);
+        }
+
+        assert_eq!(vec.len(), 100);
+        assert_eq!(Rc::strong_count(&counter), 101);
+
+        drop(vec.pop());
+
+        let cloned = vec.clone();
+
+        assert_eq!(Rc::strong_count(&counter), 199);
+
+        drop(vec);
+        drop(cloned);
+
+        assert_eq!(Rc::strong_count(&counter), 1);
+
+        let zst = AlignedBox::new(());
+
+        assert_eq!(zst.as_ptr() as usize % PADDING_SIZE, 0);
+
+        let boxed = AlignedBox::new(counter.clone());
+
+        assert_eq!(Rc::strong_count(&counter), 2);
+
+        drop(boxed.into_inner());
+
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn test_aligned_layout_is_padded() {
+        assert_eq!(aligned_layout(Layout::new::<u8>()).size(), PADDING_SIZE);
+        assert_eq!(
+            aligned_layout(Layout::array::<u8>(PADDING_SIZE + 1).unwrap()).size(),
+            2 * PADDING_SIZE
+        );
+        assert_eq!(aligned_layout(Layout::new::<()>()).size(), 0);
+    }
+}
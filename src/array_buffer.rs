@@ -1,12 +1,80 @@
 //! This module contains the [`ArrayBuffer`].
 use crate::hints::{assert_hint, likely, unlikely};
+use crate::NotEnoughSpace;
+use core::fmt;
+use core::hash::{Hash, Hasher};
 use core::mem;
 use core::mem::MaybeUninit;
-use core::ops::{Deref, DerefMut};
+use core::ops::{Bound, Deref, DerefMut, RangeBounds};
+use core::ptr;
 use core::ptr::{slice_from_raw_parts, slice_from_raw_parts_mut};
 
+/// A type that can be used as the length of an [`ArrayBuffer`].
+///
+/// It is implemented for `u8`, `u16`, `u32` and `usize`. A smaller length type
+/// makes the buffer smaller, but limits its capacity.
+pub trait ArrayBufferLen: Copy + sealed::Sealed {
+    /// The zero length.
+    const ZERO: Self;
+    /// The maximum capacity of a buffer with this length type.
+    const MAX: usize;
+
+    /// Converts the length to `usize`.
+    fn to_usize(self) -> usize;
+
+    /// Converts `usize` to the length.
+    ///
+    /// The provided length is never more than [`MAX`](Self::MAX).
+    fn from_usize(len: usize) -> Self;
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Implements [`ArrayBufferLen`] for the provided unsigned integer types.
+macro_rules! impl_array_buffer_len {
+    ($($ty:ty),*) => {
+        $(
+            impl sealed::Sealed for $ty {}
+
+            impl ArrayBufferLen for $ty {
+                const ZERO: Self = 0;
+                #[allow(clippy::cast_possible_truncation, reason = "Truncation of all ones gives `usize::MAX`")]
+                const MAX: usize = <$ty>::MAX as usize;
+
+                #[inline(always)]
+                fn to_usize(self) -> usize {
+                    self as usize
+                }
+
+                #[allow(clippy::cast_possible_truncation, reason = "The length is not more than MAX")]
+                #[inline(always)]
+                fn from_usize(len: usize) -> Self {
+                    len as Self
+                }
+            }
+        )*
+    };
+}
+
+impl_array_buffer_len!(u8, u16, u32, usize);
+
 /// `ArrayBuffer` is a fixed-sized array-based buffer.
 ///
+/// The length is stored as `L` (`usize` by default). Use a smaller length type
+/// (`u8`, `u16` or `u32`) to make small buffers more compact:
+///
+/// ```rust
+/// use orengine_utils::ArrayBuffer;
+///
+/// assert_eq!(size_of::<ArrayBuffer<u8, 15, u8>>(), 16);
+///
+/// let buffer = ArrayBuffer::<u8, 15, u8>::from_iter(*b"hello");
+///
+/// assert_eq!(buffer, *b"hello");
+/// ```
+///
 /// # Example
 ///
 /// ```rust
@@ -28,17 +96,23 @@ use core::ptr::{slice_from_raw_parts, slice_from_raw_parts_mut};
 /// assert_eq!(buffer.pop(), Some(21));
 /// assert_eq!(buffer.pop(), Some(22));
 /// ```
-pub struct ArrayBuffer<T, const N: usize> {
+pub struct ArrayBuffer<T, const N: usize, L: ArrayBufferLen = usize> {
     array: [MaybeUninit<T>; N],
-    len: usize,
+    len: L,
 }
 
-impl<T, const N: usize> ArrayBuffer<T, N> {
+impl<T, const N: usize, L: ArrayBufferLen> ArrayBuffer<T, N, L> {
     /// Creates a new ` ArrayBuffer `.
+    ///
+    /// `N` must fit into the length type `L`, it is checked at compile time.
     pub const fn new() -> Self {
+        const {
+            assert!(N <= L::MAX, "the capacity doesn't fit into the length type");
+        };
+
         Self {
             array: [const { MaybeUninit::uninit() }; N],
-            len: 0,
+            len: L::ZERO,
         }
     }
 
@@ -49,12 +123,20 @@ impl<T, const N: usize> ArrayBuffer<T, N> {
 
     /// Returns the number of elements in the buffer.
     pub fn len(&self) -> usize {
-        self.len
+        self.len.to_usize()
+    }
+
+    /// Stores the provided length in the length field.
+    #[inline(always)]
+    fn store_len(&mut self, len: usize) {
+        debug_assert!(len <= N);
+
+        self.len = L::from_usize(len);
     }
 
     /// Returns `true` if the buffer is empty.
     pub fn is_empty(&self) -> bool {
-        self.len == 0
+        self.len() == 0
     }
 
     /// Forces the length of the buffer to `new_len`.
@@ -63,13 +145,64 @@ impl<T, const N: usize> ArrayBuffer<T, N> {
     ///
     /// - `new_len` must be less than or equal to `N`.
     /// - The elements at `old_len..new_len` must be initialized.
+    ///
+    /// If `new_len` is less than the current length, the elements at `new_len..old_len`
+    /// are not dropped, use [`truncate`](Self::truncate) for that.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::ArrayBuffer;
+    ///
+    /// let mut buffer = ArrayBuffer::<u8, 16>::new();
+    ///
+    /// // Imagine that it is a `read` syscall.
+    /// let filled = unsafe {
+    ///     buffer.as_mut_ptr().copy_from_nonoverlapping(b"hello".as_ptr(), 5);
+    ///
+    ///     5
+    /// };
+    ///
+    /// unsafe { buffer.set_len(filled) };
+    ///
+    /// assert_eq!(&*buffer, b"hello");
+    /// ```
     pub unsafe fn set_len(&mut self, new_len: usize) {
         debug_assert!(
             new_len <= self.capacity(),
             "provided len is more than the capacity: {new_len} > {N}"
         );
 
-        self.len = new_len;
+        self.store_len(new_len);
+    }
+
+    /// Shortens the buffer, keeping the first `len` elements and dropping the rest.
+    ///
+    /// Does nothing if `len` is greater than or equal to the current length.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::ArrayBuffer;
+    ///
+    /// let mut buffer = ArrayBuffer::from([1, 2, 3, 4]);
+    ///
+    /// buffer.truncate(2);
+    ///
+    /// assert_eq!(&*buffer, &[1, 2]);
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len() {
+            return;
+        }
+
+        let tail =
+            slice_from_raw_parts_mut(unsafe { self.as_mut_ptr().add(len) }, self.len() - len);
+
+        // Update the length first, so a panicking destructor can't cause a double drop.
+        self.store_len(len);
+
+        unsafe { ptr::drop_in_place(tail) };
     }
 
     /// Returns a pointer to the first element of the buffer.
@@ -90,13 +223,13 @@ impl<T, const N: usize> ArrayBuffer<T, N> {
     pub unsafe fn push_unchecked(&mut self, item: T) {
         assert_hint(self.len() < N, "Tried to push to a full array buffer");
 
-        self.array[self.len].write(item);
-        self.len += 1;
+        self.array[self.len()].write(item);
+        self.store_len(self.len() + 1);
     }
 
     /// Appends an element to the buffer or returns `Err(value)` if the buffer is full.
     pub fn push(&mut self, item: T) -> Result<(), T> {
-        if unlikely(self.len == self.capacity()) {
+        if unlikely(self.len() == self.capacity()) {
             return Err(item);
         }
 
@@ -105,15 +238,300 @@ impl<T, const N: usize> ArrayBuffer<T, N> {
         Ok(())
     }
 
+    /// Appends clones of all elements of the slice to the buffer.
+    ///
+    /// It returns an error and doesn't change the buffer if it does not have enough space.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::ArrayBuffer;
+    ///
+    /// let mut buffer = ArrayBuffer::<u8, 4>::new();
+    ///
+    /// buffer.extend_from_slice(&[1, 2, 3]).unwrap();
+    ///
+    /// assert!(buffer.extend_from_slice(&[4, 5]).is_err());
+    /// assert_eq!(&*buffer, &[1, 2, 3]);
+    /// ```
+    pub fn extend_from_slice(&mut self, slice: &[T]) -> Result<(), NotEnoughSpace>
+    where
+        T: Clone,
+    {
+        if unlikely(slice.len() > N - self.len()) {
+            return Err(NotEnoughSpace);
+        }
+
+        for item in slice {
+            unsafe { self.push_unchecked(item.clone()) };
+        }
+
+        Ok(())
+    }
+
+    /// Appends `n` elements returned by the provided function to the buffer.
+    ///
+    /// It returns an error and doesn't change the buffer if it does not have enough space.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::ArrayBuffer;
+    ///
+    /// let mut buffer = ArrayBuffer::<Option<u32>, 4>::new();
+    ///
+    /// buffer.push_n(3, || None).unwrap();
+    ///
+    /// assert!(buffer.push_n(2, || None).is_err());
+    /// assert_eq!(buffer, [None, None, None]);
+    /// ```
+    pub fn push_n<F>(&mut self, n: usize, mut f: F) -> Result<(), NotEnoughSpace>
+    where
+        F: FnMut() -> T,
+    {
+        if unlikely(n > N - self.len()) {
+            return Err(NotEnoughSpace);
+        }
+
+        for _ in 0..n {
+            unsafe { self.push_unchecked(f()) };
+        }
+
+        Ok(())
+    }
+
+    /// Resizes the buffer to `new_len`.
+    ///
+    /// If `new_len` is greater than the current length, the buffer is extended with
+    /// elements returned by the provided function, otherwise it is truncated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_len` is greater than `N`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::ArrayBuffer;
+    ///
+    /// let mut buffer = ArrayBuffer::<u32, 4>::from_iter([1]);
+    /// let mut next = 1;
+    ///
+    /// buffer.resize_with(4, || {
+    ///     next *= 2;
+    ///
+    ///     next
+    /// });
+    ///
+    /// assert_eq!(buffer, [1, 2, 4, 8]);
+    ///
+    /// buffer.resize_with(2, || unreachable!());
+    ///
+    /// assert_eq!(buffer, [1, 2]);
+    /// ```
+    #[track_caller]
+    pub fn resize_with<F>(&mut self, new_len: usize, f: F)
+    where
+        F: FnMut() -> T,
+    {
+        assert!(
+            new_len <= N,
+            "Tried to resize an array buffer over its capacity: {new_len} > {N}"
+        );
+
+        if new_len <= self.len() {
+            self.truncate(new_len);
+        } else {
+            let res = self.push_n(new_len - self.len(), f);
+
+            debug_assert!(res.is_ok());
+        }
+    }
+
     /// Pops an element from the buffer or returns `None` if the buffer is empty.
     pub fn pop(&mut self) -> Option<T> {
-        if unlikely(self.len == 0) {
+        if unlikely(self.is_empty()) {
             return None;
         }
 
-        self.len -= 1;
+        self.store_len(self.len() - 1);
+
+        Some(unsafe { self.array[self.len()].as_ptr().read() })
+    }
+
+    /// Removes the element at `idx` and returns it.
+    ///
+    /// The removed element is replaced by the last element of the buffer.
+    /// It doesn't preserve the order, but it is O(1).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::ArrayBuffer;
+    ///
+    /// let mut buffer = ArrayBuffer::from([1, 2, 3, 4]);
+    ///
+    /// assert_eq!(buffer.swap_remove(1), 2);
+    /// assert_eq!(buffer, [1, 4, 3]);
+    /// ```
+    #[track_caller]
+    pub fn swap_remove(&mut self, idx: usize) -> T {
+        assert!(
+            idx < self.len(),
+            "swap_remove index (is {idx}) should be < len (is {})",
+            self.len()
+        );
+
+        self.store_len(self.len() - 1);
+
+        unsafe {
+            let ptr = self.as_mut_ptr();
+            let removed = ptr.add(idx).read();
+
+            ptr::copy(ptr.add(self.len()), ptr.add(idx), 1);
+
+            removed
+        }
+    }
+
+    /// Inserts an element into the buffer sorted by the key and returns its index,
+    /// or returns `Err(item)` if the buffer is full.
+    ///
+    /// The buffer must be sorted by the key. The element is inserted after
+    /// all elements with the same key, so the insertion is stable.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::ArrayBuffer;
+    ///
+    /// let mut deadlines = ArrayBuffer::<(u64, &str), 4>::new();
+    ///
+    /// deadlines.sort_insert_by_key((30, "c"), |(deadline, _)| *deadline).unwrap();
+    /// deadlines.sort_insert_by_key((10, "a"), |(deadline, _)| *deadline).unwrap();
+    ///
+    /// assert_eq!(deadlines.sort_insert_by_key((30, "d"), |(deadline, _)| *deadline), Ok(2));
+    /// assert_eq!(deadlines, [(10, "a"), (30, "c"), (30, "d")]);
+    /// ```
+    pub fn sort_insert_by_key<K, F>(&mut self, item: T, mut key: F) -> Result<usize, T>
+    where
+        F: FnMut(&T) -> K,
+        K: Ord,
+    {
+        if unlikely(self.len() == N) {
+            return Err(item);
+        }
+
+        let item_key = key(&item);
+        let idx = self.partition_point(|other| key(other) <= item_key);
 
-        Some(unsafe { self.array[self.len].as_ptr().read() })
+        unsafe {
+            let ptr = self.as_mut_ptr().add(idx);
+
+            ptr::copy(ptr, ptr.add(1), self.len() - idx);
+            ptr.write(item);
+        }
+
+        self.store_len(self.len() + 1);
+
+        Ok(idx)
+    }
+
+    /// Removes all but the first of consecutive elements in the buffer
+    /// that resolve to the same key.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::ArrayBuffer;
+    ///
+    /// let mut buffer = ArrayBuffer::from([10, 20, 21, 30, 20]);
+    ///
+    /// buffer.dedup_by_key(|i| *i / 10);
+    ///
+    /// assert_eq!(buffer, [10, 20, 30, 20]);
+    /// ```
+    pub fn dedup_by_key<K, F>(&mut self, mut key: F)
+    where
+        F: FnMut(&mut T) -> K,
+        K: PartialEq,
+    {
+        self.dedup_by(|a, b| key(a) == key(b));
+    }
+
+    /// Removes all but the first of consecutive elements in the buffer
+    /// satisfying a given equality relation.
+    ///
+    /// The `same_bucket` function is passed references to two elements from the buffer:
+    /// the checked element and the last retained one. If it returns `true`,
+    /// the checked element is removed.
+    pub fn dedup_by<F>(&mut self, mut same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        /// Moves the not checked elements down and fixes the length
+        /// even if the function panics.
+        struct Guard<'array_buffer, T, const N: usize, L: ArrayBufferLen> {
+            buffer: &'array_buffer mut ArrayBuffer<T, N, L>,
+            original_len: usize,
+            read: usize,
+            write: usize,
+        }
+
+        impl<T, const N: usize, L: ArrayBufferLen> Drop for Guard<'_, T, N, L> {
+            fn drop(&mut self) {
+                unsafe {
+                    let ptr = self.buffer.as_mut_ptr();
+
+                    ptr::copy(
+                        ptr.add(self.read),
+                        ptr.add(self.write),
+                        self.original_len - self.read,
+                    );
+                }
+
+                self.buffer
+                    .store_len(self.original_len - (self.read - self.write));
+            }
+        }
+
+        let original_len = self.len();
+
+        if original_len <= 1 {
+            return;
+        }
+
+        self.store_len(0);
+
+        let mut guard = Guard {
+            buffer: self,
+            original_len,
+            read: 1,
+            write: 1,
+        };
+
+        while guard.read < original_len {
+            let ptr = guard.buffer.as_mut_ptr();
+            let current = unsafe { ptr.add(guard.read) };
+            let last_retained = unsafe { ptr.add(guard.write - 1) };
+
+            if same_bucket(unsafe { &mut *current }, unsafe { &mut *last_retained }) {
+                guard.read += 1;
+
+                unsafe { ptr::drop_in_place(current) };
+            } else {
+                if guard.read != guard.write {
+                    unsafe { ptr::copy_nonoverlapping(current, ptr.add(guard.write), 1) };
+                }
+
+                guard.read += 1;
+                guard.write += 1;
+            }
+        }
     }
 
     /// Clears with calling the provided function on each element.
@@ -121,22 +539,22 @@ impl<T, const N: usize> ArrayBuffer<T, N> {
     where
         F: FnMut(T),
     {
-        for i in 0..self.len {
+        for i in 0..self.len() {
             f(unsafe { self.array[i].as_ptr().read() });
         }
 
-        self.len = 0;
+        self.store_len(0);
     }
 
     /// Drops all elements in the buffer and set the length to 0.
     pub fn clear(&mut self) {
         if mem::needs_drop::<T>() {
-            for i in 0..self.len {
+            for i in 0..self.len() {
                 drop(unsafe { self.array[i].as_ptr().read() });
             }
         }
 
-        self.len = 0;
+        self.store_len(0);
     }
 
     /// Returns a reference iterator over the buffer.
@@ -180,7 +598,7 @@ impl<T, const N: usize> ArrayBuffer<T, N> {
         }
 
         let current = self.as_ptr();
-        let end = unsafe { current.add(self.len) };
+        let end = unsafe { current.add(self.len()) };
 
         Iter {
             current,
@@ -230,7 +648,7 @@ impl<T, const N: usize> ArrayBuffer<T, N> {
         }
 
         let current = self.as_mut_ptr();
-        let end = unsafe { current.add(self.len) };
+        let end = unsafe { current.add(self.len()) };
 
         IterMut {
             current,
@@ -254,83 +672,978 @@ impl<T, const N: usize> ArrayBuffer<T, N> {
 
         debug_assert!(filled <= N, "Filled more than the capacity");
 
-        self.len = filled;
-    }
-    /// Returns a pointer to the underlying array.
-    fn as_slice_ptr(&self) -> *const [T] {
-        slice_from_raw_parts(self.as_ptr(), self.len)
+        self.store_len(filled);
     }
 
-    /// Returns a mutable pointer to the underlying array.
-    fn as_mut_slice_ptr(&mut self) -> *mut [T] {
-        slice_from_raw_parts_mut(self.as_mut_ptr(), self.len)
-    }
-}
+    /// Maps `len` initialized elements starting from `ptr` from `T` to `U` in place.
+    ///
+    /// If `f` returns an error or panics, the already mapped and not yet mapped elements
+    /// are dropped.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must point to `len` initialized elements of type `T`.
+    /// - `U` must have the same size and alignment as `T`.
+    unsafe fn transform_in_place<U, E>(
+        ptr: *mut T,
+        len: usize,
+        mut f: impl FnMut(T) -> Result<U, E>,
+    ) -> Result<(), E> {
+        struct Guard<T, U> {
+            ptr: *mut T,
+            len: usize,
+            mapped: usize,
+            _marker: core::marker::PhantomData<U>,
+        }
 
-impl<T, const N: usize> Deref for ArrayBuffer<T, N> {
-    type Target = [T];
+        impl<T, U> Drop for Guard<T, U> {
+            fn drop(&mut self) {
+                unsafe {
+                    ptr::drop_in_place(slice_from_raw_parts_mut(self.ptr.cast::<U>(), self.mapped));
+                    // The element at `mapped` has been moved into the function.
+                    ptr::drop_in_place(slice_from_raw_parts_mut(
+                        self.ptr.add(self.mapped + 1),
+                        self.len - self.mapped - 1,
+                    ));
+                }
+            }
+        }
 
-    fn deref(&self) -> &Self::Target {
-        unsafe { &*self.as_slice_ptr() }
-    }
-}
+        let mut guard = Guard::<T, U> {
+            ptr,
+            len,
+            mapped: 0,
+            _marker: core::marker::PhantomData,
+        };
 
-impl<T, const N: usize> AsRef<[T]> for ArrayBuffer<T, N> {
-    fn as_ref(&self) -> &[T] {
-        unsafe { &*self.as_slice_ptr() }
-    }
-}
+        while guard.mapped < len {
+            let slot = unsafe { ptr.add(guard.mapped) };
 
-impl<T, const N: usize> DerefMut for ArrayBuffer<T, N> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        unsafe { &mut *self.as_mut_slice_ptr() }
-    }
-}
+            unsafe { slot.cast::<U>().write(f(slot.read())?) };
 
-impl<T, const N: usize> AsMut<[T]> for ArrayBuffer<T, N> {
-    fn as_mut(&mut self) -> &mut [T] {
-        unsafe { &mut *self.as_mut_slice_ptr() }
-    }
-}
+            guard.mapped += 1;
+        }
 
-impl<T, const N: usize> Default for ArrayBuffer<T, N> {
-    fn default() -> Self {
-        Self::new()
+        mem::forget(guard);
+
+        Ok(())
     }
-}
 
-impl<T, const N: usize> From<[T; N]> for ArrayBuffer<T, N> {
-    fn from(array: [T; N]) -> Self {
-        Self {
-            array: unsafe { (&raw const array).cast::<[MaybeUninit<T>; N]>().read() },
-            len: N,
+    /// Replaces each element of the buffer with the result of the provided function.
+    ///
+    /// If the function panics, all elements are dropped and the buffer becomes empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::ArrayBuffer;
+    ///
+    /// let mut buffer = ArrayBuffer::from([1u32, 2, 3]);
+    ///
+    /// buffer.map_in_place(|x| x * 10);
+    ///
+    /// assert_eq!(&*buffer, &[10, 20, 30]);
+    /// ```
+    pub fn map_in_place<F>(&mut self, mut f: F)
+    where
+        F: FnMut(T) -> T,
+    {
+        let len = self.len();
+
+        self.store_len(0);
+
+        let res = unsafe {
+            Self::transform_in_place::<T, core::convert::Infallible>(
+                self.as_mut_ptr(),
+                len,
+                |item| Ok(f(item)),
+            )
+        };
+
+        match res {
+            Ok(()) => self.store_len(len),
+            Err(infallible) => match infallible {},
         }
     }
-}
 
-impl<T: Clone, const N: usize> Clone for ArrayBuffer<T, N> {
-    fn clone(&self) -> Self {
-        let mut res = Self {
-            array: [const { MaybeUninit::uninit() }; N],
-            len: self.len,
+    /// Converts the buffer into an `ArrayBuffer<U, N>` reusing the same storage.
+    ///
+    /// `U` must have the same size and alignment as `T`, it is checked at compile time.
+    ///
+    /// If the provided function returns an error, all elements (both converted
+    /// and not yet converted) are dropped and the error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::ArrayBuffer;
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct Id(u32);
+    ///
+    /// let buffer = ArrayBuffer::from([1u32, 2, 3]);
+    /// let ids = buffer.try_transform(|x| Ok::<_, ()>(Id(x))).unwrap();
+    ///
+    /// assert_eq!(&*ids, &[Id(1), Id(2), Id(3)]);
+    ///
+    /// let buffer = ArrayBuffer::from([1u32, 0, 3]);
+    /// let res = buffer.try_transform(|x| core::num::NonZeroU32::new(x).ok_or(x));
+    ///
+    /// assert_eq!(res.err(), Some(0));
+    /// ```
+    pub fn try_transform<U, E, F>(self, f: F) -> Result<ArrayBuffer<U, N, L>, E>
+    where
+        F: FnMut(T) -> Result<U, E>,
+    {
+        const {
+            assert!(
+                size_of::<T>() == size_of::<U>() && align_of::<T>() == align_of::<U>(),
+                "`try_transform` requires `T` and `U` to have the same size and alignment"
+            );
         };
 
-        for (i, item) in self.as_ref().iter().enumerate() {
-            res.array[i] = MaybeUninit::new(item.clone());
-        }
+        let mut this = mem::ManuallyDrop::new(self);
+        let len = this.len();
+
+        unsafe { Self::transform_in_place(this.as_mut_ptr(), len, f)? };
 
-        res
+        Ok(ArrayBuffer {
+            array: unsafe { (&raw const this.array).cast::<[MaybeUninit<U>; N]>().read() },
+            len: this.len,
+        })
     }
-}
 
-impl<T, const N: usize> Drop for ArrayBuffer<T, N> {
-    fn drop(&mut self) {
-        self.clear();
+    /// Converts the buffer into the underlying array if the buffer is full,
+    /// otherwise returns the buffer back.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::ArrayBuffer;
+    ///
+    /// let mut buffer = ArrayBuffer::<u32, 3>::new();
+    ///
+    /// buffer.extend([1, 2]);
+    ///
+    /// let Err(mut buffer) = buffer.into_full_array() else {
+    ///     unreachable!()
+    /// };
+    ///
+    /// buffer.push(3).unwrap();
+    ///
+    /// assert_eq!(buffer.into_full_array().ok(), Some([1, 2, 3]));
+    /// ```
+    pub fn into_full_array(self) -> Result<[T; N], Self> {
+        if unlikely(self.len() != N) {
+            return Err(self);
+        }
+
+        let this = mem::ManuallyDrop::new(self);
+
+        Ok(unsafe { (&raw const this.array).cast::<[T; N]>().read() })
     }
-}
 
-#[cfg(test)]
-mod tests {
+    /// Converts the buffer into the underlying array and the number of
+    /// initialized elements at its beginning.
+    ///
+    /// The caller is responsible for dropping the initialized elements.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::ArrayBuffer;
+    ///
+    /// let mut buffer = ArrayBuffer::<String, 4>::new();
+    ///
+    /// buffer.push("hello".to_string()).unwrap();
+    ///
+    /// let (array, len) = buffer.into_parts();
+    ///
+    /// assert_eq!(len, 1);
+    /// assert_eq!(unsafe { array[0].assume_init_read() }, "hello");
+    /// ```
+    pub fn into_parts(self) -> ([MaybeUninit<T>; N], usize) {
+        let this = mem::ManuallyDrop::new(self);
+
+        (unsafe { (&raw const this.array).read() }, this.len())
+    }
+
+    /// Retains only the elements for which the provided function returns `true`.
+    ///
+    /// It compacts the buffer in place and preserves the order of the retained elements.
+    /// If the function or a destructor panics, the buffer stays valid.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::ArrayBuffer;
+    ///
+    /// let mut buffer = ArrayBuffer::from([1, 2, 3, 4, 5]);
+    ///
+    /// buffer.retain(|x| {
+    ///     *x *= 10;
+    ///
+    ///     *x % 20 != 0
+    /// });
+    ///
+    /// assert_eq!(buffer, [10, 30, 50]);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        /// Moves the not processed elements down and fixes the length
+        /// even if the function panics.
+        struct Guard<'array_buffer, T, const N: usize, L: ArrayBufferLen> {
+            buffer: &'array_buffer mut ArrayBuffer<T, N, L>,
+            original_len: usize,
+            processed: usize,
+            deleted: usize,
+        }
+
+        impl<T, const N: usize, L: ArrayBufferLen> Drop for Guard<'_, T, N, L> {
+            fn drop(&mut self) {
+                if self.deleted > 0 {
+                    unsafe {
+                        let ptr = self.buffer.as_mut_ptr();
+
+                        ptr::copy(
+                            ptr.add(self.processed),
+                            ptr.add(self.processed - self.deleted),
+                            self.original_len - self.processed,
+                        );
+                    }
+                }
+
+                self.buffer.store_len(self.original_len - self.deleted);
+            }
+        }
+
+        let original_len = self.len();
+
+        self.store_len(0);
+
+        let mut guard = Guard {
+            buffer: self,
+            original_len,
+            processed: 0,
+            deleted: 0,
+        };
+
+        while guard.processed < original_len {
+            let current = unsafe { guard.buffer.as_mut_ptr().add(guard.processed) };
+            let is_retained = f(unsafe { &mut *current });
+
+            guard.processed += 1;
+
+            if is_retained {
+                if guard.deleted > 0 {
+                    unsafe {
+                        ptr::copy_nonoverlapping(current, current.sub(guard.deleted), 1);
+                    };
+                }
+            } else {
+                guard.deleted += 1;
+
+                unsafe { ptr::drop_in_place(current) };
+            }
+        }
+    }
+
+    /// Removes the elements in the provided range from the buffer and returns them
+    /// as an iterator. The elements after the range are shifted down.
+    ///
+    /// The not iterated elements of the range are dropped with the iterator.
+    /// If the iterator is leaked, the buffer loses the drained elements and the elements after
+    /// the range, but it is still safe to use.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than the end
+    /// or if the end is greater than the length of the buffer.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::ArrayBuffer;
+    ///
+    /// let mut buffer = ArrayBuffer::from([1, 2, 3, 4, 5]);
+    /// let drained: Vec<_> = buffer.drain(..2).collect();
+    ///
+    /// assert_eq!(drained, [1, 2]);
+    /// assert_eq!(buffer, [3, 4, 5]);
+    /// ```
+    #[track_caller]
+    pub fn drain<R>(&mut self, range: R) -> ArrayBufferDrain<'_, T, N, L>
+    where
+        R: RangeBounds<usize>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end + 1,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.len(),
+        };
+
+        assert!(
+            start <= end,
+            "drain range start ({start}) is greater than end ({end})"
+        );
+        assert!(
+            end <= self.len(),
+            "drain range end ({end}) is greater than the length ({})",
+            self.len()
+        );
+
+        let tail_len = self.len() - end;
+
+        // The buffer doesn't own the drained elements and the tail until the drain is dropped.
+        self.store_len(start);
+
+        ArrayBufferDrain {
+            buffer: self,
+            current: start,
+            end,
+            tail_start: end,
+            tail_len,
+        }
+    }
+
+    /// Returns a pointer to the underlying array.
+    fn as_slice_ptr(&self) -> *const [T] {
+        slice_from_raw_parts(self.as_ptr(), self.len())
+    }
+
+    /// Returns a mutable pointer to the underlying array.
+    fn as_mut_slice_ptr(&mut self) -> *mut [T] {
+        slice_from_raw_parts_mut(self.as_mut_ptr(), self.len())
+    }
+}
+
+impl<T, const N: usize, L: ArrayBufferLen> Deref for ArrayBuffer<T, N, L> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.as_slice_ptr() }
+    }
+}
+
+impl<T, const N: usize, L: ArrayBufferLen> AsRef<[T]> for ArrayBuffer<T, N, L> {
+    fn as_ref(&self) -> &[T] {
+        unsafe { &*self.as_slice_ptr() }
+    }
+}
+
+impl<T, const N: usize, L: ArrayBufferLen> DerefMut for ArrayBuffer<T, N, L> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *self.as_mut_slice_ptr() }
+    }
+}
+
+impl<T, const N: usize, L: ArrayBufferLen> AsMut<[T]> for ArrayBuffer<T, N, L> {
+    fn as_mut(&mut self) -> &mut [T] {
+        unsafe { &mut *self.as_mut_slice_ptr() }
+    }
+}
+
+impl<T, const N: usize, L: ArrayBufferLen> Extend<T> for ArrayBuffer<T, N, L> {
+    /// Appends all elements of the iterator to the buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer does not have enough space.
+    /// In this case, the elements that fit are appended.
+    #[track_caller]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            assert!(
+                self.push(item).is_ok(),
+                "Tried to extend a full array buffer"
+            );
+        }
+    }
+}
+
+impl<T, const N: usize, L: ArrayBufferLen> Default for ArrayBuffer<T, N, L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// `From` is implemented only for the default length type, so `ArrayBuffer::from` doesn't
+// require type annotations. Use `FromIterator` for other length types.
+impl<T, const N: usize> From<[T; N]> for ArrayBuffer<T, N> {
+    fn from(array: [T; N]) -> Self {
+        let array = mem::ManuallyDrop::new(array);
+        let mut buffer = Self::new();
+
+        buffer.array = unsafe { (&raw const array).cast::<[MaybeUninit<T>; N]>().read() };
+        buffer.store_len(N);
+
+        buffer
+    }
+}
+
+impl<T: Clone, const N: usize> TryFrom<&[T]> for ArrayBuffer<T, N> {
+    type Error = NotEnoughSpace;
+
+    /// Creates a new `ArrayBuffer` with clones of all elements of the slice.
+    ///
+    /// It returns an error if the slice is longer than `N`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::ArrayBuffer;
+    ///
+    /// let buffer = ArrayBuffer::<u8, 4>::try_from(&[1, 2, 3][..]).unwrap();
+    ///
+    /// assert_eq!(&*buffer, &[1, 2, 3]);
+    /// assert!(ArrayBuffer::<u8, 2>::try_from(&[1, 2, 3][..]).is_err());
+    /// ```
+    fn try_from(slice: &[T]) -> Result<Self, Self::Error> {
+        let mut buffer = Self::new();
+
+        buffer.extend_from_slice(slice)?;
+
+        Ok(buffer)
+    }
+}
+
+impl<T, const N: usize, L: ArrayBufferLen> FromIterator<T> for ArrayBuffer<T, N, L> {
+    /// Creates a new `ArrayBuffer` with the first `N` items of the iterator.
+    ///
+    /// The rest of the iterator is not consumed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::ArrayBuffer;
+    ///
+    /// let buffer: ArrayBuffer<u32, 4> = (0..10).collect();
+    ///
+    /// assert_eq!(&*buffer, &[0, 1, 2, 3]);
+    /// ```
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut buffer = Self::new();
+
+        for item in iter.into_iter().take(N) {
+            unsafe { buffer.push_unchecked(item) };
+        }
+
+        buffer
+    }
+}
+
+/// An owning iterator over the elements of an [`ArrayBuffer`].
+///
+/// The not iterated elements are dropped with the iterator.
+/// It is created by [`ArrayBuffer::into_iter`].
+pub struct ArrayBufferIntoIter<T, const N: usize> {
+    array: [MaybeUninit<T>; N],
+    /// The index of the next element to return from the front.
+    start: usize,
+    /// The index after the next element to return from the back.
+    end: usize,
+}
+
+impl<T, const N: usize> Iterator for ArrayBufferIntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if unlikely(self.start == self.end) {
+            return None;
+        }
+
+        self.start += 1;
+
+        Some(unsafe { self.array[self.start - 1].assume_init_read() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let size = self.end - self.start;
+
+        (size, Some(size))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for ArrayBufferIntoIter<T, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if unlikely(self.start == self.end) {
+            return None;
+        }
+
+        self.end -= 1;
+
+        Some(unsafe { self.array[self.end].assume_init_read() })
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for ArrayBufferIntoIter<T, N> {
+    fn len(&self) -> usize {
+        self.end - self.start
+    }
+}
+
+impl<T, const N: usize> core::iter::FusedIterator for ArrayBufferIntoIter<T, N> {}
+
+impl<T, const N: usize> Drop for ArrayBufferIntoIter<T, N> {
+    fn drop(&mut self) {
+        let rest = slice_from_raw_parts_mut(
+            unsafe { self.array.as_mut_ptr().add(self.start).cast::<T>() },
+            self.end - self.start,
+        );
+
+        // Update the bounds first, so a panicking destructor can't cause a double drop.
+        self.start = self.end;
+
+        unsafe { ptr::drop_in_place(rest) };
+    }
+}
+
+impl<T, const N: usize, L: ArrayBufferLen> IntoIterator for ArrayBuffer<T, N, L> {
+    type Item = T;
+    type IntoIter = ArrayBufferIntoIter<T, N>;
+
+    /// Returns an owning iterator over the elements of the buffer.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::ArrayBuffer;
+    ///
+    /// let buffer = ArrayBuffer::from([String::from("a"), String::from("b")]);
+    ///
+    /// assert_eq!(buffer.into_iter().collect::<Vec<_>>(), ["a", "b"]);
+    /// ```
+    fn into_iter(self) -> Self::IntoIter {
+        let (array, len) = self.into_parts();
+
+        ArrayBufferIntoIter {
+            array,
+            start: 0,
+            end: len,
+        }
+    }
+}
+
+impl<'array_buffer, T, const N: usize, L: ArrayBufferLen> IntoIterator
+    for &'array_buffer ArrayBuffer<T, N, L>
+{
+    type Item = &'array_buffer T;
+    type IntoIter = core::slice::Iter<'array_buffer, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_ref().iter()
+    }
+}
+
+impl<'array_buffer, T, const N: usize, L: ArrayBufferLen> IntoIterator
+    for &'array_buffer mut ArrayBuffer<T, N, L>
+{
+    type Item = &'array_buffer mut T;
+    type IntoIter = core::slice::IterMut<'array_buffer, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_mut().iter_mut()
+    }
+}
+
+impl<T: Clone, const N: usize, L: ArrayBufferLen> Clone for ArrayBuffer<T, N, L> {
+    fn clone(&self) -> Self {
+        // The length is increased after each clone, so a panicking `clone` can't cause
+        // dropping of uninitialized elements.
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: fmt::Debug, const N: usize, L: ArrayBufferLen> fmt::Debug for ArrayBuffer<T, N, L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.as_ref()).finish()
+    }
+}
+
+impl<T, U, const N: usize, const M: usize, L: ArrayBufferLen, L2: ArrayBufferLen>
+    PartialEq<ArrayBuffer<U, M, L2>> for ArrayBuffer<T, N, L>
+where
+    T: PartialEq<U>,
+{
+    fn eq(&self, other: &ArrayBuffer<U, M, L2>) -> bool {
+        self.as_ref() == other.as_ref()
+    }
+}
+
+impl<T, U, const N: usize, L: ArrayBufferLen> PartialEq<[U]> for ArrayBuffer<T, N, L>
+where
+    T: PartialEq<U>,
+{
+    fn eq(&self, other: &[U]) -> bool {
+        self.as_ref() == other
+    }
+}
+
+impl<T, U, const N: usize, const M: usize, L: ArrayBufferLen> PartialEq<[U; M]>
+    for ArrayBuffer<T, N, L>
+where
+    T: PartialEq<U>,
+{
+    fn eq(&self, other: &[U; M]) -> bool {
+        self.as_ref() == other
+    }
+}
+
+impl<T: Eq, const N: usize, L: ArrayBufferLen> Eq for ArrayBuffer<T, N, L> {}
+
+impl<T: Hash, const N: usize, L: ArrayBufferLen> Hash for ArrayBuffer<T, N, L> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_ref().hash(state);
+    }
+}
+
+impl<T, const N: usize, L: ArrayBufferLen> Drop for ArrayBuffer<T, N, L> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<const N: usize, L: ArrayBufferLen> ArrayBuffer<u8, N, L> {
+    /// Reads bytes from the reader into the remaining capacity of the buffer
+    /// with a single [`read`](std::io::Read::read) call and returns the number of read bytes.
+    ///
+    /// It returns `Ok(0)` if the buffer is full or the reader is exhausted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reader returns a length larger than the provided buffer.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::ArrayBuffer;
+    ///
+    /// let mut buffer = ArrayBuffer::<u8, 4>::new();
+    /// let mut reader: &[u8] = b"hello";
+    ///
+    /// assert_eq!(buffer.read_from(&mut reader).unwrap(), 4);
+    /// assert_eq!(buffer.read_from(&mut reader).unwrap(), 0);
+    /// assert_eq!(buffer, *b"hell");
+    /// ```
+    #[cfg(not(feature = "no_std"))]
+    pub fn read_from<R: std::io::Read + ?Sized>(
+        &mut self,
+        reader: &mut R,
+    ) -> std::io::Result<usize> {
+        let len = self.len();
+        let spare = &mut self.array[len..];
+
+        if spare.is_empty() {
+            return Ok(0);
+        }
+
+        spare.fill(MaybeUninit::new(0));
+
+        let spare = unsafe { &mut *(ptr::from_mut(spare) as *mut [u8]) };
+        let read = reader.read(spare)?;
+
+        assert!(read <= spare.len(), "The reader returned an invalid length");
+
+        self.store_len(self.len() + read);
+
+        Ok(read)
+    }
+}
+
+impl<const N: usize, L: ArrayBufferLen> fmt::Write for ArrayBuffer<u8, N, L> {
+    /// Appends the string to the buffer or returns an error and doesn't change the buffer
+    /// if it does not have enough space.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    /// use orengine_utils::ArrayBuffer;
+    ///
+    /// let mut header = ArrayBuffer::<u8, 16>::new();
+    ///
+    /// write!(header, "LEN {}\r\n", 42).unwrap();
+    ///
+    /// assert_eq!(&*header, b"LEN 42\r\n");
+    /// assert!(write!(header, "{}", "too long string").is_err());
+    /// ```
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.extend_from_slice(s.as_bytes()).map_err(|_| fmt::Error)
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+impl<const N: usize, L: ArrayBufferLen> std::io::Write for ArrayBuffer<u8, N, L> {
+    /// Appends as many bytes as fit into the buffer and returns their number.
+    ///
+    /// Because of it, [`write_all`](std::io::Write::write_all) returns
+    /// an error of the [`WriteZero`](std::io::ErrorKind::WriteZero) kind
+    /// if the buffer does not have enough space.
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = buf.len().min(N - self.len());
+
+        // It can't fail, because `written` is not more than the remaining capacity.
+        let _ = self.extend_from_slice(&buf[..written]);
+
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize, const N: usize, L: ArrayBufferLen> serde::Serialize
+    for ArrayBuffer<T, N, L>
+{
+    /// Serializes the buffer as a sequence of its elements.
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.as_ref())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>, const N: usize, L: ArrayBufferLen> serde::Deserialize<'de>
+    for ArrayBuffer<T, N, L>
+{
+    /// Deserializes the buffer from a sequence.
+    ///
+    /// It returns an error if the sequence is longer than `N`.
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ArrayBufferVisitor<T, const N: usize, L>(core::marker::PhantomData<(T, L)>);
+
+        impl<'de, T: serde::Deserialize<'de>, const N: usize, L: ArrayBufferLen>
+            serde::de::Visitor<'de> for ArrayBufferVisitor<T, N, L>
+        {
+            type Value = ArrayBuffer<T, N, L>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(formatter, "a sequence of at most {N} elements")
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<Self::Value, A::Error> {
+                if let Some(len) = seq.size_hint() {
+                    if unlikely(len > N) {
+                        return Err(serde::de::Error::invalid_length(len, &self));
+                    }
+                }
+
+                let mut buffer = ArrayBuffer::new();
+
+                while let Some(item) = seq.next_element()? {
+                    if unlikely(buffer.push(item).is_err()) {
+                        return Err(serde::de::Error::invalid_length(N + 1, &self));
+                    }
+                }
+
+                Ok(buffer)
+            }
+        }
+
+        deserializer.deserialize_seq(ArrayBufferVisitor(core::marker::PhantomData))
+    }
+}
+
+/// A draining iterator over a range of an [`ArrayBuffer`].
+///
+/// It is created by [`ArrayBuffer::drain`].
+pub struct ArrayBufferDrain<'array_buffer, T, const N: usize, L: ArrayBufferLen = usize> {
+    buffer: &'array_buffer mut ArrayBuffer<T, N, L>,
+    /// The index of the next element to return from the front.
+    current: usize,
+    /// The index after the next element to return from the back.
+    end: usize,
+    /// The index of the first element after the drained range.
+    tail_start: usize,
+    /// The number of elements after the drained range.
+    tail_len: usize,
+}
+
+impl<T, const N: usize, L: ArrayBufferLen> Iterator for ArrayBufferDrain<'_, T, N, L> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if unlikely(self.current == self.end) {
+            return None;
+        }
+
+        self.current += 1;
+
+        Some(unsafe { self.buffer.array[self.current - 1].assume_init_read() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let size = self.end - self.current;
+
+        (size, Some(size))
+    }
+}
+
+impl<T, const N: usize, L: ArrayBufferLen> DoubleEndedIterator for ArrayBufferDrain<'_, T, N, L> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if unlikely(self.current == self.end) {
+            return None;
+        }
+
+        self.end -= 1;
+
+        Some(unsafe { self.buffer.array[self.end].assume_init_read() })
+    }
+}
+
+impl<T, const N: usize, L: ArrayBufferLen> ExactSizeIterator for ArrayBufferDrain<'_, T, N, L> {
+    fn len(&self) -> usize {
+        self.end - self.current
+    }
+}
+
+impl<T, const N: usize, L: ArrayBufferLen> core::iter::FusedIterator
+    for ArrayBufferDrain<'_, T, N, L>
+{
+}
+
+impl<T, const N: usize, L: ArrayBufferLen> Drop for ArrayBufferDrain<'_, T, N, L> {
+    fn drop(&mut self) {
+        /// Moves the tail down even if a destructor of a drained element panics.
+        struct MoveTail<'drain, T, const N: usize, L: ArrayBufferLen> {
+            buffer: &'drain mut ArrayBuffer<T, N, L>,
+            tail_start: usize,
+            tail_len: usize,
+        }
+
+        impl<T, const N: usize, L: ArrayBufferLen> Drop for MoveTail<'_, T, N, L> {
+            fn drop(&mut self) {
+                let new_len = self.buffer.len() + self.tail_len;
+
+                unsafe {
+                    let ptr = self.buffer.as_mut_ptr();
+
+                    ptr::copy(
+                        ptr.add(self.tail_start),
+                        ptr.add(self.buffer.len()),
+                        self.tail_len,
+                    );
+                }
+
+                self.buffer.store_len(new_len);
+            }
+        }
+
+        let rest = slice_from_raw_parts_mut(
+            unsafe { self.buffer.as_mut_ptr().add(self.current) },
+            self.end - self.current,
+        );
+
+        self.current = self.end;
+
+        let _move_tail = MoveTail {
+            buffer: self.buffer,
+            tail_start: self.tail_start,
+            tail_len: self.tail_len,
+        };
+
+        unsafe { ptr::drop_in_place(rest) };
+    }
+}
+
+/// An iterator adapter that groups items of the inner iterator into [`ArrayBuffer`]s
+/// of `N` items.
+///
+/// The last chunk can contain fewer items. It is created by [`BatchedExt::batched`].
+#[derive(Debug, Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Batched<I, const N: usize> {
+    iter: I,
+    is_finished: bool,
+}
+
+impl<I: Iterator, const N: usize> Iterator for Batched<I, N> {
+    type Item = ArrayBuffer<I::Item, N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if unlikely(self.is_finished) {
+            return None;
+        }
+
+        let mut chunk = ArrayBuffer::new();
+
+        while chunk.len() < N {
+            if let Some(item) = self.iter.next() {
+                unsafe { chunk.push_unchecked(item) };
+            } else {
+                self.is_finished = true;
+
+                break;
+            }
+        }
+
+        if chunk.is_empty() {
+            None
+        } else {
+            Some(chunk)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.is_finished {
+            return (0, Some(0));
+        }
+
+        let (lower, upper) = self.iter.size_hint();
+
+        (lower.div_ceil(N), upper.map(|upper| upper.div_ceil(N)))
+    }
+}
+
+impl<I: Iterator, const N: usize> core::iter::FusedIterator for Batched<I, N> {}
+
+/// An extension trait that provides the [`batched`](BatchedExt::batched) adapter
+/// for all iterators.
+pub trait BatchedExt: Iterator + Sized {
+    /// Groups items of the iterator into [`ArrayBuffer`]s of `N` items,
+    /// the last chunk can contain fewer items.
+    ///
+    /// `N` must be greater than zero, it is checked at compile time.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::BatchedExt;
+    ///
+    /// let chunks: Vec<Vec<u32>> = (0..5)
+    ///     .batched::<2>()
+    ///     .map(|chunk| chunk.to_vec())
+    ///     .collect();
+    ///
+    /// assert_eq!(chunks, [vec![0, 1], vec![2, 3], vec![4]]);
+    /// ```
+    fn batched<const N: usize>(self) -> Batched<Self, N> {
+        const {
+            assert!(N > 0, "the batch size must be greater than zero");
+        };
+
+        Batched {
+            iter: self,
+            is_finished: false,
+        }
+    }
+}
+
+impl<I: Iterator> BatchedExt for I {}
+
+#[cfg(test)]
+mod tests {
     use super::*;
     use alloc::vec;
     use alloc::vec::Vec;
@@ -425,4 +1738,366 @@ mod tests {
 
         assert_eq!(buffer.len(), 1);
     }
+
+    #[test]
+    fn test_array_buffer_map_in_place_and_try_transform() {
+        use alloc::rc::Rc;
+
+        let mut buffer = ArrayBuffer::<u32, 4>::from([1, 2, 3, 4]);
+
+        buffer.map_in_place(|x| x + 1);
+        assert_eq!(&*buffer, &[2, 3, 4, 5]);
+
+        let transformed = buffer.try_transform(i32::try_from).unwrap();
+        assert_eq!(&*transformed, &[2, 3, 4, 5]);
+
+        let counter = Rc::new(());
+        let buffer = ArrayBuffer::<Rc<()>, 4>::from([
+            counter.clone(),
+            counter.clone(),
+            counter.clone(),
+            counter.clone(),
+        ]);
+        let mut calls = 0;
+        let res = buffer.try_transform(|rc| {
+            calls += 1;
+
+            if calls == 3 {
+                return Err(rc);
+            }
+
+            Ok(Some(rc))
+        });
+
+        assert_eq!(Rc::strong_count(&res.err().unwrap()), 2);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn test_array_buffer_extend() {
+        let mut buffer = ArrayBuffer::<u32, 4>::new();
+
+        buffer.extend([1, 2]);
+        buffer.extend_from_slice(&[3]).unwrap();
+
+        assert_eq!(&*buffer, &[1, 2, 3]);
+        assert!(buffer.extend_from_slice(&[4, 5]).is_err());
+
+        buffer.extend(core::iter::once(4));
+
+        assert_eq!(&*buffer, &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_array_buffer_into_full_array_and_into_parts() {
+        use alloc::rc::Rc;
+
+        let counter = Rc::new(());
+        let mut buffer = ArrayBuffer::<Rc<()>, 2>::new();
+
+        buffer.push(counter.clone()).unwrap();
+
+        let mut buffer = buffer.into_full_array().err().unwrap();
+
+        assert_eq!(Rc::strong_count(&counter), 2);
+
+        buffer.push(counter.clone()).unwrap();
+
+        let array = buffer.into_full_array().ok().unwrap();
+
+        assert_eq!(Rc::strong_count(&counter), 3);
+
+        drop(array);
+
+        assert_eq!(Rc::strong_count(&counter), 1);
+
+        let (mut array, len) = ArrayBuffer::<u32, 4>::from([1, 2, 3, 4]).into_parts();
+
+        assert_eq!(len, 4);
+        assert_eq!(unsafe { array[3].assume_init_read() }, 4);
+
+        array[0] = MaybeUninit::new(5);
+
+        assert_eq!(unsafe { array[0].assume_init() }, 5);
+    }
+
+    #[test]
+    fn test_array_buffer_into_iter_and_from_iter() {
+        use alloc::rc::Rc;
+
+        let counter = Rc::new(());
+        let buffer: ArrayBuffer<Rc<()>, 4> = core::iter::repeat_n(counter.clone(), 10).collect();
+
+        assert_eq!(buffer.len(), 4);
+        assert_eq!(Rc::strong_count(&counter), 5);
+
+        let mut iter = buffer.into_iter();
+
+        assert_eq!(iter.len(), 4);
+        drop(iter.next().unwrap());
+        drop(iter.next_back().unwrap());
+        assert_eq!(iter.len(), 2);
+        assert_eq!(Rc::strong_count(&counter), 3);
+
+        drop(iter);
+
+        assert_eq!(Rc::strong_count(&counter), 1);
+
+        let buffer = ArrayBuffer::<u32, 4>::try_from(&[1, 2, 3][..]).unwrap();
+
+        assert_eq!((&buffer).into_iter().sum::<u32>(), 6);
+        assert_eq!(buffer.into_iter().rev().collect::<Vec<_>>(), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn test_array_buffer_clone_debug_eq_hash() {
+        use alloc::format;
+        use alloc::string::String;
+
+        /// A hasher that remembers all written bytes.
+        #[derive(Default)]
+        struct BytesHasher(Vec<u8>);
+
+        impl Hasher for BytesHasher {
+            fn finish(&self) -> u64 {
+                0
+            }
+
+            fn write(&mut self, bytes: &[u8]) {
+                self.0.extend_from_slice(bytes);
+            }
+        }
+
+        fn hashed_bytes(value: &(impl Hash + ?Sized)) -> Vec<u8> {
+            let mut hasher = BytesHasher::default();
+
+            value.hash(&mut hasher);
+
+            hasher.0
+        }
+
+        let buffer =
+            ArrayBuffer::<String, 4>::try_from(&[String::from("a"), String::from("b")][..])
+                .unwrap();
+        let mut other = ArrayBuffer::<String, 8>::new();
+
+        assert_ne!(buffer, other);
+
+        other.extend(buffer.clone());
+
+        assert_eq!(buffer, other);
+        assert_eq!(buffer, ["a", "b"]);
+        assert_eq!(format!("{buffer:?}"), r#"["a", "b"]"#);
+
+        assert_eq!(hashed_bytes(&buffer), hashed_bytes(&other));
+        assert_eq!(hashed_bytes(&buffer), hashed_bytes(&*buffer));
+    }
+
+    #[test]
+    fn test_array_buffer_drain() {
+        use alloc::rc::Rc;
+
+        let counter = Rc::new(());
+        let mut buffer: ArrayBuffer<_, 8> = (0..6).map(|i| (i, counter.clone())).collect();
+        let mut drain = buffer.drain(1..4);
+
+        assert_eq!(drain.len(), 3);
+        assert_eq!(drain.next().unwrap().0, 1);
+        assert_eq!(drain.next_back().unwrap().0, 3);
+
+        drop(drain);
+
+        assert_eq!(Rc::strong_count(&counter), 4);
+        assert_eq!(
+            buffer.iter().map(|(i, _)| *i).collect::<Vec<_>>(),
+            [0, 4, 5]
+        );
+
+        assert_eq!(buffer.drain(2..=2).count(), 1);
+        assert_eq!(buffer.drain(..0).count(), 0);
+
+        core::mem::forget(buffer.drain(1..));
+
+        assert_eq!(buffer.len(), 1);
+        assert_eq!(buffer.drain(..).count(), 1);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_array_buffer_retain() {
+        use alloc::rc::Rc;
+
+        let counter = Rc::new(());
+        let mut buffer: ArrayBuffer<_, 8> = (0..8).map(|i| (i, counter.clone())).collect();
+
+        buffer.retain(|(i, _)| *i % 3 == 0);
+
+        assert_eq!(Rc::strong_count(&counter), 4);
+        assert_eq!(
+            buffer.iter().map(|(i, _)| *i).collect::<Vec<_>>(),
+            [0, 3, 6]
+        );
+
+        buffer.retain(|_| false);
+
+        assert!(buffer.is_empty());
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn test_array_buffer_swap_remove() {
+        let mut buffer = ArrayBuffer::<u32, 4>::from([1, 2, 3, 4]);
+
+        assert_eq!(buffer.swap_remove(0), 1);
+        assert_eq!(buffer, [4, 2, 3]);
+        assert_eq!(buffer.swap_remove(2), 3);
+        assert_eq!(buffer, [4, 2]);
+        assert_eq!(buffer.swap_remove(1), 2);
+        assert_eq!(buffer.swap_remove(0), 4);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_array_buffer_sort_insert_and_dedup() {
+        use alloc::rc::Rc;
+
+        let mut buffer = ArrayBuffer::<u32, 4>::new();
+
+        for (item, idx) in [(5, 0), (1, 0), (3, 1), (5, 3)] {
+            assert_eq!(buffer.sort_insert_by_key(item, |x| *x), Ok(idx));
+        }
+
+        assert_eq!(buffer, [1, 3, 5, 5]);
+        assert_eq!(buffer.sort_insert_by_key(0, |x| *x), Err(0));
+
+        let counter = Rc::new(());
+        let mut buffer: ArrayBuffer<_, 8> = [1, 1, 2, 3, 3, 3, 1]
+            .into_iter()
+            .map(|i| (i, counter.clone()))
+            .collect();
+
+        buffer.dedup_by_key(|(i, _)| *i);
+
+        assert_eq!(
+            buffer.iter().map(|(i, _)| *i).collect::<Vec<_>>(),
+            [1, 2, 3, 1]
+        );
+        assert_eq!(Rc::strong_count(&counter), 5);
+    }
+
+    #[test]
+    fn test_array_buffer_resize_with_and_push_n() {
+        let mut buffer = ArrayBuffer::<u32, 4>::new();
+
+        buffer.resize_with(2, || 7);
+        assert_eq!(buffer, [7, 7]);
+
+        assert!(buffer.push_n(3, || 1).is_err());
+        buffer.push_n(2, || 1).unwrap();
+        assert_eq!(buffer, [7, 7, 1, 1]);
+
+        buffer.resize_with(1, || unreachable!());
+        assert_eq!(buffer, [7]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde", not(feature = "no_std")))]
+    fn test_array_buffer_serde() {
+        use crate::rw_serde::RWDeserializer;
+        use crate::rw_serde::RWSerializer;
+        use serde::{Deserialize, Serialize};
+        use std::io::Cursor;
+
+        let buffer = ArrayBuffer::<u32, 4>::from_iter([1, 2, 3]);
+        let mut ser = RWSerializer::new(Vec::new());
+
+        buffer.serialize(&mut ser).unwrap();
+
+        let buf = ser.into_inner();
+        let restored =
+            ArrayBuffer::<u32, 4>::deserialize(&mut RWDeserializer::new(Cursor::new(buf.clone())))
+                .unwrap();
+
+        assert_eq!(buffer, restored);
+        ArrayBuffer::<u32, 2>::deserialize(&mut RWDeserializer::new(Cursor::new(buf)))
+            .expect_err("the sequence is longer than the capacity");
+    }
+
+    #[test]
+    #[cfg(not(feature = "no_std"))]
+    fn test_array_buffer_io() {
+        use std::io::Write;
+
+        let mut buffer = ArrayBuffer::<u8, 8>::new();
+
+        buffer.write_all(b"abc").unwrap();
+
+        assert_eq!(buffer.write(b"defghi").unwrap(), 5);
+        assert_eq!(
+            buffer.write_all(b"j").unwrap_err().kind(),
+            std::io::ErrorKind::WriteZero
+        );
+        assert_eq!(buffer, *b"abcdefgh");
+
+        buffer.truncate(6);
+
+        let mut reader: &[u8] = b"xyz";
+
+        assert_eq!(buffer.read_from(&mut reader).unwrap(), 2);
+        assert_eq!(buffer, *b"abcdefxy");
+    }
+
+    #[test]
+    #[cfg(not(feature = "no_std"))]
+    #[should_panic(expected = "The reader returned an invalid length")]
+    fn test_array_buffer_read_from_invalid_reader() {
+        struct InvalidReader;
+
+        impl std::io::Read for InvalidReader {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                Ok(buf.len() + 1)
+            }
+        }
+
+        let mut buffer = ArrayBuffer::<u8, 8>::new();
+
+        buffer.push(1).unwrap();
+
+        let _ = buffer.read_from(&mut InvalidReader);
+    }
+
+    #[test]
+    fn test_array_buffer_with_small_len_type() {
+        let mut buffer = ArrayBuffer::<u8, 255, u8>::new();
+
+        assert_eq!(size_of_val(&buffer), 256);
+
+        buffer.push_n(255, || 1).unwrap();
+
+        assert_eq!(buffer.len(), 255);
+        assert_eq!(buffer.push(2), Err(2));
+
+        buffer.retain(|x| *x == 0);
+
+        assert!(buffer.is_empty());
+
+        let mut buffer = ArrayBuffer::<u32, 4, u16>::from_iter([1, 2, 3, 4]);
+
+        assert_eq!(buffer.drain(1..3).collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(buffer, ArrayBuffer::<u32, 2>::from([1, 4]));
+    }
+
+    #[test]
+    fn test_batched() {
+        let mut batched = (0..7u32).batched::<3>();
+
+        assert_eq!(batched.size_hint(), (3, Some(3)));
+        assert_eq!(&*batched.next().unwrap(), &[0, 1, 2]);
+        assert_eq!(&*batched.next().unwrap(), &[3, 4, 5]);
+        assert_eq!(&*batched.next().unwrap(), &[6]);
+        assert!(batched.next().is_none());
+        assert!(batched.next().is_none());
+
+        assert_eq!(core::iter::empty::<u32>().batched::<4>().count(), 0);
+    }
 }
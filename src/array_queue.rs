@@ -1,6 +1,5 @@
 //! This module contains the [`ArrayQueue`].
-use crate::hints::{assert_hint, likely, unlikely};
-use alloc::format;
+use crate::hints::{assert_hint, likely, select_unpredictable, unlikely};
 use core::error::Error;
 use core::fmt::{Display, Formatter};
 use core::mem::MaybeUninit;
@@ -29,7 +28,10 @@ pub struct ArrayQueue<T, const N: usize> {
     head: usize,
 }
 
-/// Error returned by [`ArrayQueue::extend_from_slice`] when the queue does not have enough space.
+/// Error returned by [`ArrayQueue::extend_from_slice`] and [`ArrayBuffer::extend_from_slice`]
+/// when the collection does not have enough space.
+///
+/// [`ArrayBuffer::extend_from_slice`]: crate::ArrayBuffer::extend_from_slice
 #[derive(Debug)]
 pub struct NotEnoughSpace;
 
@@ -79,11 +81,12 @@ impl<T, const N: usize> ArrayQueue<T, N> {
 
         debug_assert!(logical_index < N || (logical_index - N) < N);
 
-        if unlikely(logical_index >= N) {
-            logical_index - N
-        } else {
-            logical_index
-        }
+        // The wraparound depends on the data, so it is unpredictable.
+        select_unpredictable(
+            logical_index >= N,
+            logical_index.wrapping_sub(N),
+            logical_index,
+        )
     }
 
     /// Returns a pair of slices that represent the occupied region of the queue.
@@ -118,7 +121,7 @@ impl<T, const N: usize> ArrayQueue<T, N> {
         let phys_head = self.to_physical_idx_from_head(0);
         let phys_tail = self.to_physical_idx_from_head(self.len());
 
-        if phys_tail > phys_head {
+        if phys_head + self.len <= N {
             (
                 unsafe {
                     &*slice_from_raw_parts(self.array.as_ptr().add(phys_head).cast(), self.len)
@@ -167,7 +170,7 @@ impl<T, const N: usize> ArrayQueue<T, N> {
         let phys_head = self.to_physical_idx_from_head(0);
         let phys_tail = self.to_physical_idx_from_head(self.len());
 
-        if phys_tail > phys_head {
+        if phys_head + self.len <= N {
             (
                 unsafe {
                     &mut *slice::from_raw_parts_mut(
@@ -362,9 +365,11 @@ impl<T, const N: usize> ArrayQueue<T, N> {
             let idx = self.head;
             self.head = self.to_physical_idx_from_head(1);
 
-            assert_hint(
+            crate::assert_hint_fmt!(
                 self.array.len() > idx,
-                &format!("idx: {}, len: {}", idx, self.array.len()),
+                "idx: {}, len: {}",
+                idx,
+                self.array.len()
             );
 
             Some(unsafe { self.array.get_unchecked_mut(idx).assume_init_read() })
@@ -440,6 +445,52 @@ impl<T, const N: usize> ArrayQueue<T, N> {
         Ok(())
     }
 
+    /// Accepts a function that will be called with the slices of the queue to move.
+    ///
+    /// # Safety
+    ///
+    /// The function should copy all elements from the provided slices.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::ArrayQueue;
+    ///
+    /// let mut queue = ArrayQueue::<u32, 4>::from([1, 2, 3, 4]);
+    ///
+    /// queue.pop().unwrap();
+    /// queue.push(5).unwrap();
+    ///
+    /// let mut receiver = Vec::with_capacity(4);
+    ///
+    /// unsafe {
+    ///     let popped = queue.take_batch(|first_slice, second_slice| {
+    ///         receiver.extend_from_slice(first_slice);
+    ///         receiver.extend_from_slice(second_slice);
+    ///
+    ///         first_slice.len() + second_slice.len()
+    ///     }, 4);
+    ///
+    ///     assert_eq!(popped, 4);
+    /// }
+    ///
+    /// assert_eq!(receiver, [2, 3, 4, 5]);
+    /// assert!(queue.is_empty());
+    /// ```
+    pub unsafe fn take_batch<R, F: FnOnce(&[T], &[T]) -> R>(&mut self, f: F, limit: usize) -> R {
+        let limit = self.len().min(limit);
+        let (first, second) = self.as_slices();
+        let first_len = first.len().min(limit);
+        let first = unsafe { &*slice_from_raw_parts(first.as_ptr(), first_len) };
+        let second = unsafe { &*slice_from_raw_parts(second.as_ptr(), limit - first_len) };
+
+        let res = f(first, second);
+
+        unsafe { self.inc_head_by(limit) };
+
+        res
+    }
+
     /// Clears with calling the provided function on each element.
     pub fn clear_with<F>(&mut self, mut f: F)
     where
@@ -462,7 +513,7 @@ impl<T, const N: usize> ArrayQueue<T, N> {
             for i in 0..self.len {
                 let idx = self.to_physical_idx_from_head(i);
 
-                unsafe { ptr::drop_in_place(self.array.get_unchecked_mut(idx)) };
+                unsafe { self.array.get_unchecked_mut(idx).assume_init_drop() };
             }
         }
 
@@ -583,6 +634,8 @@ impl<T, const N: usize> Default for ArrayQueue<T, N> {
 
 impl<T, const N: usize> From<[T; N]> for ArrayQueue<T, N> {
     fn from(array: [T; N]) -> Self {
+        let array = mem::ManuallyDrop::new(array);
+
         Self {
             array: unsafe { (&raw const array).cast::<[MaybeUninit<T>; N]>().read() },
             len: N,
@@ -744,4 +797,64 @@ mod tests {
             assert_eq!(q.len(), 3, "len must remain unchanged");
         }
     }
+
+    #[test]
+    fn test_from_array_drops_once() {
+        use alloc::rc::Rc;
+
+        let counter = Rc::new(());
+
+        {
+            let mut queue = ArrayQueue::from([counter.clone(), counter.clone(), counter.clone()]);
+
+            assert_eq!(Rc::strong_count(&counter), 4);
+
+            drop(queue.pop());
+
+            assert_eq!(Rc::strong_count(&counter), 3);
+        }
+
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn test_as_slices_wrapped() {
+        let mut queue = ArrayQueue::<u32, 4>::new();
+
+        for i in 0..3 {
+            queue.push(i).unwrap();
+        }
+
+        while queue.pop().is_some() {}
+
+        let empty: (&[u32], &[u32]) = (&[], &[]);
+
+        assert_eq!(queue.as_slices(), empty);
+        assert_eq!(
+            queue.as_mut_slices().0.len() + queue.as_mut_slices().1.len(),
+            0
+        );
+
+        queue.push(0).unwrap();
+        queue.push(1).unwrap();
+
+        // The head is at the last slot, so the second element wraps around
+        let wrapped: (&[u32], &[u32]) = (&[0], &[1]);
+
+        assert_eq!(queue.as_slices(), wrapped);
+        assert_eq!(queue.as_mut_slices().1, [1]);
+
+        queue.push(2).unwrap();
+        queue.push(3).unwrap();
+
+        let full: (&[u32], &[u32]) = (&[0], &[1, 2, 3]);
+
+        assert_eq!(queue.as_slices(), full);
+
+        let mut queue = ArrayQueue::<u32, 4>::from([1, 2, 3, 4]);
+        let full: (&[u32], &[u32]) = (&[1, 2, 3, 4], &[]);
+
+        assert_eq!(queue.as_slices(), full);
+        assert_eq!(queue.as_mut_slices().0, [1, 2, 3, 4]);
+    }
 }
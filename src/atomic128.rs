@@ -0,0 +1,303 @@
+//! This module provides 128-bit atomic integers for x86-64.
+//!
+//! `core::sync::atomic::AtomicU128` is not stable yet, so [`AtomicU128`] and [`AtomicI128`]
+//! are implemented with the lock-free `cmpxchg16b` instruction.
+//! All operations are sequentially consistent regardless of the provided [`Ordering`].
+//!
+//! # Panics
+//!
+//! All operations panic if the CPU doesn't support `cmpxchg16b`
+//! (it is supported by all x86-64 CPUs since 2006).
+//!
+//! # Example
+//!
+//! ```rust
+//! use orengine_utils::atomic128::AtomicU128;
+//! use std::sync::atomic::Ordering;
+//!
+//! // A sequence number and a pointer in one atomic.
+//! let pair = AtomicU128::new(1 << 64);
+//!
+//! pair.fetch_add(1 << 64, Ordering::AcqRel);
+//!
+//! assert_eq!(pair.load(Ordering::Acquire) >> 64, 2);
+//! ```
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+/// Returns `true` if the CPU supports `cmpxchg16b`.
+///
+/// The result is cached after the first call.
+#[inline]
+fn has_cmpxchg16b() -> bool {
+    const UNKNOWN: u8 = 0;
+    const UNSUPPORTED: u8 = 1;
+    const SUPPORTED: u8 = 2;
+
+    static CMPXCHG16B: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+    if cfg!(target_feature = "cmpxchg16b") {
+        return true;
+    }
+
+    match CMPXCHG16B.load(Ordering::Relaxed) {
+        SUPPORTED => true,
+        UNSUPPORTED => false,
+        _ => {
+            #[allow(unused_unsafe, reason = "cpuid is safe since Rust 1.88")]
+            let is_supported = unsafe { core::arch::x86_64::__cpuid(1).ecx & (1 << 13) != 0 };
+
+            CMPXCHG16B.store(
+                if is_supported { SUPPORTED } else { UNSUPPORTED },
+                Ordering::Relaxed,
+            );
+
+            is_supported
+        }
+    }
+}
+
+/// Atomically compares the value at `dst` with `old` and replaces it with `new` if they are equal.
+///
+/// Returns the previous value in `Ok` if the value was replaced and in `Err` otherwise.
+///
+/// # Safety
+///
+/// `dst` must be valid for writes and aligned to 16 bytes.
+#[inline]
+unsafe fn compare_exchange(dst: *mut u128, old: u128, new: u128) -> Result<u128, u128> {
+    assert!(
+        has_cmpxchg16b(),
+        "128-bit atomics require the cmpxchg16b instruction"
+    );
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        reason = "cmpxchg16b accepts values as two halves"
+    )]
+    let (prev_lo, prev_hi, is_replaced): (u64, u64, u8) = {
+        let (prev_lo, prev_hi, is_replaced);
+
+        // `rbx` is reserved by LLVM, so it is swapped with a temporary register.
+        unsafe {
+            core::arch::asm!(
+                "xchg {rbx_tmp}, rbx",
+                "lock cmpxchg16b xmmword ptr [{dst}]",
+                "mov rbx, {rbx_tmp}",
+                "setz {is_replaced}",
+                dst = in(reg) dst,
+                rbx_tmp = inout(reg) new as u64 => _,
+                is_replaced = out(reg_byte) is_replaced,
+                in("rcx") (new >> 64) as u64,
+                inout("rax") old as u64 => prev_lo,
+                inout("rdx") (old >> 64) as u64 => prev_hi,
+                options(nostack),
+            );
+        }
+
+        (prev_lo, prev_hi, is_replaced)
+    };
+
+    let prev = u128::from(prev_lo) | (u128::from(prev_hi) << 64);
+
+    if is_replaced != 0 {
+        Ok(prev)
+    } else {
+        Err(prev)
+    }
+}
+
+/// Generates a 128-bit atomic integer type.
+macro_rules! atomic_128 {
+    ($name:ident, $number_type:ident) => {
+        #[doc = concat!(
+            "An integer type which can be safely shared between threads with the same API as `core::sync::atomic::Atomic",
+            stringify!($number_type), "`.\n\nRead the [`module-level documentation`](crate::atomic128) for more details."
+        )]
+        #[repr(C, align(16))]
+        pub struct $name {
+            value: UnsafeCell<$number_type>,
+        }
+
+        unsafe impl Sync for $name {}
+
+        #[allow(
+            clippy::cast_sign_loss,
+            clippy::cast_possible_wrap,
+            reason = "Values are transmuted between u128 and i128"
+        )]
+        impl $name {
+            /// Creates a new atomic integer.
+            #[inline]
+            pub const fn new(value: $number_type) -> Self {
+                Self {
+                    value: UnsafeCell::new(value),
+                }
+            }
+
+            /// Returns a mutable reference to the underlying integer.
+            #[inline]
+            pub fn get_mut(&mut self) -> &mut $number_type {
+                self.value.get_mut()
+            }
+
+            /// Consumes the atomic and returns the contained value.
+            #[inline]
+            pub const fn into_inner(self) -> $number_type {
+                self.value.into_inner()
+            }
+
+            /// Loads a value from the atomic integer.
+            #[inline]
+            pub fn load(&self, _order: Ordering) -> $number_type {
+                match unsafe { compare_exchange(self.value.get().cast(), 0, 0) } {
+                    Ok(prev) | Err(prev) => prev as $number_type,
+                }
+            }
+
+            /// Stores a value into the atomic integer.
+            #[inline]
+            pub fn store(&self, value: $number_type, order: Ordering) {
+                self.swap(value, order);
+            }
+
+            /// Stores a value into the atomic integer, returning the previous value.
+            #[inline]
+            pub fn swap(&self, value: $number_type, order: Ordering) -> $number_type {
+                self.fetch_update(order, order, |_| Some(value)).unwrap_or_else(|prev| prev)
+            }
+
+            /// Stores a value into the atomic integer if the current value is the same as
+            /// the `current` value.
+            ///
+            /// The return value is a result indicating whether the new value was written
+            /// and containing the previous value.
+            #[inline]
+            pub fn compare_exchange(
+                &self,
+                current: $number_type,
+                new: $number_type,
+                _success: Ordering,
+                _failure: Ordering,
+            ) -> Result<$number_type, $number_type> {
+                match unsafe {
+                    compare_exchange(self.value.get().cast(), current as u128, new as u128)
+                } {
+                    Ok(prev) => Ok(prev as $number_type),
+                    Err(prev) => Err(prev as $number_type),
+                }
+            }
+
+            /// The same as [`compare_exchange`](Self::compare_exchange),
+            /// it never fails spuriously.
+            #[inline]
+            pub fn compare_exchange_weak(
+                &self,
+                current: $number_type,
+                new: $number_type,
+                success: Ordering,
+                failure: Ordering,
+            ) -> Result<$number_type, $number_type> {
+                self.compare_exchange(current, new, success, failure)
+            }
+
+            /// Fetches the value, and applies a function to it that returns an optional new value.
+            ///
+            /// Returns a `Result` of `Ok(previous_value)` if the function returned `Some(_)`,
+            /// else `Err(previous_value)`.
+            #[inline]
+            pub fn fetch_update<F>(
+                &self,
+                set_order: Ordering,
+                fetch_order: Ordering,
+                mut f: F,
+            ) -> Result<$number_type, $number_type>
+            where
+                F: FnMut($number_type) -> Option<$number_type>,
+            {
+                let mut prev = self.load(fetch_order);
+
+                while let Some(next) = f(prev) {
+                    match self.compare_exchange(prev, next, set_order, fetch_order) {
+                        Ok(prev) => return Ok(prev),
+                        Err(actual) => prev = actual,
+                    }
+                }
+
+                Err(prev)
+            }
+
+            /// Adds to the current value (with wrapping), returning the previous value.
+            #[inline]
+            pub fn fetch_add(&self, value: $number_type, order: Ordering) -> $number_type {
+                self.fetch_update(order, order, |prev| Some(prev.wrapping_add(value)))
+                    .unwrap_or_else(|prev| prev)
+            }
+
+            /// Subtracts from the current value (with wrapping), returning the previous value.
+            #[inline]
+            pub fn fetch_sub(&self, value: $number_type, order: Ordering) -> $number_type {
+                self.fetch_update(order, order, |prev| Some(prev.wrapping_sub(value)))
+                    .unwrap_or_else(|prev| prev)
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new(0)
+            }
+        }
+
+        impl From<$number_type> for $name {
+            fn from(value: $number_type) -> Self {
+                Self::new(value)
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Debug::fmt(&self.load(Ordering::Relaxed), f)
+            }
+        }
+    };
+}
+
+atomic_128!(AtomicU128, u128);
+atomic_128!(AtomicI128, i128);
+
+#[cfg(all(test, not(feature = "no_std")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_atomic_u128_contended_fetch_add() {
+        let atomic = AtomicU128::new(u128::from(u64::MAX));
+
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..1000 {
+                        atomic.fetch_add(1 | (1 << 64), Ordering::AcqRel);
+                    }
+                });
+            }
+        });
+
+        // The low half overflows into the high half once.
+        assert_eq!(
+            atomic.load(Ordering::Acquire),
+            u128::from(u64::MAX) + 4000 + (4000 << 64)
+        );
+        assert_eq!(
+            atomic.compare_exchange(0, 1, Ordering::AcqRel, Ordering::Acquire),
+            Err(atomic.load(Ordering::Relaxed))
+        );
+
+        let atomic = AtomicI128::new(-1);
+
+        assert_eq!(atomic.swap(i128::MIN, Ordering::AcqRel), -1);
+        assert_eq!(atomic.fetch_sub(1, Ordering::AcqRel), i128::MIN);
+        assert_eq!(atomic.into_inner(), i128::MAX);
+    }
+}
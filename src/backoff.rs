@@ -2,11 +2,24 @@
 //! preemptive yield when it is necessary.
 //!
 //! It has the same API as `crossbeam::Backoff`.
+//!
+//! What [`Backoff::snooze`] does after the spin phase is defined by
+//! the [`BackoffStrategy`] (yielding the thread by default).
 use crate::hints::likely;
+#[cfg(not(feature = "no_std"))]
+use crate::OrengineInstant;
 use core::cell::Cell;
 use core::fmt;
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicPtr, AtomicU8, Ordering};
+use core::task::{Context, Poll};
+use core::{mem, ptr};
 
+/// The default number of steps that only spin.
 const SPIN_LIMIT: u32 = 6;
+/// The default number of steps after which the backoff is completed.
+const YIELD_LIMIT: u32 = SPIN_LIMIT;
 
 /// Performs exponential backoff in spin loops.
 ///
@@ -16,15 +29,125 @@ const SPIN_LIMIT: u32 = 6;
 /// scheduler, and tell when it is a good time to block the thread using a different synchronization
 /// mechanism. Each step of the back off procedure takes roughly twice as long as the previous
 /// step.
-pub struct Backoff {
+///
+/// By default, the first 6 steps spin and then the backoff is completed.
+/// Use [`with_limits`](Self::with_limits) to change it.
+///
+/// After the spin phase, [`snooze`](Self::snooze) waits using the strategy `S`
+/// (read [`BackoffStrategy`] for more details).
+pub struct Backoff<S: BackoffStrategy = SpinThenYield> {
     step: Cell<u32>,
+    max_step: Cell<u32>,
+    spin_limit: u32,
+    yield_limit: u32,
+    strategy: S,
+    #[cfg(feature = "metrics")]
+    stats: Cell<BackoffStats>,
 }
 
 impl Backoff {
     /// Creates a new `Backoff` instance.
     #[inline]
     pub fn new() -> Self {
-        Self { step: Cell::new(0) }
+        Self::with_limits(SPIN_LIMIT, YIELD_LIMIT)
+    }
+
+    /// Creates a new `Backoff` instance with the provided limits.
+    ///
+    /// - The first `spin_limit` steps only spin and the step `i` spins `2^i` times.
+    /// - The next steps up to `yield_limit` yield the thread (in [`snooze`](Self::snooze)).
+    /// - After `yield_limit` steps the backoff [`is completed`](Self::is_completed).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `spin_limit` is greater than `yield_limit` or greater than 31.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::backoff::Backoff;
+    ///
+    /// let backoff = Backoff::with_limits(2, 4);
+    ///
+    /// for _ in 0..4 {
+    ///     assert!(!backoff.is_completed());
+    ///
+    ///     backoff.snooze();
+    /// }
+    ///
+    /// assert!(backoff.is_completed());
+    /// ```
+    #[inline]
+    pub fn with_limits(spin_limit: u32, yield_limit: u32) -> Self {
+        Self::with_strategy_and_limits(SpinThenYield, spin_limit, yield_limit)
+    }
+}
+
+impl<S: BackoffStrategy> Backoff<S> {
+    /// Creates a new `Backoff` instance with the provided strategy and the default limits.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::backoff::{Backoff, SpinOnly};
+    ///
+    /// let backoff = Backoff::with_strategy(SpinOnly);
+    ///
+    /// while !backoff.is_completed() {
+    ///     backoff.snooze(); // Never yields the thread
+    /// }
+    /// ```
+    #[inline]
+    pub fn with_strategy(strategy: S) -> Self {
+        Self::with_strategy_and_limits(strategy, SPIN_LIMIT, YIELD_LIMIT)
+    }
+
+    /// Creates a new `Backoff` instance with the provided strategy and limits.
+    ///
+    /// Read [`with_limits`](Backoff::with_limits) for more details about the limits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `spin_limit` is greater than `yield_limit` or greater than 31.
+    #[inline]
+    pub fn with_strategy_and_limits(strategy: S, spin_limit: u32, yield_limit: u32) -> Self {
+        assert!(
+            spin_limit <= yield_limit,
+            "spin_limit ({spin_limit}) should be <= yield_limit ({yield_limit})"
+        );
+        assert!(
+            spin_limit < u32::BITS,
+            "spin_limit should be < {}",
+            u32::BITS
+        );
+
+        Self {
+            step: Cell::new(0),
+            max_step: Cell::new(u32::MAX),
+            spin_limit,
+            yield_limit,
+            strategy,
+            #[cfg(feature = "metrics")]
+            stats: Cell::new(BackoffStats::new()),
+        }
+    }
+
+    /// Returns the strategy of the backoff.
+    #[inline]
+    pub fn strategy(&self) -> &S {
+        &self.strategy
+    }
+
+    /// Returns the number of steps that only spin.
+    #[inline]
+    pub fn spin_limit(&self) -> u32 {
+        self.spin_limit
+    }
+
+    /// Returns the number of steps after which the backoff is completed.
+    #[inline]
+    pub fn yield_limit(&self) -> u32 {
+        self.yield_limit
     }
 
     /// Returns the current backoff step
@@ -40,6 +163,64 @@ impl Backoff {
         self.step.set(0);
     }
 
+    /// Sets the step of the backoff (capped by the [`max step`](Self::set_max_step)).
+    ///
+    /// It allows seeding the backoff with prior knowledge.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::backoff::Backoff;
+    ///
+    /// let backoff = Backoff::new();
+    ///
+    /// for _ in 0..4 {
+    ///     backoff.spin();
+    /// }
+    ///
+    /// // Partial progress is made, retain half of the step.
+    /// backoff.reset_to(backoff.step() / 2);
+    ///
+    /// assert_eq!(backoff.step(), 2);
+    /// ```
+    #[inline]
+    pub fn reset_to(&self, step: u32) {
+        self.step.set(step.min(self.max_step.get()));
+    }
+
+    /// Sets the maximum step of the backoff. It caps the maximum pause burst.
+    ///
+    /// If the maximum step is less than the [`yield limit`](Self::yield_limit),
+    /// the backoff never [`completes`](Self::is_completed).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::backoff::Backoff;
+    ///
+    /// let backoff = Backoff::new();
+    ///
+    /// backoff.set_max_step(3);
+    ///
+    /// for _ in 0..10 {
+    ///     backoff.spin(); // At most 2^3 iterations of `spin_loop`
+    /// }
+    ///
+    /// assert_eq!(backoff.step(), 3);
+    /// assert!(!backoff.is_completed());
+    /// ```
+    #[inline]
+    pub fn set_max_step(&self, max_step: u32) {
+        self.max_step.set(max_step);
+        self.step.set(self.step.get().min(max_step));
+    }
+
+    /// Returns the maximum step of the backoff set by [`set_max_step`](Self::set_max_step).
+    #[inline]
+    pub fn max_step(&self) -> u32 {
+        self.max_step.get()
+    }
+
     /// Backs off in a lock-free loop.
     ///
     /// This method should be used when we need to retry an operation because another thread made
@@ -48,11 +229,14 @@ impl Backoff {
     /// The processor may yield using the *YIELD* or *PAUSE* instruction.
     #[inline]
     pub fn spin(&self) {
-        for _ in 0..1 << self.step.get().min(SPIN_LIMIT) {
+        for _ in 0..1u32 << self.step.get().min(self.spin_limit) {
             core::hint::spin_loop();
         }
 
-        self.step.set(self.step.get() + 1);
+        #[cfg(feature = "metrics")]
+        self.update_stats(|stats| stats.spins += 1);
+
+        self.next_step();
     }
 
     /// It [`spins`](Self::spin) or calls the provided function if
@@ -65,58 +249,938 @@ impl Backoff {
     where
         F: FnOnce(),
     {
-        if likely(self.step.get() < SPIN_LIMIT) {
-            for _ in 0..1 << self.step.get().min(SPIN_LIMIT) {
+        if likely(self.step.get() < self.spin_limit) {
+            for _ in 0..1u32 << self.step.get() {
                 core::hint::spin_loop();
             }
+
+            #[cfg(feature = "metrics")]
+            self.update_stats(|stats| stats.spins += 1);
         } else {
             f();
+
+            #[cfg(feature = "metrics")]
+            self.update_stats(|stats| stats.yields += 1);
+        }
+
+        self.next_step();
+    }
+
+    /// Increments the step.
+    #[inline(always)]
+    fn next_step(&self) {
+        let prev_step = self.step.get();
+        let step = prev_step.saturating_add(1).min(self.max_step.get());
+
+        self.step.set(step);
+
+        #[cfg(feature = "metrics")]
+        if prev_step < self.yield_limit && step >= self.yield_limit {
+            self.update_stats(|stats| stats.completions += 1);
         }
 
-        self.step.set(self.step.get() + 1);
+        #[cfg(not(feature = "metrics"))]
+        let _ = prev_step;
+    }
+
+    /// Updates the statistics of the backoff.
+    #[cfg(feature = "metrics")]
+    #[inline(always)]
+    fn update_stats(&self, f: impl FnOnce(&mut BackoffStats)) {
+        let mut stats = self.stats.get();
+
+        f(&mut stats);
+
+        self.stats.set(stats);
+    }
+
+    /// Returns a snapshot of the statistics of the backoff since its creation.
+    ///
+    /// It is available only with the `metrics` feature.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::backoff::{Backoff, BackoffStats};
+    ///
+    /// let backoff = Backoff::with_limits(2, 3);
+    ///
+    /// for _ in 0..4 {
+    ///     backoff.snooze();
+    /// }
+    ///
+    /// let stats = backoff.stats();
+    ///
+    /// assert_eq!((stats.spins, stats.yields, stats.completions), (2, 2, 1));
+    /// ```
+    #[cfg(feature = "metrics")]
+    #[inline]
+    pub fn stats(&self) -> BackoffStats {
+        self.stats.get()
     }
 
     /// Backs off in a blocking loop.
     ///
     /// This method should be used when we need to wait for another thread to make progress.
     ///
-    /// The processor may yield using the *YIELD* or *PAUSE* instruction, and the current thread
-    /// may yield by giving up a timeslice to the OS scheduler.
+    /// The processor may yield using the *YIELD* or *PAUSE* instruction. After the spin phase,
+    /// it waits using the [`strategy`](BackoffStrategy) (by default, the current thread
+    /// yields by giving up a timeslice to the OS scheduler).
     ///
-    /// In `#[no_std]` environments, this method is equivalent to [`spin`].
+    /// In `#[no_std]` environments, the default strategy is equivalent to [`spin`]
+    /// unless a hook is registered by [`set_yield_hook`].
     ///
-    /// If possible, use [`is_completed`] to check when it is advised to stop using backoff and
+    /// It returns the [`BlockingHint`] for the next step. If it is [`BlockingHint::Block`]
+    /// (the same as [`is_completed`]), it is advised to stop using backoff and
     /// block the current thread using a different synchronization mechanism instead.
     ///
     /// [`spin`]: Backoff::spin
     /// [`is_completed`]: Backoff::is_completed
     #[inline]
-    pub fn snooze(&self) {
-        #[cfg(not(feature = "no_std"))]
-        self.spin_or(std::thread::yield_now);
+    pub fn snooze(&self) -> BlockingHint {
+        let step = self.step.get();
+
+        self.spin_or(|| self.strategy.wait(step, self.spin_limit));
+
+        self.blocking_hint()
+    }
+
+    /// Returns what the next [`snooze`](Self::snooze) does.
+    #[inline]
+    pub fn blocking_hint(&self) -> BlockingHint {
+        let step = self.step.get();
+
+        if step >= self.yield_limit {
+            BlockingHint::Block
+        } else if step >= self.spin_limit {
+            BlockingHint::Yield
+        } else {
+            BlockingHint::Spin
+        }
+    }
+
+    /// Waits until the provided condition is `true` using the three-phase ladder:
+    /// it spins for [`spin_limit`](Self::spin_limit) steps, then it waits using the strategy
+    /// (yields by default) up to [`yield_limit`](Self::yield_limit) steps, and then
+    /// it calls `block` (for example, to park the thread or to wait on a futex)
+    /// until the condition is `true`.
+    ///
+    /// The backoff is [`reset`](Self::reset) before the first check of the condition.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::backoff::Backoff;
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::time::Duration;
+    ///
+    /// let is_ready = AtomicBool::new(false);
+    ///
+    /// std::thread::scope(|scope| {
+    ///     let waiter = scope.spawn(|| {
+    ///         Backoff::new().spin_then(
+    ///             || is_ready.load(Ordering::Acquire),
+    ///             || std::thread::park_timeout(Duration::from_millis(1)),
+    ///         );
+    ///     });
+    ///
+    ///     is_ready.store(true, Ordering::Release);
+    ///     waiter.thread().unpark();
+    /// });
+    /// ```
+    #[inline]
+    pub fn spin_then<C, B>(&self, mut is_ready: C, mut block: B)
+    where
+        C: FnMut() -> bool,
+        B: FnMut(),
+    {
+        self.reset();
+
+        while !is_ready() {
+            if self.blocking_hint() == BlockingHint::Block {
+                block();
+            } else {
+                self.snooze();
+            }
+        }
+    }
+
+    /// Backs off in a blocking loop sleeping after the spin phase.
+    ///
+    /// After the spin phase, it sleeps for exponentially increasing durations:
+    /// 1µs, 2µs, 4µs and so on up to `max_sleep`. It is suitable for low-priority
+    /// background work, where even yielding loops burn a whole core.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::backoff::Backoff;
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::time::Duration;
+    ///
+    /// let has_garbage = AtomicBool::new(false);
+    /// let backoff = Backoff::new();
+    ///
+    /// std::thread::scope(|scope| {
+    ///     scope.spawn(|| has_garbage.store(true, Ordering::Release));
+    ///
+    ///     while !has_garbage.load(Ordering::Acquire) {
+    ///         backoff.snooze_or_sleep(Duration::from_millis(1));
+    ///     }
+    /// });
+    /// ```
+    #[cfg(not(feature = "no_std"))]
+    #[inline]
+    pub fn snooze_or_sleep(&self, max_sleep: core::time::Duration) {
+        let sleep_step = self.step.get().saturating_sub(self.spin_limit).min(31);
+
+        self.spin_or(|| {
+            std::thread::sleep(
+                core::time::Duration::from_micros(1)
+                    .saturating_mul(1 << sleep_step)
+                    .min(max_sleep),
+            );
+        });
+    }
+
+    /// [`Spins`](Self::spin) while the provided condition is `true`.
+    ///
+    /// The backoff is [`reset`](Self::reset) before the first check of the condition.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::backoff::Backoff;
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    ///
+    /// let is_locked = AtomicBool::new(true);
+    ///
+    /// std::thread::scope(|scope| {
+    ///     scope.spawn(|| is_locked.store(false, Ordering::Release));
+    ///
+    ///     Backoff::new().spin_while(|| is_locked.load(Ordering::Acquire));
+    /// });
+    /// ```
+    #[inline]
+    pub fn spin_while<F>(&self, mut cond: F)
+    where
+        F: FnMut() -> bool,
+    {
+        self.reset();
+
+        while cond() {
+            self.spin();
+        }
+    }
+
+    /// [`Snoozes`](Self::snooze) until the provided condition is `true`
+    /// or `max_steps` steps are done.
+    ///
+    /// Returns `true` if the condition became `true` and `false` if the steps ran out,
+    /// so the caller can switch to blocking.
+    ///
+    /// The backoff is [`reset`](Self::reset) before the first check of the condition.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::backoff::Backoff;
+    ///
+    /// let backoff = Backoff::new();
+    /// let mut attempts = 0;
+    ///
+    /// assert!(backoff.wait_until(
+    ///     || {
+    ///         attempts += 1;
+    ///
+    ///         attempts == 3
+    ///     },
+    ///     10
+    /// ));
+    /// assert!(!backoff.wait_until(|| false, 10));
+    /// assert_eq!(backoff.step(), 10);
+    /// ```
+    #[inline]
+    pub fn wait_until<F>(&self, mut cond: F, max_steps: u32) -> bool
+    where
+        F: FnMut() -> bool,
+    {
+        self.reset();
+
+        loop {
+            if cond() {
+                return true;
+            }
+
+            if self.step.get() >= max_steps {
+                return false;
+            }
+
+            self.snooze();
+        }
+    }
+
+    /// Spins for approximately the provided duration without reading the clock.
+    ///
+    /// The cost of [`spin_loop`](core::hint::spin_loop) is calibrated once per process
+    /// (see [`calibrate_spin`]). It doesn't change the [`step`](Self::step).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::backoff::Backoff;
+    /// use std::time::Duration;
+    ///
+    /// let backoff = Backoff::new();
+    ///
+    /// // Spin ~2µs and then park the thread.
+    /// backoff.spin_for(Duration::from_micros(2));
+    /// ```
+    #[cfg(not(feature = "no_std"))]
+    #[inline]
+    pub fn spin_for(&self, duration: core::time::Duration) {
+        let spins = duration.as_nanos() * 1000 / u128::from(picos_per_spin());
+
+        for _ in 0..spins {
+            core::hint::spin_loop();
+        }
+    }
+
+    /// [`Snoozes`](Self::snooze) until the provided duration elapses.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::backoff::Backoff;
+    /// use orengine_utils::OrengineInstant;
+    /// use std::time::Duration;
+    ///
+    /// let start = OrengineInstant::now();
+    ///
+    /// Backoff::new().snooze_for(Duration::from_micros(50));
+    ///
+    /// assert!(start.elapsed() >= Duration::from_micros(50));
+    /// ```
+    #[cfg(not(feature = "no_std"))]
+    #[inline]
+    pub fn snooze_for(&self, duration: core::time::Duration) {
+        let deadline = OrengineInstant::now() + duration;
+
+        while self.snooze_until(deadline) {}
+    }
+
+    /// [`Spins`](Self::spin) if the deadline has not passed yet.
+    ///
+    /// Returns `false` without spinning if the deadline has passed,
+    /// so the caller can switch to blocking.
+    #[cfg(not(feature = "no_std"))]
+    #[inline]
+    pub fn spin_with_deadline(&self, deadline: OrengineInstant) -> bool {
+        if OrengineInstant::now() >= deadline {
+            return false;
+        }
 
-        #[cfg(feature = "no_std")]
         self.spin();
+
+        true
+    }
+
+    /// [`Snoozes`](Self::snooze) if the deadline has not passed yet.
+    ///
+    /// Returns `false` without snoozing if the deadline has passed,
+    /// so the caller can switch to blocking.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::backoff::Backoff;
+    /// use orengine_utils::OrengineInstant;
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::time::Duration;
+    ///
+    /// let is_ready = AtomicBool::new(false);
+    /// let backoff = Backoff::new();
+    /// let deadline = OrengineInstant::now() + Duration::from_millis(1);
+    ///
+    /// while !is_ready.load(Ordering::Acquire) {
+    ///     if !backoff.snooze_until(deadline) {
+    ///         // Time to block the thread using a different synchronization mechanism.
+    ///         break;
+    ///     }
+    /// }
+    ///
+    /// assert!(OrengineInstant::now() >= deadline);
+    /// ```
+    #[cfg(not(feature = "no_std"))]
+    #[inline]
+    pub fn snooze_until(&self, deadline: OrengineInstant) -> bool {
+        if OrengineInstant::now() >= deadline {
+            return false;
+        }
+
+        self.snooze();
+
+        true
+    }
+
+    /// It [`spins`](Self::spin) or returns the future created by the provided `yield_now` hook
+    /// if it [`should be used`](Self::is_completed).
+    ///
+    /// It is an async version of [`snooze`](Self::snooze) that doesn't block an async worker
+    /// thread. Spinning is done immediately, so the returned future doesn't borrow the backoff.
+    ///
+    /// The hook is the `yield_now` function of the used executor or the executor-agnostic
+    /// [`yield_now`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::backoff::{self, Backoff};
+    /// use std::future::Future;
+    /// use std::pin::pin;
+    /// use std::task::{Context, Poll, Waker};
+    ///
+    /// let backoff = Backoff::new();
+    /// let mut cx = Context::from_waker(Waker::noop());
+    ///
+    /// while !backoff.is_completed() {
+    ///     // Early steps only spin.
+    ///     assert!(pin!(backoff.spin_or_yield_async(backoff::yield_now)).poll(&mut cx).is_ready());
+    /// }
+    ///
+    /// let mut yielding = pin!(backoff.spin_or_yield_async(backoff::yield_now));
+    ///
+    /// assert!(yielding.as_mut().poll(&mut cx).is_pending());
+    /// assert!(yielding.poll(&mut cx).is_ready());
+    /// ```
+    #[inline]
+    pub fn spin_or_yield_async<F, Fut>(&self, yield_now: F) -> impl Future<Output = ()>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        let mut yield_future = None;
+
+        self.spin_or(|| yield_future = Some(yield_now()));
+
+        async move {
+            if let Some(yield_future) = yield_future {
+                yield_future.await;
+            }
+        }
     }
 
     /// Returns `true` if exponential backoff has completed and blocking the thread is advised.
     #[inline]
     pub fn is_completed(&self) -> bool {
-        self.step.get() >= SPIN_LIMIT
+        self.step.get() >= self.yield_limit
     }
 }
 
-impl fmt::Debug for Backoff {
+impl<S: BackoffStrategy> fmt::Debug for Backoff<S> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Backoff")
             .field("step", &self.step)
+            .field("max_step", &self.max_step)
+            .field("spin_limit", &self.spin_limit)
+            .field("yield_limit", &self.yield_limit)
             .field("is_completed", &self.is_completed())
-            .finish()
+            .finish_non_exhaustive()
     }
 }
 
-impl Default for Backoff {
+impl<S: BackoffStrategy + Default> Default for Backoff<S> {
     fn default() -> Self {
-        Self::new()
+        Self::with_strategy(S::default())
+    }
+}
+
+/// A hint that tells what the next [`Backoff::snooze`] does.
+///
+/// It is returned by [`Backoff::snooze`] and [`Backoff::blocking_hint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockingHint {
+    /// The backoff is in the spin phase.
+    Spin,
+    /// The backoff is in the yield phase, it waits using the [`BackoffStrategy`].
+    Yield,
+    /// The backoff is completed, it is advised to block the thread
+    /// using a different synchronization mechanism.
+    Block,
+}
+
+/// Statistics of a [`Backoff`] returned by [`Backoff::stats`].
+///
+/// It is available only with the `metrics` feature.
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BackoffStats {
+    /// The number of steps that spun.
+    pub spins: u64,
+    /// The number of steps that waited using the [`BackoffStrategy`]
+    /// (or the function provided to [`Backoff::spin_or`]).
+    pub yields: u64,
+    /// The number of times the backoff [`completed`](Backoff::is_completed).
+    pub completions: u64,
+}
+
+#[cfg(feature = "metrics")]
+impl BackoffStats {
+    /// Creates new zeroed `BackoffStats`.
+    pub const fn new() -> Self {
+        Self {
+            spins: 0,
+            yields: 0,
+            completions: 0,
+        }
+    }
+}
+
+/// Picoseconds per one [`spin_loop`](core::hint::spin_loop) or `0` if it is not calibrated yet.
+#[cfg(not(feature = "no_std"))]
+static PICOS_PER_SPIN: core::sync::atomic::AtomicU64 = core::sync::atomic::AtomicU64::new(0);
+
+/// Returns the calibrated number of picoseconds per one [`spin_loop`](core::hint::spin_loop).
+#[cfg(not(feature = "no_std"))]
+#[inline]
+fn picos_per_spin() -> u64 {
+    let picos = PICOS_PER_SPIN.load(Ordering::Relaxed);
+
+    if likely(picos != 0) {
+        return picos;
+    }
+
+    calibrate_spin()
+}
+
+/// Measures the cost of [`spin_loop`](core::hint::spin_loop) that is used by
+/// [`Backoff::spin_for`] and returns it in picoseconds.
+///
+/// It is called lazily on the first [`Backoff::spin_for`] and can take up to a millisecond,
+/// call it at startup to not pay for it on the first wait.
+#[cfg(not(feature = "no_std"))]
+#[cold]
+#[inline(never)]
+pub fn calibrate_spin() -> u64 {
+    const SPINS: u32 = 10_000;
+
+    let start = std::time::Instant::now();
+
+    for _ in 0..SPINS {
+        core::hint::spin_loop();
+    }
+
+    let picos = u64::try_from(start.elapsed().as_nanos() * 1000 / u128::from(SPINS))
+        .unwrap_or(u64::MAX)
+        .max(1);
+
+    PICOS_PER_SPIN.store(picos, Ordering::Relaxed);
+
+    picos
+}
+
+/// Defines how [`Backoff::snooze`] waits after the spin phase.
+///
+/// Implement it to provide a custom waiting behavior.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::backoff::{Backoff, BackoffStrategy};
+/// use std::cell::Cell;
+///
+/// #[derive(Default)]
+/// struct CountWaits(Cell<usize>);
+///
+/// impl BackoffStrategy for CountWaits {
+///     fn wait(&self, _step: u32, _spin_limit: u32) {
+///         self.0.set(self.0.get() + 1);
+///     }
+/// }
+///
+/// let backoff = Backoff::with_strategy_and_limits(CountWaits::default(), 1, 3);
+///
+/// while !backoff.is_completed() {
+///     backoff.snooze();
+/// }
+///
+/// assert_eq!(backoff.strategy().0.get(), 2);
+/// ```
+pub trait BackoffStrategy {
+    /// Waits on the provided step of the backoff after the spin phase.
+    ///
+    /// `step` is not less than `spin_limit`, which is the number of steps of the spin phase.
+    fn wait(&self, step: u32, spin_limit: u32);
+}
+
+/// Spins with the maximum number of iterations of the spin phase.
+/// It never yields the thread.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SpinOnly;
+
+impl BackoffStrategy for SpinOnly {
+    #[inline]
+    fn wait(&self, _step: u32, spin_limit: u32) {
+        for _ in 0..1u32 << spin_limit {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+/// A process-wide backoff profile that is set by [`set_default_profile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum Profile {
+    /// With this profile, [`SpinThenYield`] yields the thread after the spin phase.
+    /// It is the default profile.
+    #[default]
+    SpinThenYield,
+    /// With this profile, [`SpinThenYield`] never yields the thread and only spins.
+    ///
+    /// It is useful for runtimes with pinned dedicated threads,
+    /// where yielding to the OS scheduler is pointless.
+    NoYield,
+}
+
+/// The profile set by [`set_default_profile`].
+static DEFAULT_PROFILE: AtomicU8 = AtomicU8::new(Profile::SpinThenYield as u8);
+
+/// Sets the process-wide backoff profile.
+///
+/// It changes the behavior of all backoffs with the default [`SpinThenYield`] strategy,
+/// so it allows disabling the OS-yield phase without changing every call site.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::backoff::{self, Backoff, Profile};
+///
+/// backoff::set_default_profile(Profile::NoYield);
+///
+/// assert_eq!(backoff::default_profile(), Profile::NoYield);
+///
+/// let backoff = Backoff::new();
+///
+/// while !backoff.is_completed() {
+///     backoff.snooze(); // Never yields the thread
+/// }
+///
+/// // Restore the default profile, it is shared by the whole process
+/// backoff::set_default_profile(Profile::SpinThenYield);
+/// ```
+pub fn set_default_profile(profile: Profile) {
+    DEFAULT_PROFILE.store(profile as u8, Ordering::Relaxed);
+}
+
+/// Returns the process-wide backoff profile set by [`set_default_profile`].
+#[inline]
+pub fn default_profile() -> Profile {
+    if DEFAULT_PROFILE.load(Ordering::Relaxed) == Profile::NoYield as u8 {
+        Profile::NoYield
+    } else {
+        Profile::SpinThenYield
+    }
+}
+
+/// The yield hook registered by [`set_yield_hook`] or null.
+static YIELD_HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Registers the function that is called by [`SpinThenYield`] to yield the current thread.
+///
+/// It allows yielding in `#[no_std]` environments (for example, to an RTOS scheduler)
+/// and overrides [`std::thread::yield_now`] otherwise.
+///
+/// The hook can be set only once, it returns `false` if the hook is already set.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::backoff::set_yield_hook;
+///
+/// fn yield_to_scheduler() {
+///     // For example, call the scheduler of an RTOS.
+/// }
+///
+/// assert!(set_yield_hook(yield_to_scheduler));
+/// assert!(!set_yield_hook(yield_to_scheduler));
+/// ```
+pub fn set_yield_hook(hook: fn()) -> bool {
+    YIELD_HOOK
+        .compare_exchange(
+            ptr::null_mut(),
+            hook as *mut (),
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        )
+        .is_ok()
+}
+
+/// Returns the yield hook registered by [`set_yield_hook`].
+#[inline]
+fn yield_hook() -> Option<fn()> {
+    let hook = YIELD_HOOK.load(Ordering::Acquire);
+
+    if hook.is_null() {
+        None
+    } else {
+        Some(unsafe { mem::transmute::<*mut (), fn()>(hook) })
+    }
+}
+
+/// Yields the current thread. It is the default strategy.
+///
+/// If the [`default profile`](default_profile) is [`Profile::NoYield`],
+/// it is equivalent to [`SpinOnly`].
+///
+/// It calls the hook registered by [`set_yield_hook`] if any, otherwise
+/// it calls [`std::thread::yield_now`].
+///
+/// In `#[no_std]` environments without the registered hook, it is equivalent to [`SpinOnly`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SpinThenYield;
+
+impl BackoffStrategy for SpinThenYield {
+    #[inline]
+    fn wait(&self, step: u32, spin_limit: u32) {
+        if default_profile() == Profile::NoYield {
+            SpinOnly.wait(step, spin_limit);
+
+            return;
+        }
+
+        if let Some(hook) = yield_hook() {
+            hook();
+
+            return;
+        }
+
+        #[cfg(not(feature = "no_std"))]
+        {
+            let _ = (step, spin_limit);
+
+            std::thread::yield_now();
+        }
+
+        #[cfg(feature = "no_std")]
+        SpinOnly.wait(step, spin_limit);
+    }
+}
+
+/// Waits using a hardware wait instruction that escalates with the step.
+///
+/// The first step after the spin phase waits for about as long as the last step
+/// of the spin phase spins, and each next step waits twice as long,
+/// up to 16 times as long.
+/// `wfe` can't be timed, so on aarch64 every step waits until the next event.
+///
+/// It reduces power consumption and interference with the SMT sibling
+/// compared to [`SpinOnly`]. It uses:
+///
+/// - `tpause` on x86 and x86-64 with the `waitpkg` extension (detected at runtime);
+/// - `wfe` on aarch64 Linux if the kernel event stream is enabled (detected at runtime),
+///   because the event stream wakes it up at least every 100µs.
+///
+/// On other platforms, it is equivalent to [`SpinOnly`].
+/// Use [`is_hardware_wait_supported`] to check what is used.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::backoff::{Backoff, SpinThenHardwareWait};
+///
+/// let backoff = Backoff::with_strategy_and_limits(SpinThenHardwareWait, 4, 8);
+///
+/// while !backoff.is_completed() {
+///     backoff.snooze();
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SpinThenHardwareWait;
+
+impl BackoffStrategy for SpinThenHardwareWait {
+    #[inline]
+    fn wait(&self, step: u32, spin_limit: u32) {
+        let escalation = step
+            .saturating_sub(spin_limit)
+            .min(MAX_HARDWARE_WAIT_ESCALATION);
+
+        if !hardware_wait(1u64 << (spin_limit + escalation)) {
+            SpinOnly.wait(step, spin_limit);
+        }
+    }
+}
+
+/// The maximum power of two by which [`SpinThenHardwareWait`] extends the wait
+/// of the last spin step.
+const MAX_HARDWARE_WAIT_ESCALATION: u32 = 4;
+
+/// Approximate number of cycles of one [`spin_loop`](core::hint::spin_loop) iteration.
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+const CYCLES_PER_SPIN: u64 = 40;
+
+/// Returns `true` if [`SpinThenHardwareWait`] uses a hardware wait instruction
+/// on the current CPU.
+pub fn is_hardware_wait_supported() -> bool {
+    #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+    {
+        has_waitpkg()
+    }
+
+    #[cfg(all(target_arch = "aarch64", target_os = "linux"))]
+    {
+        crate::hints::has_event_stream()
+    }
+
+    #[cfg(not(any(
+        target_arch = "x86_64",
+        target_arch = "x86",
+        all(target_arch = "aarch64", target_os = "linux")
+    )))]
+    {
+        false
+    }
+}
+
+/// Returns `true` if the CPU supports the `waitpkg` extension (`tpause` and `umwait`).
+///
+/// The result is cached after the first call.
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+fn has_waitpkg() -> bool {
+    const UNKNOWN: u8 = 0;
+    const UNSUPPORTED: u8 = 1;
+    const SUPPORTED: u8 = 2;
+
+    static WAITPKG: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+    match WAITPKG.load(Ordering::Relaxed) {
+        SUPPORTED => true,
+        UNSUPPORTED => false,
+        _ => {
+            #[cfg(target_arch = "x86")]
+            use core::arch::x86::{__cpuid, __cpuid_count};
+            #[cfg(target_arch = "x86_64")]
+            use core::arch::x86_64::{__cpuid, __cpuid_count};
+
+            #[allow(unused_unsafe, reason = "cpuid is safe since Rust 1.88")]
+            let is_supported =
+                unsafe { __cpuid(0).eax >= 7 && __cpuid_count(7, 0).ecx & (1 << 5) != 0 };
+
+            WAITPKG.store(
+                if is_supported { SUPPORTED } else { UNSUPPORTED },
+                Ordering::Relaxed,
+            );
+
+            is_supported
+        }
+    }
+}
+
+/// Waits using a hardware wait instruction for about `spins` spin iterations.
+///
+/// Returns `false` if no hardware wait instruction is available.
+#[inline]
+fn hardware_wait(spins: u64) -> bool {
+    #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+    {
+        if !has_waitpkg() {
+            return false;
+        }
+
+        #[cfg(target_arch = "x86_64")]
+        let now = unsafe { core::arch::x86_64::_rdtsc() };
+        #[cfg(target_arch = "x86")]
+        let now = unsafe { core::arch::x86::_rdtsc() };
+
+        let deadline = now.wrapping_add(spins * CYCLES_PER_SPIN);
+
+        #[allow(
+            clippy::cast_possible_truncation,
+            reason = "tpause accepts the deadline as two halves"
+        )]
+        unsafe {
+            // The control value 1 selects the C0.1 state that has a faster wakeup.
+            core::arch::asm!(
+                "tpause {control:e}",
+                control = in(reg) 1u32,
+                in("eax") deadline as u32,
+                in("edx") (deadline >> 32) as u32,
+                options(nomem, nostack),
+            );
+        }
+
+        true
+    }
+
+    #[cfg(all(target_arch = "aarch64", target_os = "linux"))]
+    {
+        let _ = spins;
+
+        if !crate::hints::has_event_stream() {
+            return false;
+        }
+
+        unsafe {
+            core::arch::asm!("wfe", options(nomem, nostack, preserves_flags));
+        }
+
+        true
     }
+
+    #[cfg(not(any(
+        target_arch = "x86_64",
+        target_arch = "x86",
+        all(target_arch = "aarch64", target_os = "linux")
+    )))]
+    {
+        let _ = spins;
+
+        false
+    }
+}
+
+/// Sleeps the current thread for the provided duration.
+///
+/// It is suitable for waiting for events that take a long time.
+#[cfg(not(feature = "no_std"))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SpinThenSleep(pub std::time::Duration);
+
+#[cfg(not(feature = "no_std"))]
+impl BackoffStrategy for SpinThenSleep {
+    #[inline]
+    fn wait(&self, _step: u32, _spin_limit: u32) {
+        std::thread::sleep(self.0);
+    }
+}
+
+/// A future that yields to the executor once. It is created by [`yield_now`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct YieldNow {
+    is_yielded: bool,
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.is_yielded {
+            return Poll::Ready(());
+        }
+
+        self.is_yielded = true;
+        cx.waker().wake_by_ref();
+
+        Poll::Pending
+    }
+}
+
+/// Returns a future that yields to the executor once.
+///
+/// It works with any executor: it wakes itself and returns [`Poll::Pending`] on the first poll,
+/// so the executor can run other tasks before polling it again.
+///
+/// It can be used as the hook for [`Backoff::spin_or_yield_async`].
+pub fn yield_now() -> YieldNow {
+    YieldNow { is_yielded: false }
 }
@@ -18,12 +18,17 @@
 //! assert_eq!(*padded_value, 42);
 //! ```
 // This code is forked from crossbeam: https://github.com/crossbeam-rs/crossbeam/blob/master/crossbeam-utils/src/cache_padded.rs
+use crate::backoff::Backoff;
+use core::cell::UnsafeCell;
 use core::fmt;
+use core::mem::{self, MaybeUninit};
 use core::ops::{Deref, DerefMut};
 use core::sync::atomic::{
-    AtomicBool, AtomicI16, AtomicI32, AtomicI64, AtomicI8, AtomicIsize, AtomicPtr, AtomicU16,
-    AtomicU32, AtomicU64, AtomicU8, AtomicUsize,
+    AtomicBool, AtomicI16, AtomicI32, AtomicI8, AtomicIsize, AtomicPtr, AtomicU16, AtomicU32,
+    AtomicU8, AtomicUsize, Ordering,
 };
+#[cfg(target_has_atomic = "64")]
+use core::sync::atomic::{AtomicI64, AtomicU64};
 
 /// Pads and aligns a value to the length of a cache line.
 ///
@@ -173,6 +178,108 @@ pub struct CachePadded<T> {
     value: T,
 }
 
+/// The alignment and the minimum size of [`CachePadded`] on the target architecture.
+///
+/// It can be bigger than [`CACHE_LINE_SIZE`], because some CPUs prefetch pairs of cache lines.
+pub const PADDING_SIZE: usize = align_of::<CachePadded<u8>>();
+
+/// The expected size of a cache line on the target architecture.
+///
+/// Use [`detect_cache_line_size`] to verify it at runtime.
+pub const CACHE_LINE_SIZE: usize = if cfg!(any(
+    target_arch = "powerpc64",
+    all(target_arch = "aarch64", target_vendor = "apple")
+)) {
+    128
+} else if cfg!(any(
+    target_arch = "arm",
+    target_arch = "mips",
+    target_arch = "mips32r6",
+    target_arch = "mips64",
+    target_arch = "mips64r6",
+    target_arch = "sparc",
+    target_arch = "hexagon",
+)) {
+    32
+} else if cfg!(target_arch = "m68k") {
+    16
+} else if cfg!(target_arch = "s390x") {
+    256
+} else {
+    64
+};
+
+const _: () = assert!(CACHE_LINE_SIZE <= PADDING_SIZE);
+
+/// Detects the size of a cache line of the current CPU at runtime.
+///
+/// It uses `CPUID` on x86 and x86-64, `sysfs` on Linux and `sysctl` on Apple platforms.
+/// Returns `None` if the size can't be detected.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::cache_padded::{detect_cache_line_size, CACHE_LINE_SIZE};
+///
+/// if let Some(size) = detect_cache_line_size() {
+///     if size != CACHE_LINE_SIZE {
+///         eprintln!("Expected {CACHE_LINE_SIZE}-byte cache lines, but detected {size}-byte ones");
+///     }
+/// }
+/// ```
+#[cold]
+pub fn detect_cache_line_size() -> Option<usize> {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        #[cfg(target_arch = "x86")]
+        use core::arch::x86::__cpuid;
+        #[cfg(target_arch = "x86_64")]
+        use core::arch::x86_64::__cpuid;
+
+        // CLFLUSH line size in 8-byte units.
+        #[allow(unused_unsafe, reason = "cpuid is safe since Rust 1.88")]
+        let clflush_size = (unsafe { __cpuid(1) }.ebx >> 8) & 0xff;
+
+        if clflush_size != 0 {
+            return Some(clflush_size as usize * 8);
+        }
+    }
+
+    #[cfg(all(target_os = "linux", not(feature = "no_std")))]
+    {
+        if let Some(size) =
+            std::fs::read_to_string("/sys/devices/system/cpu/cpu0/cache/index0/coherency_line_size")
+                .ok()
+                .and_then(|size| size.trim().parse().ok())
+                .filter(|&size| size != 0)
+        {
+            return Some(size);
+        }
+    }
+
+    #[cfg(target_vendor = "apple")]
+    {
+        let mut size = 0u64;
+        let mut len = size_of::<u64>();
+
+        let res = unsafe {
+            libc::sysctlbyname(
+                c"hw.cachelinesize".as_ptr(),
+                (&raw mut size).cast(),
+                &raw mut len,
+                core::ptr::null_mut(),
+                0,
+            )
+        };
+
+        if res == 0 && size != 0 {
+            return usize::try_from(size).ok();
+        }
+    }
+
+    None
+}
+
 unsafe impl<T: Send> Send for CachePadded<T> {}
 unsafe impl<T: Sync> Sync for CachePadded<T> {}
 
@@ -241,8 +348,211 @@ impl<T: fmt::Display> fmt::Display for CachePadded<T> {
     }
 }
 
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for CachePadded<T> {
+    /// Serializes the inner value transparently.
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for CachePadded<T> {
+    /// Deserializes the inner value and pads it.
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self::new)
+    }
+}
+
+// Zero padding bytes are valid.
+#[cfg(feature = "bytemuck")]
+unsafe impl<T: bytemuck::Zeroable> bytemuck::Zeroable for CachePadded<T> {}
+
+// `CachePadded` can't implement `bytemuck::Pod`, because it can have padding bytes,
+// but any bit pattern of the padding is valid.
+#[cfg(feature = "bytemuck")]
+unsafe impl<T: bytemuck::AnyBitPattern> bytemuck::AnyBitPattern for CachePadded<T> {}
+
+/// A marker of an alignment for [`Padded`].
+///
+/// It implements [`SupportedAlign`] for powers of two from 1 to 4096.
+#[derive(Clone, Copy, Debug)]
+pub struct Align<const ALIGN: usize>;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// An alignment that can be used with [`Padded`].
+///
+/// This trait is sealed and implemented for [`Align`] with powers of two from 1 to 4096.
+pub trait SupportedAlign: sealed::Sealed {
+    /// A zero-sized type with the alignment.
+    type Marker: Clone + Copy + Default + fmt::Debug + core::hash::Hash + PartialEq + Eq;
+}
+
+macro_rules! impl_supported_align {
+    ($($align:literal => $marker:ident),* $(,)?) => {
+        $(
+            #[doc(hidden)]
+            #[derive(Clone, Copy, Default, Debug, Hash, PartialEq, Eq)]
+            #[repr(align($align))]
+            pub struct $marker;
+
+            impl sealed::Sealed for Align<$align> {}
+
+            impl SupportedAlign for Align<$align> {
+                type Marker = $marker;
+            }
+        )*
+    };
+}
+
+impl_supported_align!(
+    1 => Align1, 2 => Align2, 4 => Align4, 8 => Align8, 16 => Align16, 32 => Align32,
+    64 => Align64, 128 => Align128, 256 => Align256, 512 => Align512, 1024 => Align1024,
+    2048 => Align2048, 4096 => Align4096,
+);
+
+/// Pads and aligns a value to `ALIGN` bytes.
+///
+/// It is the same as [`CachePadded`], but the alignment is chosen by the user
+/// instead of the target architecture. It is useful when the default alignment
+/// wastes too much memory in large arrays or when the deployment target is known.
+///
+/// `ALIGN` must be a power of two from 1 to 4096.
+///
+/// # Example
+///
+/// ```
+/// use orengine_utils::cache_padded::Padded;
+///
+/// let array = [Padded::<u8, 64>::new(1), Padded::new(2)];
+///
+/// assert_eq!(size_of_val(&array), 128);
+/// assert_eq!(*array[1], 2);
+/// ```
+#[derive(Clone, Copy, Default, Hash, PartialEq, Eq)]
+pub struct Padded<T, const ALIGN: usize>
+where
+    Align<ALIGN>: SupportedAlign,
+{
+    _align: [<Align<ALIGN> as SupportedAlign>::Marker; 0],
+    value: T,
+}
+
+impl<T, const ALIGN: usize> Padded<T, ALIGN>
+where
+    Align<ALIGN>: SupportedAlign,
+{
+    /// Pads and aligns a value to `ALIGN` bytes.
+    pub const fn new(t: T) -> Self {
+        Self {
+            _align: [],
+            value: t,
+        }
+    }
+
+    /// Returns the inner value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, const ALIGN: usize> Deref for Padded<T, ALIGN>
+where
+    Align<ALIGN>: SupportedAlign,
+{
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T, const ALIGN: usize> DerefMut for Padded<T, ALIGN>
+where
+    Align<ALIGN>: SupportedAlign,
+{
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T: fmt::Debug, const ALIGN: usize> fmt::Debug for Padded<T, ALIGN>
+where
+    Align<ALIGN>: SupportedAlign,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Padded")
+            .field("value", &self.value)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, const ALIGN: usize> From<T> for Padded<T, ALIGN>
+where
+    Align<ALIGN>: SupportedAlign,
+{
+    fn from(t: T) -> Self {
+        Self::new(t)
+    }
+}
+
+impl<T: fmt::Display, const ALIGN: usize> fmt::Display for Padded<T, ALIGN>
+where
+    Align<ALIGN>: SupportedAlign,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.value, f)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize, const ALIGN: usize> serde::Serialize for Padded<T, ALIGN>
+where
+    Align<ALIGN>: SupportedAlign,
+{
+    /// Serializes the inner value transparently.
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>, const ALIGN: usize> serde::Deserialize<'de>
+    for Padded<T, ALIGN>
+where
+    Align<ALIGN>: SupportedAlign,
+{
+    /// Deserializes the inner value and pads it.
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self::new)
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl<T: bytemuck::Zeroable, const ALIGN: usize> bytemuck::Zeroable for Padded<T, ALIGN> where
+    Align<ALIGN>: SupportedAlign
+{
+}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl<T: bytemuck::AnyBitPattern, const ALIGN: usize> bytemuck::AnyBitPattern
+    for Padded<T, ALIGN>
+where
+    Align<ALIGN>: SupportedAlign,
+{
+}
+
 macro_rules! cache_padded_atomic_number {
     ($name:ident, $atomic_type:ident, $number_type:ident) => {
+        cache_padded_atomic_number!(
+            @impl [] $name, $atomic_type, $number_type, $number_type::default()
+        );
+    };
+
+    (@impl [$($generic:ident)?] $name:ident, $atomic_type:ty, $number_type:ty, $default:expr) => {
         #[allow(
             rustdoc::redundant_explicit_links,
             reason = "It is needed for right IDE doc formating"
@@ -250,19 +560,31 @@ macro_rules! cache_padded_atomic_number {
         #[doc = concat!(
             "Alias to [`CachePadded`](CachePadded)`<`[`", stringify!($atomic_type), "`]`>`."
         )]
-        pub struct $name(CachePadded<$atomic_type>);
+        pub struct $name$(<$generic>)?(CachePadded<$atomic_type>);
 
-        impl $name {
+        impl$(<$generic>)? $name$(<$generic>)? {
              #[doc = concat!(
                  "Creates a new [`CachePadded`](CachePadded)`<`[`", stringify!($atomic_type), "`]`>`."
              )]
             #[inline(always)]
             pub const fn new(t: $number_type) -> Self {
-                Self($crate::cache_padded::CachePadded::new($atomic_type::new(t)))
+                Self($crate::cache_padded::CachePadded::new(<$atomic_type>::new(t)))
+            }
+
+            #[doc = concat!("Returns a reference to the inner [`", stringify!($atomic_type), "`].")]
+            #[inline(always)]
+            pub const fn as_atomic(&self) -> &$atomic_type {
+                &self.0.value
+            }
+
+            /// Returns the contained value.
+            #[inline(always)]
+            pub const fn into_inner(self) -> $number_type {
+                self.0.value.into_inner()
             }
         }
 
-        impl core::ops::Deref for $name {
+        impl$(<$generic>)? core::ops::Deref for $name$(<$generic>)? {
             type Target = $atomic_type;
 
             fn deref(&self) -> &$atomic_type {
@@ -270,67 +592,908 @@ macro_rules! cache_padded_atomic_number {
             }
         }
 
-        impl core::ops::DerefMut for $name {
+        impl$(<$generic>)? core::ops::DerefMut for $name$(<$generic>)? {
             fn deref_mut(&mut self) -> &mut $atomic_type {
                 &mut self.0
             }
         }
 
-        impl Default for $name {
+        impl$(<$generic>)? Default for $name$(<$generic>)? {
             fn default() -> Self {
-                Self::new($number_type::default())
+                Self::new($default)
+            }
+        }
+
+        impl$(<$generic>)? From<$number_type> for $name$(<$generic>)? {
+            fn from(t: $number_type) -> Self {
+                Self::new(t)
             }
         }
+
+        impl$(<$generic>)? fmt::Debug for $name$(<$generic>)? {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Debug::fmt(self.as_atomic(), f)
+            }
+        }
+
+        // An atomic with all zero bytes is a valid zero.
+        #[cfg(feature = "bytemuck")]
+        unsafe impl$(<$generic>)? bytemuck::Zeroable for $name$(<$generic>)? {}
     };
 }
 
 cache_padded_atomic_number!(CachePaddedAtomicU8, AtomicU8, u8);
 cache_padded_atomic_number!(CachePaddedAtomicU16, AtomicU16, u16);
 cache_padded_atomic_number!(CachePaddedAtomicU32, AtomicU32, u32);
+#[cfg(target_has_atomic = "64")]
 cache_padded_atomic_number!(CachePaddedAtomicU64, AtomicU64, u64);
 cache_padded_atomic_number!(CachePaddedAtomicUsize, AtomicUsize, usize);
 
 cache_padded_atomic_number!(CachePaddedAtomicI8, AtomicI8, i8);
 cache_padded_atomic_number!(CachePaddedAtomicI16, AtomicI16, i16);
 cache_padded_atomic_number!(CachePaddedAtomicI32, AtomicI32, i32);
+#[cfg(target_has_atomic = "64")]
 cache_padded_atomic_number!(CachePaddedAtomicI64, AtomicI64, i64);
 cache_padded_atomic_number!(CachePaddedAtomicIsize, AtomicIsize, isize);
 
 cache_padded_atomic_number!(CachePaddedAtomicBool, AtomicBool, bool);
 
-#[allow(
-    rustdoc::redundant_explicit_links,
-    reason = "It is needed for right IDE doc formating"
-)]
-#[allow(
-    clippy::doc_markdown,
-    reason = "It is needed for right IDE doc formating"
-)]
-/// Alias to <code>[CachePadded](CachePadded)<[AtomicPtr](AtomicPtr)`<T>`></code>.
-pub struct CachePaddedAtomicPtr<T>(CachePadded<AtomicPtr<T>>);
+#[cfg(target_arch = "x86_64")]
+use crate::atomic128::{AtomicI128, AtomicU128};
+
+#[cfg(target_arch = "x86_64")]
+cache_padded_atomic_number!(CachePaddedAtomicU128, AtomicU128, u128);
+#[cfg(target_arch = "x86_64")]
+cache_padded_atomic_number!(CachePaddedAtomicI128, AtomicI128, i128);
+
+/// Generates a cache-padded atomic float that is stored as bits in an integer atomic.
+#[cfg(feature = "atomic_float")]
+macro_rules! cache_padded_atomic_float {
+    ($name:ident, $atomic_type:ident, $float_type:ident) => {
+        #[doc = concat!(
+            "A cache-padded atomic `", stringify!($float_type), "`.\n\n",
+            "It stores the bits of the float in [`", stringify!($atomic_type), "`] ",
+            "and implements arithmetic with compare-and-swap loops."
+        )]
+        pub struct $name(CachePadded<$atomic_type>);
+
+        impl $name {
+            #[doc = concat!("Creates a new cache-padded atomic `", stringify!($float_type), "`.")]
+            #[inline(always)]
+            pub const fn new(value: $float_type) -> Self {
+                Self(CachePadded::new($atomic_type::new(value.to_bits())))
+            }
+
+            /// Loads the value.
+            #[inline]
+            pub fn load(&self, order: Ordering) -> $float_type {
+                $float_type::from_bits(self.0.load(order))
+            }
+
+            /// Stores the value.
+            #[inline]
+            pub fn store(&self, value: $float_type, order: Ordering) {
+                self.0.store(value.to_bits(), order);
+            }
+
+            /// Stores the value, returning the previous one.
+            #[inline]
+            pub fn swap(&self, value: $float_type, order: Ordering) -> $float_type {
+                $float_type::from_bits(self.0.swap(value.to_bits(), order))
+            }
+
+            /// Stores `new` if the current value has the same bits as `current`.
+            ///
+            /// # Errors
+            ///
+            /// Returns the current value if it is not `current`.
+            #[inline]
+            pub fn compare_exchange(
+                &self,
+                current: $float_type,
+                new: $float_type,
+                success: Ordering,
+                failure: Ordering,
+            ) -> Result<$float_type, $float_type> {
+                self.0
+                    .compare_exchange(current.to_bits(), new.to_bits(), success, failure)
+                    .map($float_type::from_bits)
+                    .map_err($float_type::from_bits)
+            }
+
+            /// Fetches the value, and applies a function to it that returns an optional new value.
+            ///
+            /// # Errors
+            ///
+            /// Returns the current value if `f` returned `None`.
+            #[inline]
+            pub fn fetch_update(
+                &self,
+                set_order: Ordering,
+                fetch_order: Ordering,
+                mut f: impl FnMut($float_type) -> Option<$float_type>,
+            ) -> Result<$float_type, $float_type> {
+                self.0
+                    .fetch_update(set_order, fetch_order, |bits| {
+                        f($float_type::from_bits(bits)).map($float_type::to_bits)
+                    })
+                    .map($float_type::from_bits)
+                    .map_err($float_type::from_bits)
+            }
+
+            /// Adds to the current value, returning the previous value.
+            #[inline]
+            pub fn fetch_add(&self, value: $float_type, order: Ordering) -> $float_type {
+                self.fetch_update(order, Ordering::Relaxed, |prev| Some(prev + value))
+                    .unwrap_or_else(|prev| prev)
+            }
+
+            /// Subtracts from the current value, returning the previous value.
+            #[inline]
+            pub fn fetch_sub(&self, value: $float_type, order: Ordering) -> $float_type {
+                self.fetch_update(order, Ordering::Relaxed, |prev| Some(prev - value))
+                    .unwrap_or_else(|prev| prev)
+            }
+
+            /// Stores the maximum of the current value and `value`, returning the previous value.
+            ///
+            #[doc = concat!("It uses [`", stringify!($float_type), "::max`], so `NaN`s are ignored.")]
+            #[inline]
+            pub fn fetch_max(&self, value: $float_type, order: Ordering) -> $float_type {
+                self.fetch_update(order, Ordering::Relaxed, |prev| {
+                    let max = prev.max(value);
+
+                    (max.to_bits() != prev.to_bits()).then_some(max)
+                })
+                .unwrap_or_else(|prev| prev)
+            }
+
+            /// Stores the minimum of the current value and `value`, returning the previous value.
+            ///
+            #[doc = concat!("It uses [`", stringify!($float_type), "::min`], so `NaN`s are ignored.")]
+            #[inline]
+            pub fn fetch_min(&self, value: $float_type, order: Ordering) -> $float_type {
+                self.fetch_update(order, Ordering::Relaxed, |prev| {
+                    let min = prev.min(value);
+
+                    (min.to_bits() != prev.to_bits()).then_some(min)
+                })
+                .unwrap_or_else(|prev| prev)
+            }
+
+            /// Returns the inner value.
+            #[inline]
+            pub fn into_inner(self) -> $float_type {
+                $float_type::from_bits(self.0.into_inner().into_inner())
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new(0.0)
+            }
+        }
+
+        // All zero bits are `0.0`.
+        #[cfg(feature = "bytemuck")]
+        unsafe impl bytemuck::Zeroable for $name {}
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Debug::fmt(&self.load(Ordering::Relaxed), f)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "atomic_float")]
+cache_padded_atomic_float!(CachePaddedAtomicF32, AtomicU32, f32);
+#[cfg(all(feature = "atomic_float", target_has_atomic = "64"))]
+cache_padded_atomic_float!(CachePaddedAtomicF64, AtomicU64, f64);
+
+cache_padded_atomic_number!(
+    @impl [T] CachePaddedAtomicPtr, AtomicPtr<T>, *mut T, core::ptr::null_mut()
+);
+
+/// A cache-padded pair of `usize` values that are loaded and updated together.
+///
+/// It is a fallback for 64-bit counters on targets without 64-bit atomics
+/// (where [`CachePaddedAtomicU64`] is unavailable), but it works on all targets.
+///
+/// It is a sequence lock: readers never block writers, but retry if a write happened
+/// during the read, and writers exclude each other with a spin lock.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::cache_padded::CachePaddedAtomicUsizePair;
+///
+/// // A 64-bit counter as the high and the low halves on 32-bit targets.
+/// let counter = CachePaddedAtomicUsizePair::new(0, usize::MAX);
+///
+/// counter.fetch_update(|(high, low)| {
+///     let (low, is_overflowed) = low.overflowing_add(1);
+///
+///     Some((high + usize::from(is_overflowed), low))
+/// }).unwrap();
+///
+/// assert_eq!(counter.load(), (1, 0));
+/// ```
+#[cfg(target_has_atomic = "ptr")]
+pub struct CachePaddedAtomicUsizePair(CachePadded<(AtomicUsize, AtomicUsize, AtomicUsize)>);
+
+#[cfg(target_has_atomic = "ptr")]
+impl CachePaddedAtomicUsizePair {
+    /// Creates a new `CachePaddedAtomicUsizePair`.
+    #[inline(always)]
+    pub const fn new(first: usize, second: usize) -> Self {
+        Self(CachePadded::new((
+            AtomicUsize::new(0),
+            AtomicUsize::new(first),
+            AtomicUsize::new(second),
+        )))
+    }
+
+    /// Loads both values.
+    #[inline]
+    pub fn load(&self) -> (usize, usize) {
+        let (sequence, first, second) = &*self.0;
+        let backoff = Backoff::new();
+
+        loop {
+            let start = sequence.load(Ordering::Acquire);
+
+            if start & 1 == 0 {
+                let pair = (
+                    first.load(Ordering::Relaxed),
+                    second.load(Ordering::Relaxed),
+                );
+
+                core::sync::atomic::fence(Ordering::Acquire);
+
+                if sequence.load(Ordering::Relaxed) == start {
+                    return pair;
+                }
+            }
+
+            backoff.snooze();
+        }
+    }
+
+    /// Stores both values.
+    #[inline]
+    pub fn store(&self, first: usize, second: usize) {
+        let _ = self.write(|_| Some((first, second)));
+    }
 
-impl<T> CachePaddedAtomicPtr<T> {
+    /// Stores `new` if the current values are `current`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the current values if they are not `current`.
+    #[inline]
+    pub fn compare_exchange(
+        &self,
+        current: (usize, usize),
+        new: (usize, usize),
+    ) -> Result<(usize, usize), (usize, usize)> {
+        self.write(|prev| (prev == current).then_some(new))
+    }
+
+    /// Fetches the values, and applies a function to them that returns optional new values.
+    ///
+    /// # Errors
+    ///
+    /// Returns the current values if `f` returned `None`.
+    #[inline]
+    pub fn fetch_update(
+        &self,
+        mut f: impl FnMut((usize, usize)) -> Option<(usize, usize)>,
+    ) -> Result<(usize, usize), (usize, usize)> {
+        loop {
+            let prev = self.load();
+            let next = f(prev).ok_or(prev)?;
+
+            if let Ok(prev) = self.compare_exchange(prev, next) {
+                return Ok(prev);
+            }
+        }
+    }
+
+    /// Locks the pair and replaces the values with the result of `f` if it is `Some`.
+    fn write(
+        &self,
+        f: impl FnOnce((usize, usize)) -> Option<(usize, usize)>,
+    ) -> Result<(usize, usize), (usize, usize)> {
+        let (sequence, first, second) = &*self.0;
+        let backoff = Backoff::new();
+        let mut start = sequence.load(Ordering::Relaxed);
+
+        loop {
+            if start & 1 == 0 {
+                match sequence.compare_exchange_weak(
+                    start,
+                    start.wrapping_add(1),
+                    Ordering::Acquire,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => break,
+                    Err(actual) => start = actual,
+                }
+            } else {
+                backoff.snooze();
+
+                start = sequence.load(Ordering::Relaxed);
+            }
+        }
+
+        core::sync::atomic::fence(Ordering::Release);
+
+        let prev = (
+            first.load(Ordering::Relaxed),
+            second.load(Ordering::Relaxed),
+        );
+        let res = f(prev);
+
+        if let Some((new_first, new_second)) = res {
+            first.store(new_first, Ordering::Relaxed);
+            second.store(new_second, Ordering::Relaxed);
+        }
+
+        sequence.store(start.wrapping_add(2), Ordering::Release);
+
+        res.map_or(Err(prev), |_| Ok(prev))
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl Default for CachePaddedAtomicUsizePair {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl fmt::Debug for CachePaddedAtomicUsizePair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.load(), f)
+    }
+}
+
+/// Defines a struct which hot fields are individually cache-padded and cold fields are grouped
+/// together in the cache lines after them.
+///
+/// Hot fields are stored in [`CachePadded`] and are accessed with generated
+/// `field()` and `field_mut()` methods. Cold fields are stored as is and are accessed directly.
+/// The struct is always aligned to [`PADDING_SIZE`], so cold fields never share
+/// a cache line with hot fields or neighbouring values.
+///
+/// It also generates a `const fn new` that accepts all fields in the order of declaration.
+/// Attributes of the struct (such as `#[derive(Default)]`) are kept.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::define_padded_struct;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// define_padded_struct! {
+///     /// Indexes of a ring buffer.
+///     #[derive(Default)]
+///     pub struct Indexes {
+///         hot {
+///             /// Updated by the consumer.
+///             pub head: AtomicUsize,
+///             /// Updated by the producer.
+///             pub tail: AtomicUsize,
+///         }
+///         cold {
+///             pub capacity: usize,
+///         }
+///     }
+/// }
+///
+/// let indexes = Indexes::new(AtomicUsize::new(0), AtomicUsize::new(3), 16);
+///
+/// indexes.tail().fetch_add(1, Ordering::Relaxed);
+///
+/// assert_eq!(indexes.tail().load(Ordering::Relaxed), 4);
+/// assert_eq!(indexes.capacity, 16);
+/// assert_eq!(Indexes::default().capacity, 0);
+/// ```
+#[macro_export]
+macro_rules! define_padded_struct {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident {
+            $(hot {
+                $($(#[$hot_attr:meta])* $hot_vis:vis $hot:ident: $hot_ty:ty),* $(,)?
+            })?
+            $(cold {
+                $($(#[$cold_attr:meta])* $cold_vis:vis $cold:ident: $cold_ty:ty),* $(,)?
+            })?
+        }
+    ) => {
+        $(#[$attr])*
+        #[repr(C)]
+        $vis struct $name {
+            $($(
+                $(#[$hot_attr])*
+                $hot: $crate::cache_padded::CachePadded<$hot_ty>,
+            )*)?
+            $($(
+                $(#[$cold_attr])*
+                $cold_vis $cold: $cold_ty,
+            )*)?
+            _align: [$crate::cache_padded::CachePadded<()>; 0],
+        }
+
+        $crate::__private::paste! {
+            #[allow(
+                dead_code,
+                clippy::too_many_arguments,
+                reason = "Accessors and the constructor are generated"
+            )]
+            impl $name {
+                #[doc = concat!("Creates a new `", stringify!($name), "`.")]
+                #[inline]
+                $vis const fn new(
+                    $($($hot: $hot_ty,)*)?
+                    $($($cold: $cold_ty,)*)?
+                ) -> Self {
+                    Self {
+                        $($($hot: $crate::cache_padded::CachePadded::new($hot),)*)?
+                        $($($cold,)*)?
+                        _align: [],
+                    }
+                }
+
+                $($(
+                    $(#[$hot_attr])*
+                    #[inline(always)]
+                    $hot_vis fn $hot(&self) -> &$hot_ty {
+                        &self.$hot
+                    }
+
+                    $(#[$hot_attr])*
+                    #[inline(always)]
+                    $hot_vis fn [<$hot _mut>](&mut self) -> &mut $hot_ty {
+                        &mut self.$hot
+                    }
+                )*)?
+            }
+        }
+    };
+}
+
+/// A state of the [`RawOnce`] that hasn't been initialized yet.
+const UNINIT: u8 = 0;
+/// A state of the [`RawOnce`] that is being initialized right now.
+const RUNNING: u8 = 1;
+/// A state of the [`RawOnce`] that has been initialized.
+const DONE: u8 = 2;
+
+/// A once-initialized slot without padding.
+struct RawOnce<T> {
+    state: AtomicU8,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+impl<T> RawOnce<T> {
+    const fn new() -> Self {
+        Self {
+            state: AtomicU8::new(UNINIT),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    #[inline]
+    fn get(&self) -> Option<&T> {
+        if self.state.load(Ordering::Acquire) == DONE {
+            Some(unsafe { (*self.value.get()).assume_init_ref() })
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn get_or_init(&self, f: impl FnOnce() -> T) -> &T {
+        if let Some(value) = self.get() {
+            return value;
+        }
+
+        self.initialize(f)
+    }
+
+    #[cold]
+    fn initialize(&self, f: impl FnOnce() -> T) -> &T {
+        /// Makes the slot uninitialized again if the initializer panics.
+        struct ResetOnPanic<'state>(&'state AtomicU8);
+
+        impl Drop for ResetOnPanic<'_> {
+            fn drop(&mut self) {
+                self.0.store(UNINIT, Ordering::Release);
+            }
+        }
+
+        let backoff = Backoff::new();
+        let mut f = Some(f);
+
+        loop {
+            match self
+                .state
+                .compare_exchange(UNINIT, RUNNING, Ordering::Acquire, Ordering::Acquire)
+            {
+                Ok(_) => {
+                    let guard = ResetOnPanic(&self.state);
+                    let value = (f.take().unwrap())();
+
+                    unsafe { (*self.value.get()).write(value) };
+
+                    mem::forget(guard);
+
+                    self.state.store(DONE, Ordering::Release);
+
+                    return unsafe { (*self.value.get()).assume_init_ref() };
+                }
+                Err(DONE) => return unsafe { (*self.value.get()).assume_init_ref() },
+                Err(_) => {
+                    backoff.snooze();
+                }
+            }
+        }
+    }
+
+    fn into_inner(self) -> Option<T> {
+        let this = mem::ManuallyDrop::new(self);
+
+        if this.state.load(Ordering::Acquire) == DONE {
+            Some(unsafe { (*this.value.get()).assume_init_read() })
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> Drop for RawOnce<T> {
+    fn drop(&mut self) {
+        if *self.state.get_mut() == DONE {
+            unsafe { self.value.get_mut().assume_init_drop() };
+        }
+    }
+}
+
+/// A cache-padded slot that can be initialized only once, like `std::sync::OnceLock`.
+///
+/// The state and the value share the same padded block, so an array of
+/// `CachePaddedOnce` takes one padded block per element.
+/// It can be used in statics.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::cache_padded::CachePaddedOnce;
+///
+/// static SHARDS: [CachePaddedOnce<Vec<u32>>; 4] = [const { CachePaddedOnce::new() }; 4];
+///
+/// assert!(SHARDS[1].get().is_none());
+/// assert_eq!(SHARDS[1].get_or_init(|| vec![1, 2]), &[1, 2]);
+/// assert!(SHARDS[1].set(vec![3]).is_err());
+/// ```
+pub struct CachePaddedOnce<T>(CachePadded<RawOnce<T>>);
+
+impl<T> CachePaddedOnce<T> {
+    /// Creates a new uninitialized `CachePaddedOnce`.
+    pub const fn new() -> Self {
+        Self(CachePadded::new(RawOnce::new()))
+    }
+
+    /// Returns the value if it is initialized.
+    #[inline]
+    pub fn get(&self) -> Option<&T> {
+        self.0.get()
+    }
+
+    /// Returns the value, initializing it with `f` if it is not initialized yet.
+    ///
+    /// If several threads call it concurrently, only one of them calls `f`
+    /// and others wait for it. If `f` panics, the slot stays uninitialized.
+    #[inline]
+    pub fn get_or_init(&self, f: impl FnOnce() -> T) -> &T {
+        self.0.get_or_init(f)
+    }
+
+    /// Initializes the value if it is not initialized yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns the provided value back if the slot is already initialized.
     #[allow(
-        clippy::doc_markdown,
-        reason = "It is needed for right IDE doc formating"
+        clippy::missing_panics_doc,
+        reason = "The initializer is called at most once, so the value is always present"
     )]
-    /// Creates a new <code>[CachePadded](CachePadded)<[AtomicPtr](AtomicPtr)`<T>`></code>.
-    #[inline(always)]
-    pub const fn new(ptr: *mut T) -> Self {
-        Self(CachePadded::new(AtomicPtr::new(ptr)))
+    pub fn set(&self, value: T) -> Result<(), T> {
+        let mut value = Some(value);
+
+        self.get_or_init(|| value.take().unwrap());
+
+        value.map_or(Ok(()), Err)
+    }
+
+    /// Returns the value if it is initialized.
+    pub fn into_inner(self) -> Option<T> {
+        self.0.into_inner().into_inner()
     }
 }
 
-impl<T> Deref for CachePaddedAtomicPtr<T> {
-    type Target = AtomicPtr<T>;
+impl<T> Default for CachePaddedOnce<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+impl<T: fmt::Debug> fmt::Debug for CachePaddedOnce<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CachePaddedOnce").field(&self.get()).finish()
     }
 }
 
-impl<T> DerefMut for CachePaddedAtomicPtr<T> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+unsafe impl<T: Send> Send for CachePaddedOnce<T> {}
+unsafe impl<T: Send + Sync> Sync for CachePaddedOnce<T> {}
+
+/// A cache-padded value that is initialized on the first access, like `std::sync::LazyLock`.
+///
+/// The state, the initializer and the value share the same padded block.
+/// It can be used in statics.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::cache_padded::CachePaddedLazy;
+///
+/// static TABLE: CachePaddedLazy<Vec<u64>> = CachePaddedLazy::new(|| (0..8).map(|i| i * i).collect());
+///
+/// assert_eq!(TABLE[3], 9);
+/// ```
+pub struct CachePaddedLazy<T, F = fn() -> T>(CachePadded<(RawOnce<T>, UnsafeCell<Option<F>>)>);
+
+impl<T, F: FnOnce() -> T> CachePaddedLazy<T, F> {
+    /// Creates a new `CachePaddedLazy` that is initialized with `f` on the first access.
+    pub const fn new(f: F) -> Self {
+        Self(CachePadded::new((RawOnce::new(), UnsafeCell::new(Some(f)))))
+    }
+
+    /// Initializes the value if it is not initialized yet and returns it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the initializer panicked before.
+    #[inline]
+    pub fn force(this: &Self) -> &T {
+        let (once, init) = &*this.0;
+
+        once.get_or_init(|| {
+            // Only the initializing thread accesses the initializer.
+            let f = unsafe { (*init.get()).take() };
+
+            f.expect("CachePaddedLazy instance has previously been poisoned")()
+        })
+    }
+
+    /// Returns the value if it is initialized.
+    #[inline]
+    pub fn get(this: &Self) -> Option<&T> {
+        this.0 .0.get()
+    }
+}
+
+impl<T, F: FnOnce() -> T> Deref for CachePaddedLazy<T, F> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        Self::force(self)
+    }
+}
+
+impl<T: Default> Default for CachePaddedLazy<T> {
+    fn default() -> Self {
+        Self::new(T::default)
+    }
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for CachePaddedLazy<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CachePaddedLazy")
+            .field(&self.0 .0.get())
+            .finish()
+    }
+}
+
+unsafe impl<T: Send, F: Send> Send for CachePaddedLazy<T, F> {}
+unsafe impl<T: Send + Sync, F: Send> Sync for CachePaddedLazy<T, F> {}
+
+#[cfg(all(test, not(feature = "no_std")))]
+mod tests {
+    use super::*;
+    use core::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn test_cache_padded_once_is_initialized_once() {
+        let once = CachePaddedOnce::new();
+        let calls = AtomicUsize::new(0);
+
+        let res = std::panic::catch_unwind(core::panic::AssertUnwindSafe(|| {
+            once.get_or_init(|| panic!("initializer panicked"))
+        }));
+
+        res.expect_err("the initializer should panic");
+        assert!(once.get().is_none());
+
+        std::thread::scope(|scope| {
+            for i in 0..8 {
+                let (once, calls) = (&once, &calls);
+
+                scope.spawn(move || {
+                    once.get_or_init(|| {
+                        calls.fetch_add(1, Ordering::Relaxed);
+
+                        i
+                    });
+                });
+            }
+        });
+
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        assert!(once.into_inner().is_some());
+    }
+
+    #[test]
+    fn test_cache_padded_atomic_aliases() {
+        const COUNTER: u32 = CachePaddedAtomicU32::new(5).into_inner();
+
+        let counter = CachePaddedAtomicU32::from(COUNTER);
+
+        counter.fetch_add(1, Ordering::Relaxed);
+
+        assert_eq!(format!("{counter:?}"), "6");
+        assert_eq!(counter.as_atomic().load(Ordering::Relaxed), 6);
+
+        let mut value = 1;
+        let ptr = CachePaddedAtomicPtr::from(&raw mut value);
+
+        assert!(CachePaddedAtomicPtr::<i32>::default()
+            .into_inner()
+            .is_null());
+        assert_eq!(ptr.into_inner(), &raw mut value);
+    }
+
+    #[test]
+    fn test_define_padded_struct_layout() {
+        define_padded_struct! {
+            struct Cold {
+                cold {
+                    len: u8,
+                }
+            }
+        }
+
+        define_padded_struct! {
+            struct State {
+                hot {
+                    head: u8,
+                    tail: u8,
+                }
+                cold {
+                    len: u8,
+                    capacity: u8,
+                }
+            }
+        }
+
+        let mut state = State::new(1, 2, 3, 4);
+
+        *state.tail_mut() += 1;
+
+        let head = core::ptr::from_ref(state.head()) as usize;
+        let tail = core::ptr::from_ref(state.tail()) as usize;
+        let len = core::ptr::from_ref(&state.len) as usize;
+
+        assert_eq!(*state.tail(), 3);
+        assert_eq!(state.capacity, 4);
+        assert_eq!(tail - head, PADDING_SIZE);
+        assert_eq!(len - tail, PADDING_SIZE);
+        assert_eq!(size_of::<State>(), 3 * PADDING_SIZE);
+        assert_eq!(align_of::<Cold>(), PADDING_SIZE);
+        assert_eq!(Cold::new(1).len, 1);
+    }
+
+    #[test]
+    fn test_cache_padded_atomic_usize_pair_is_consistent() {
+        let pair = CachePaddedAtomicUsizePair::default();
+
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..1000 {
+                        pair.fetch_update(|(a, b)| Some((a + 1, b + 2))).unwrap();
+
+                        let (a, b) = pair.load();
+
+                        assert_eq!(a * 2, b);
+                    }
+                });
+            }
+        });
+
+        assert_eq!(pair.load(), (4000, 8000));
+        assert_eq!(pair.compare_exchange((0, 0), (1, 1)), Err((4000, 8000)));
+    }
+
+    #[cfg(feature = "atomic_float")]
+    #[test]
+    fn test_cache_padded_atomic_float() {
+        let sum = CachePaddedAtomicF64::new(0.0);
+        let max = CachePaddedAtomicF32::new(f32::MIN);
+
+        std::thread::scope(|scope| {
+            for i in 0..4u8 {
+                let (sum, max) = (&sum, &max);
+
+                scope.spawn(move || {
+                    for _ in 0..100 {
+                        sum.fetch_add(0.5, Ordering::Relaxed);
+                    }
+
+                    max.fetch_max(f32::from(i), Ordering::Relaxed);
+                });
+            }
+        });
+
+        assert!((sum.into_inner() - 200.0).abs() < f64::EPSILON);
+        assert!((max.load(Ordering::Relaxed) - 3.0).abs() < f32::EPSILON);
+        assert!((max.fetch_max(f32::NAN, Ordering::Relaxed) - 3.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_cache_padded_serde() {
+        use crate::rw_serde::{RWDeserializer, RWSerializer};
+        use serde::{Deserialize, Serialize};
+        use std::io::Cursor;
+
+        let mut padded_ser = RWSerializer::new(Vec::new());
+        let mut plain_ser = RWSerializer::new(Vec::new());
+
+        CachePadded::new(42u32).serialize(&mut padded_ser).unwrap();
+        42u32.serialize(&mut plain_ser).unwrap();
+
+        let buf = padded_ser.into_inner();
+
+        assert_eq!(buf, plain_ser.into_inner());
+
+        let restored =
+            Padded::<u32, 64>::deserialize(&mut RWDeserializer::new(Cursor::new(buf))).unwrap();
+
+        assert_eq!(*restored, 42);
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn test_cache_padded_bytemuck() {
+        let counters: [CachePaddedAtomicU32; 2] = bytemuck::Zeroable::zeroed();
+
+        assert_eq!(counters[1].load(Ordering::Relaxed), 0);
+
+        // The buffer is aligned to the padding, so the cast doesn't depend on the stack layout
+        let bytes = CachePadded::new([7u8; 3 * PADDING_SIZE]);
+        let padded: &[CachePadded<u8>] =
+            bytemuck::try_cast_slice(&bytes[..2 * PADDING_SIZE]).unwrap();
+
+        assert_eq!(*padded[1], 7);
+        assert_eq!(
+            bytemuck::try_cast_slice::<u8, CachePadded<u8>>(&bytes[1..=PADDING_SIZE]),
+            Err(bytemuck::PodCastError::TargetAlignmentGreaterAndInputNotAligned)
+        );
     }
 }
@@ -1,13 +1,497 @@
-//! This module contains the [`clear_with`] function that works as `drain(..)` but faster.
+//! This module contains the [`clear_with`] and [`take_all`] functions that work as `drain(..)`
+//! but faster and the [`retain_with`] function that works as [`Vec::retain`] but passes
+//! removed elements to a callback. It also contains the [`clear_map_with`]
+//! and [`clear_set_with`] functions that clear hash maps and sets, and the [`par_clear_with`]
+//! function that clears a [`Vec`] on multiple threads.
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+use core::{fmt, ptr, slice};
 
 /// This function is like [`drain`](Vec::drain) for the whole [`Vec`], but faster.
-pub fn clear_with<T, F>(vec: &mut alloc::vec::Vec<T>, mut f: F)
+pub fn clear_with<T, F>(vec: &mut Vec<T>, mut f: F)
 where
     F: FnMut(T),
 {
     for item in vec.iter_mut() {
-        unsafe { f(core::ptr::read(item)) };
+        unsafe { f(ptr::read(item)) };
     }
 
     unsafe { vec.set_len(0) };
 }
+
+/// Keeps the state of [`retain_with`] and restores the [`Vec`] when dropped,
+/// even if the predicate or the callback panics.
+///
+/// Elements `[0, write)` are retained and moved, `[run_start, read)` are retained
+/// but not moved yet, and `[read, len)` are not processed.
+struct RetainGuard<'vec, T> {
+    vec: &'vec mut Vec<T>,
+    len: usize,
+    read: usize,
+    write: usize,
+    run_start: usize,
+}
+
+impl<T> RetainGuard<'_, T> {
+    /// Moves the run of retained elements that are not moved yet to the end of moved ones.
+    fn move_run(&mut self) {
+        let run = self.read - self.run_start;
+
+        if self.write != self.run_start && run > 0 {
+            unsafe {
+                let ptr = self.vec.as_mut_ptr();
+
+                ptr::copy(ptr.add(self.run_start), ptr.add(self.write), run);
+            }
+        }
+
+        self.write += run;
+    }
+}
+
+impl<T> Drop for RetainGuard<'_, T> {
+    fn drop(&mut self) {
+        // The unprocessed elements are retained, so they join the current run
+        self.read = self.len;
+
+        self.move_run();
+
+        unsafe { self.vec.set_len(self.write) };
+    }
+}
+
+/// Retains only the elements for which the predicate returns `true`
+/// and passes removed elements to `on_removed` in order.
+///
+/// It works as [`Vec::retain_mut`], but moves each run of retained elements with a single
+/// [`ptr::copy`], so it is faster when many elements are removed.
+///
+/// If the predicate or the callback panics, the unprocessed elements are retained.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::retain_with;
+///
+/// let mut vec = vec![1, 2, 3, 4, 5, 6];
+/// let mut removed = Vec::new();
+///
+/// retain_with(&mut vec, |x| *x % 3 == 0, |x| removed.push(x));
+///
+/// assert_eq!(vec, [3, 6]);
+/// assert_eq!(removed, [1, 2, 4, 5]);
+/// ```
+pub fn retain_with<T, P, F>(vec: &mut Vec<T>, mut pred: P, mut on_removed: F)
+where
+    P: FnMut(&mut T) -> bool,
+    F: FnMut(T),
+{
+    let len = vec.len();
+
+    // Elements are invalid while they are moved, so they must not be visible if we panic
+    unsafe { vec.set_len(0) };
+
+    let mut guard = RetainGuard {
+        vec,
+        len,
+        read: 0,
+        write: 0,
+        run_start: 0,
+    };
+
+    while guard.read < guard.len {
+        let item = unsafe { guard.vec.as_mut_ptr().add(guard.read) };
+
+        if pred(unsafe { &mut *item }) {
+            guard.read += 1;
+
+            continue;
+        }
+
+        guard.move_run();
+        guard.read += 1;
+        guard.run_start = guard.read;
+
+        on_removed(unsafe { ptr::read(item) });
+    }
+}
+
+/// Takes all elements of the [`Vec`] lazily and returns them by the iterator.
+///
+/// It works as [`drain(..)`](Vec::drain), but sets the length of the [`Vec`] to zero up front,
+/// so it doesn't need to shift the tail. The remaining elements are dropped when the iterator
+/// is dropped, or can be returned to the [`Vec`] with [`TakeAll::keep_rest`].
+/// If the iterator is leaked, the remaining elements are leaked too.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::take_all;
+///
+/// let mut vec = vec![1, 2, 3, 4, 5];
+/// let mut batch = take_all(&mut vec);
+///
+/// assert_eq!(batch.next(), Some(1));
+/// assert_eq!(batch.next(), Some(2));
+///
+/// // Stop early and process the rest in the next batch
+/// batch.keep_rest();
+///
+/// assert_eq!(vec, [3, 4, 5]);
+/// assert_eq!(take_all(&mut vec).sum::<i32>(), 12);
+/// assert!(vec.is_empty());
+/// ```
+pub fn take_all<T>(vec: &mut Vec<T>) -> TakeAll<'_, T> {
+    let len = vec.len();
+
+    unsafe { vec.set_len(0) };
+
+    TakeAll { vec, read: 0, len }
+}
+
+/// The iterator returned by [`take_all`].
+pub struct TakeAll<'vec, T> {
+    vec: &'vec mut Vec<T>,
+    read: usize,
+    len: usize,
+}
+
+impl<T> TakeAll<'_, T> {
+    /// Returns the remaining elements as a slice.
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.vec.as_ptr().add(self.read), self.len - self.read) }
+    }
+
+    /// Returns the remaining elements to the [`Vec`] instead of dropping them.
+    pub fn keep_rest(self) {
+        let mut this = core::mem::ManuallyDrop::new(self);
+        let remaining = this.len - this.read;
+
+        unsafe {
+            let ptr = this.vec.as_mut_ptr();
+
+            ptr::copy(ptr.add(this.read), ptr, remaining);
+
+            this.vec.set_len(remaining);
+        }
+    }
+}
+
+impl<T> Iterator for TakeAll<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.read == self.len {
+            return None;
+        }
+
+        let item = unsafe { ptr::read(self.vec.as_ptr().add(self.read)) };
+
+        self.read += 1;
+
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.read;
+
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for TakeAll<'_, T> {}
+
+impl<T> FusedIterator for TakeAll<'_, T> {}
+
+impl<T: fmt::Debug> fmt::Debug for TakeAll<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TakeAll").field(&self.as_slice()).finish()
+    }
+}
+
+impl<T> Drop for TakeAll<'_, T> {
+    fn drop(&mut self) {
+        let remaining = ptr::slice_from_raw_parts_mut(
+            unsafe { self.vec.as_mut_ptr().add(self.read) },
+            self.len - self.read,
+        );
+
+        // If a destructor panics, the rest are still dropped by the slice drop glue
+        unsafe { ptr::drop_in_place(remaining) };
+    }
+}
+
+/// Clears the [`HashMap`](std::collections::HashMap) and passes its entries to the function.
+///
+/// It keeps the allocated buckets and the hasher, so the map can be refilled without
+/// reallocations, for example, a scratch map that returns its values to a pool
+/// at the end of every tick.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::clear_map_with;
+/// use std::collections::HashMap;
+///
+/// let mut map = HashMap::from([(1, vec![0u8; 16]), (2, vec![0u8; 32])]);
+/// let capacity = map.capacity();
+/// let mut pool = Vec::new();
+///
+/// clear_map_with(&mut map, |_, buffer| pool.push(buffer));
+///
+/// assert!(map.is_empty());
+/// assert_eq!(map.capacity(), capacity);
+/// assert_eq!(pool.len(), 2);
+/// ```
+#[cfg(not(feature = "no_std"))]
+pub fn clear_map_with<K, V, S, F>(map: &mut std::collections::HashMap<K, V, S>, mut f: F)
+where
+    F: FnMut(K, V),
+{
+    for (key, value) in map.drain() {
+        f(key, value);
+    }
+}
+
+/// Clears the [`HashSet`](std::collections::HashSet) and passes its elements to the function.
+///
+/// It keeps the allocated buckets and the hasher, so the set can be refilled without
+/// reallocations.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::clear_set_with;
+/// use std::collections::HashSet;
+///
+/// let mut set = HashSet::from([1, 2, 3]);
+/// let mut sum = 0;
+///
+/// clear_set_with(&mut set, |x| sum += x);
+///
+/// assert!(set.is_empty());
+/// assert_eq!(sum, 6);
+/// ```
+#[cfg(not(feature = "no_std"))]
+pub fn clear_set_with<T, S, F>(set: &mut std::collections::HashSet<T, S>, f: F)
+where
+    F: FnMut(T),
+{
+    set.drain().for_each(f);
+}
+
+/// Clears the [`Vec`] and passes its elements to the function on `threads` threads,
+/// including the current one.
+///
+/// The elements are split into contiguous chunks, one per thread, and each chunk
+/// is processed in order on scoped threads. It is useful to drop a huge number of
+/// elements with expensive destructors, for example, at shutdown. The capacity is kept.
+///
+/// If `threads` is less than 2 or the [`Vec`] is shorter than `threads`,
+/// it works as [`clear_with`] on the current thread.
+///
+/// # Panics
+///
+/// Panics if the function panics or a thread can't be spawned. In this case,
+/// the unprocessed elements are leaked.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::par_clear_with;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// let mut vec: Vec<_> = (0..100_000).map(Box::new).collect();
+/// let processed = AtomicUsize::new(0);
+///
+/// par_clear_with(&mut vec, |_| { processed.fetch_add(1, Ordering::Relaxed); }, 4);
+///
+/// assert!(vec.is_empty());
+/// assert_eq!(processed.into_inner(), 100_000);
+///
+/// // Only drop the elements
+/// let mut vec: Vec<_> = (0..100_000).map(Box::new).collect();
+///
+/// par_clear_with(&mut vec, drop, 4);
+///
+/// assert!(vec.is_empty());
+/// ```
+#[cfg(not(feature = "no_std"))]
+pub fn par_clear_with<T, F>(vec: &mut Vec<T>, f: F, threads: usize)
+where
+    T: Send,
+    F: Fn(T) + Sync,
+{
+    use core::mem::ManuallyDrop;
+
+    let len = vec.len();
+
+    if threads < 2 || len < threads {
+        clear_with(vec, f);
+
+        return;
+    }
+
+    // The elements are moved out, so they must not be visible if we panic
+    unsafe { vec.set_len(0) };
+
+    let items =
+        unsafe { slice::from_raw_parts_mut(vec.as_mut_ptr().cast::<ManuallyDrop<T>>(), len) };
+    let f = &f;
+    let clear_chunk = move |chunk: &mut [ManuallyDrop<T>]| {
+        for item in chunk {
+            f(unsafe { ManuallyDrop::take(item) });
+        }
+    };
+
+    std::thread::scope(|scope| {
+        let mut chunks = items.chunks_mut(len.div_ceil(threads));
+        let first = chunks.next();
+
+        for chunk in chunks {
+            scope.spawn(move || clear_chunk(chunk));
+        }
+
+        if let Some(first) = first {
+            clear_chunk(first);
+        }
+    });
+}
+
+#[cfg(all(test, not(miri)))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retain_with() {
+        let mut vec: Vec<_> = (0..100).collect();
+        let mut removed = Vec::new();
+
+        retain_with(&mut vec, |x| *x % 10 < 3, |x| removed.push(x));
+
+        assert_eq!(vec.len(), 30);
+        assert!(vec.iter().all(|x| x % 10 < 3));
+        assert_eq!(removed.len(), 70);
+        assert!(removed.windows(2).all(|pair| pair[0] < pair[1]));
+
+        retain_with(&mut vec, |_| true, |_| unreachable!());
+
+        assert_eq!(vec.len(), 30);
+
+        retain_with(&mut vec, |_| false, drop);
+
+        assert!(vec.is_empty());
+    }
+
+    #[test]
+    fn test_take_all() {
+        let mut vec: Vec<_> = (0..10).map(alloc::boxed::Box::new).collect();
+        let mut iter = take_all(&mut vec);
+
+        assert_eq!(iter.len(), 10);
+        assert_eq!(*iter.next().unwrap(), 0);
+        assert_eq!(iter.as_slice().len(), 9);
+
+        drop(iter);
+
+        assert!(vec.is_empty());
+
+        vec.extend((0..5).map(alloc::boxed::Box::new));
+
+        let mut iter = take_all(&mut vec);
+
+        iter.nth(1);
+        iter.keep_rest();
+
+        assert_eq!(vec.iter().map(|x| **x).collect::<Vec<_>>(), [2, 3, 4]);
+        assert_eq!(take_all(&mut vec).map(|x| *x).sum::<i32>(), 9);
+        assert!(vec.is_empty());
+        assert!(vec.capacity() >= 5);
+    }
+
+    #[test]
+    #[cfg(not(feature = "no_std"))]
+    fn test_clear_map_with() {
+        use std::collections::{HashMap, HashSet};
+
+        let mut map: HashMap<_, _> = (0..100).map(|i| (i, i * 2)).collect();
+        let capacity = map.capacity();
+        let mut sum = 0;
+
+        clear_map_with(&mut map, |key, value| {
+            assert_eq!(value, key * 2);
+
+            sum += value;
+        });
+
+        assert!(map.is_empty());
+        assert_eq!(map.capacity(), capacity);
+        assert_eq!(sum, 9900);
+
+        let mut set: HashSet<_> = (0..100).collect();
+        let mut taken = Vec::new();
+
+        clear_set_with(&mut set, |x| taken.push(x));
+        taken.sort_unstable();
+
+        assert!(set.is_empty());
+        assert!(set.capacity() >= 100);
+        assert_eq!(taken, (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    #[cfg(not(feature = "no_std"))]
+    fn test_par_clear_with() {
+        use std::sync::Mutex;
+
+        for (len, threads) in [(0, 4), (3, 4), (10, 1), (1000, 3), (1001, 8)] {
+            let mut vec: Vec<_> = (0..len).map(alloc::boxed::Box::new).collect();
+            let taken = Mutex::new(Vec::new());
+
+            par_clear_with(&mut vec, |x| taken.lock().unwrap().push(*x), threads);
+
+            let mut taken = taken.into_inner().unwrap();
+
+            taken.sort_unstable();
+
+            assert!(vec.is_empty());
+            assert!(vec.capacity() >= len);
+            assert_eq!(taken, (0..len).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "no_std"))]
+    fn test_retain_with_panic() {
+        use alloc::rc::Rc;
+        use alloc::vec;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let counter = Rc::new(());
+        let mut vec = vec![counter.clone(); 10];
+        let mut calls = 0;
+
+        let res = catch_unwind(AssertUnwindSafe(|| {
+            retain_with(
+                &mut vec,
+                |_| {
+                    calls += 1;
+
+                    assert!(calls < 6, "the predicate panics");
+
+                    calls % 2 == 0
+                },
+                drop,
+            );
+        }));
+
+        assert!(res.is_err());
+        // 3 of 5 processed elements are removed, and 5 unprocessed ones are retained
+        assert_eq!(vec.len(), 7);
+        assert_eq!(Rc::strong_count(&counter), 8);
+
+        drop(vec);
+
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+}
@@ -0,0 +1,116 @@
+/// Asserts that a constant expression is `true` at compile time.
+///
+/// It accepts an optional message literal.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::const_assert;
+///
+/// const CAPACITY: usize = 64;
+///
+/// const_assert!(CAPACITY.is_power_of_two());
+/// const_assert!(CAPACITY >= 16, "the capacity is too small");
+/// ```
+///
+/// ```rust,compile_fail
+/// use orengine_utils::const_assert;
+///
+/// const_assert!(3usize.is_power_of_two());
+/// ```
+#[macro_export]
+macro_rules! const_assert {
+    ($cond:expr $(,)?) => {
+        const _: () = assert!(
+            $cond,
+            concat!("const assertion failed: ", stringify!($cond))
+        );
+    };
+    ($cond:expr, $msg:literal $(,)?) => {
+        const _: () = assert!($cond, $msg);
+    };
+}
+
+/// Asserts that two constant integer expressions are equal at compile time.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::const_assert_eq;
+///
+/// const_assert_eq!(usize::BITS, 64);
+/// ```
+#[macro_export]
+macro_rules! const_assert_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        const _: () = assert!(
+            $left == $right,
+            concat!(
+                "const assertion failed: ",
+                stringify!($left),
+                " == ",
+                stringify!($right)
+            )
+        );
+    };
+}
+
+/// Asserts that the size of a type is equal to the provided number of bytes at compile time.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::assert_size_of;
+/// use orengine_utils::OrengineInstant;
+///
+/// assert_size_of!(OrengineInstant, 8);
+/// assert_size_of!([u32; 4], 16);
+/// ```
+///
+/// ```rust,compile_fail
+/// use orengine_utils::assert_size_of;
+///
+/// assert_size_of!(u64, 4);
+/// ```
+#[macro_export]
+macro_rules! assert_size_of {
+    ($ty:ty, $size:expr $(,)?) => {
+        const _: () = assert!(
+            ::core::mem::size_of::<$ty>() == $size,
+            concat!(
+                "the size of `",
+                stringify!($ty),
+                "` is not ",
+                stringify!($size),
+                " bytes"
+            )
+        );
+    };
+}
+
+/// Asserts that the alignment of a type is equal to the provided number of bytes
+/// at compile time.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::assert_align_of;
+/// use orengine_utils::cache_padded::{CachePadded, PADDING_SIZE};
+///
+/// assert_align_of!(CachePadded<u8>, PADDING_SIZE);
+/// ```
+#[macro_export]
+macro_rules! assert_align_of {
+    ($ty:ty, $align:expr $(,)?) => {
+        const _: () = assert!(
+            ::core::mem::align_of::<$ty>() == $align,
+            concat!(
+                "the alignment of `",
+                stringify!($ty),
+                "` is not ",
+                stringify!($align),
+                " bytes"
+            )
+        );
+    };
+}
@@ -0,0 +1,205 @@
+//! This module provides a portable monotonic cycle counter.
+//!
+//! [`now`] reads the cheapest monotonic hardware counter of the target architecture:
+//!
+//! - `rdtsc` on x86 and x86-64;
+//! - `cntvct_el0` on aarch64;
+//! - `mftb` on powerpc64.
+//!
+//! On other architectures, it falls back to monotonic nanoseconds.
+//!
+//! Deltas of the counter can be converted to [`Duration`]s with [`to_duration`].
+//! The frequency of the counter is calibrated lazily on the first conversion
+//! (or explicitly with [`calibrate`]).
+//!
+//! # Example
+//!
+//! ```rust
+//! use orengine_utils::cycles;
+//!
+//! let start = cycles::now();
+//!
+//! std::thread::sleep(std::time::Duration::from_millis(1));
+//!
+//! let elapsed = cycles::to_duration(cycles::now() - start);
+//!
+//! assert!(elapsed >= std::time::Duration::from_micros(500));
+//! ```
+use core::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Cycles per second of the counter or `0` if it is not calibrated yet.
+static FREQUENCY: AtomicU64 = AtomicU64::new(0);
+
+/// How long the calibration measures the counter.
+const CALIBRATION_DURATION: Duration = Duration::from_millis(10);
+
+/// Returns `true` if [`now`] reads a hardware counter and not the fallback clock.
+pub const fn is_hardware_counter() -> bool {
+    cfg!(any(
+        target_arch = "x86_64",
+        target_arch = "x86",
+        target_arch = "aarch64",
+        target_arch = "powerpc64"
+    ))
+}
+
+/// Returns the current value of the cycle counter.
+///
+/// Only deltas of returned values are meaningful,
+/// use [`to_duration`] to convert them to [`Duration`]s.
+#[inline(always)]
+pub fn now() -> u64 {
+    #[cfg(target_arch = "x86_64")]
+    {
+        unsafe { core::arch::x86_64::_rdtsc() }
+    }
+
+    #[cfg(target_arch = "x86")]
+    {
+        unsafe { core::arch::x86::_rdtsc() }
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    {
+        let counter: u64;
+
+        unsafe {
+            core::arch::asm!("mrs {}, cntvct_el0", out(reg) counter, options(nomem, nostack));
+        }
+
+        counter
+    }
+
+    #[cfg(target_arch = "powerpc64")]
+    {
+        let counter: u64;
+
+        unsafe {
+            core::arch::asm!("mftb {}", out(reg) counter, options(nomem, nostack));
+        }
+
+        counter
+    }
+
+    #[cfg(not(any(
+        target_arch = "x86_64",
+        target_arch = "x86",
+        target_arch = "aarch64",
+        target_arch = "powerpc64"
+    )))]
+    {
+        fallback_nanos()
+    }
+}
+
+/// Returns monotonic nanoseconds since the first call.
+#[cfg_attr(
+    any(
+        target_arch = "x86_64",
+        target_arch = "x86",
+        target_arch = "aarch64",
+        target_arch = "powerpc64"
+    ),
+    allow(
+        dead_code,
+        reason = "It is used only on architectures without hardware counters"
+    )
+)]
+fn fallback_nanos() -> u64 {
+    static EPOCH: std::sync::OnceLock<Instant> = std::sync::OnceLock::new();
+
+    u64::try_from(EPOCH.get_or_init(Instant::now).elapsed().as_nanos()).unwrap_or(u64::MAX)
+}
+
+/// Measures the frequency of the counter.
+#[cold]
+#[inline(never)]
+fn measure_frequency() -> u64 {
+    if !is_hardware_counter() {
+        return 1_000_000_000;
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    {
+        let frequency: u64;
+
+        unsafe {
+            core::arch::asm!("mrs {}, cntfrq_el0", out(reg) frequency, options(nomem, nostack));
+        }
+
+        if frequency != 0 {
+            return frequency;
+        }
+    }
+
+    let start_instant = Instant::now();
+    let start_cycles = now();
+
+    while start_instant.elapsed() < CALIBRATION_DURATION {
+        core::hint::spin_loop();
+    }
+
+    let cycles = now().wrapping_sub(start_cycles);
+    let nanos = start_instant.elapsed().as_nanos();
+
+    u64::try_from(u128::from(cycles) * 1_000_000_000 / nanos)
+        .unwrap_or(u64::MAX)
+        .max(1)
+}
+
+/// Calibrates the frequency of the counter if it is not calibrated yet.
+///
+/// The calibration can take about 10 milliseconds, so call it at startup
+/// to not pay for it on the first [`to_duration`].
+pub fn calibrate() {
+    frequency();
+}
+
+/// Returns the frequency of the counter in cycles per second.
+///
+/// It calibrates the counter on the first call.
+pub fn frequency() -> u64 {
+    let frequency = FREQUENCY.load(Ordering::Relaxed);
+
+    if frequency != 0 {
+        return frequency;
+    }
+
+    let frequency = measure_frequency();
+
+    FREQUENCY.store(frequency, Ordering::Relaxed);
+
+    frequency
+}
+
+/// Converts a delta of two [`now`] values to a [`Duration`].
+///
+/// It calibrates the counter on the first call.
+#[inline]
+pub fn to_duration(delta: u64) -> Duration {
+    let nanos = u128::from(delta) * 1_000_000_000 / u128::from(frequency());
+
+    Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycles_to_duration() {
+        calibrate();
+
+        assert!(frequency() > 0);
+
+        let start = now();
+
+        std::thread::sleep(Duration::from_millis(20));
+
+        let elapsed = to_duration(now().wrapping_sub(start));
+
+        assert!(elapsed >= Duration::from_millis(15), "elapsed: {elapsed:?}");
+        assert!(elapsed < Duration::from_secs(5), "elapsed: {elapsed:?}");
+    }
+}
@@ -17,6 +17,137 @@ pub fn assert_hint(cond: bool, debug_msg: &str) {
     }
 }
 
+/// Do the same as [`assert_hint`], but formats the message
+/// only if the assertion fails with `debug_assertions`.
+///
+/// It accepts the same arguments as [`assert!`], so the hot path never builds
+/// a message string when the assertion passes.
+///
+/// # Panics
+///
+/// It panics with `debug_assertions` if the condition is `false`.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::assert_hint_fmt;
+///
+/// let (idx, len) = (1, 4);
+///
+/// assert_hint_fmt!(idx < len, "idx: {idx}, len: {len}");
+/// ```
+#[macro_export]
+macro_rules! assert_hint_fmt {
+    ($cond:expr, $($arg:tt)+) => {{
+        let cond: bool = $cond;
+
+        if cfg!(debug_assertions) {
+            assert!(cond, $($arg)+);
+        } else {
+            $crate::hints::assume(cond);
+        }
+    }};
+}
+
+/// Do the same as [`assert_eq!`] with `debug_assertions`,
+/// else hints to the compiler that both operands are equal.
+///
+/// Both operands are evaluated once and formatted with [`Debug`](core::fmt::Debug)
+/// only if the assertion fails, so it neither allocates nor formats on the hot path.
+/// An optional message accepts the same arguments as [`assert_eq!`].
+///
+/// # Panics
+///
+/// It panics with `debug_assertions` if the operands are not equal.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::{assert_hint_eq, assert_hint_ne};
+///
+/// let (head, tail) = (3usize, 3usize);
+///
+/// assert_hint_eq!(head, tail);
+/// assert_hint_ne!(head, 0, "the queue {} must be allocated", "q");
+/// ```
+#[macro_export]
+macro_rules! assert_hint_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if cfg!(debug_assertions) {
+                    assert_eq!(left, right);
+                } else {
+                    $crate::hints::assume(*left == *right);
+                }
+            }
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if cfg!(debug_assertions) {
+                    assert_eq!(left, right, $($arg)+);
+                } else {
+                    $crate::hints::assume(*left == *right);
+                }
+            }
+        }
+    };
+}
+
+/// Do the same as [`assert_ne!`] with `debug_assertions`,
+/// else hints to the compiler that the operands are not equal.
+///
+/// Read [`assert_hint_eq!`] for more details.
+///
+/// # Panics
+///
+/// It panics with `debug_assertions` if the operands are equal.
+#[macro_export]
+macro_rules! assert_hint_ne {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if cfg!(debug_assertions) {
+                    assert_ne!(left, right);
+                } else {
+                    $crate::hints::assume(*left != *right);
+                }
+            }
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if cfg!(debug_assertions) {
+                    assert_ne!(left, right, $($arg)+);
+                } else {
+                    $crate::hints::assume(*left != *right);
+                }
+            }
+        }
+    };
+}
+
+/// Do the same as [`assert_hint`], but without a message.
+///
+/// It is intended for internal invariants that are too obvious to be described,
+/// such as `index < len` right after a bounds check in a caller.
+///
+/// # Panics
+///
+/// It panics with `debug_assertions` if `cond` is `false`.
+#[inline(always)]
+#[track_caller]
+pub fn assume(cond: bool) {
+    if cfg!(debug_assertions) {
+        assert!(cond, "`assume` has been failed. It is a bug.");
+    } else {
+        unsafe { core::hint::assert_unchecked(cond) };
+    }
+}
+
 /// Do the same as [`unreachable_unchecked`](core::hint::unreachable_unchecked), but instead of UB,
 /// it panics with `debug_assertions`.
 ///
@@ -34,11 +165,82 @@ pub fn unreachable_hint() -> ! {
     }
 }
 
+/// Returns `a` if `cond` is `true` and `b` otherwise without branching on `cond`.
+///
+/// It is intended for data-dependent conditions that the branch predictor can't learn,
+/// such as wraparounds of ring buffer indexes: the selection is done by indexing
+/// an array of both values, which compilers lower to a conditional move.
+/// Both values are always computed, and the unselected one is dropped.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::hints::select_unpredictable;
+///
+/// let capacity = 8;
+/// let wrap = |index: usize| {
+///     select_unpredictable(index >= capacity, index.wrapping_sub(capacity), index)
+/// };
+///
+/// assert_eq!(wrap(3), 3);
+/// assert_eq!(wrap(10), 2);
+/// ```
+#[inline(always)]
+pub fn select_unpredictable<T>(cond: bool, a: T, b: T) -> T {
+    let values = [
+        core::mem::ManuallyDrop::new(b),
+        core::mem::ManuallyDrop::new(a),
+    ];
+    let selected = usize::from(cond);
+
+    // Each value is read exactly once, and `values` doesn't drop them.
+    unsafe {
+        drop(core::mem::ManuallyDrop::into_inner(core::ptr::read(
+            values.as_ptr().add(selected ^ 1),
+        )));
+
+        core::mem::ManuallyDrop::into_inner(core::ptr::read(values.as_ptr().add(selected)))
+    }
+}
+
 /// Indicate that a given branch is **not** likely to be taken, relatively speaking.
 #[inline(always)]
 #[cold]
 pub const fn cold_path() {}
 
+/// Calls `f` in a cold function that is never inlined.
+///
+/// It outlines error and slow branches of otherwise-inlined hot functions
+/// without writing a named `#[cold]` function for each of them.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::hints::cold_call;
+///
+/// #[inline(always)]
+/// fn checked_div(a: u32, b: u32) -> u32 {
+///     if b == 0 {
+///         return cold_call(|| {
+///             eprintln!("division by zero, returning 0");
+///
+///             0
+///         });
+///     }
+///
+///     a / b
+/// }
+///
+/// assert_eq!(checked_div(6, 3), 2);
+/// assert_eq!(checked_div(6, 0), 0);
+/// ```
+#[cold]
+#[inline(never)]
+#[track_caller]
+pub fn cold_call<R>(f: impl FnOnce() -> R) -> R {
+    f()
+}
+
 /// Indicate that a given condition is likely to be true.
 #[inline(always)]
 pub const fn likely(b: bool) -> bool {
@@ -59,6 +261,181 @@ pub const fn unlikely(b: bool) -> bool {
     b
 }
 
+/// Hints the CPU that the thread waits for another thread in a spin loop.
+///
+/// On aarch64 Linux with the kernel event stream enabled (detected at runtime), it executes
+/// `wfe`, that puts the core into a low-power state until an event is signaled
+/// by [`signal_hint`] on another core, an exclusive monitor is cleared,
+/// or the event stream wakes it up (about every 100 microseconds).
+/// Without the event stream, `wfe` can sleep until the next interrupt,
+/// so otherwise it is the same as [`spin_loop`](core::hint::spin_loop) (`pause` on x86).
+///
+/// The waiting thread must re-check its condition after each call,
+/// because wake-ups can be spurious.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::hints::{signal_hint, wait_hint};
+/// use std::sync::atomic::{AtomicBool, Ordering};
+///
+/// let is_ready = AtomicBool::new(false);
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| {
+///         is_ready.store(true, Ordering::Release);
+///         signal_hint();
+///     });
+///
+///     while !is_ready.load(Ordering::Acquire) {
+///         wait_hint();
+///     }
+/// });
+/// ```
+#[inline(always)]
+pub fn wait_hint() {
+    #[cfg(all(target_arch = "aarch64", target_os = "linux"))]
+    if has_event_stream() {
+        unsafe {
+            core::arch::asm!("wfe", options(nomem, nostack, preserves_flags));
+        }
+
+        return;
+    }
+
+    core::hint::spin_loop();
+}
+
+/// Returns `true` if the kernel event stream is enabled, so `wfe` can't wait forever.
+///
+/// The result is cached after the first call.
+#[cfg(all(target_arch = "aarch64", target_os = "linux"))]
+pub(crate) fn has_event_stream() -> bool {
+    use core::sync::atomic::{AtomicU8, Ordering};
+
+    const UNKNOWN: u8 = 0;
+    const UNSUPPORTED: u8 = 1;
+    const SUPPORTED: u8 = 2;
+    const HWCAP_EVTSTRM: libc::c_ulong = 1 << 2;
+
+    static EVENT_STREAM: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+    match EVENT_STREAM.load(Ordering::Relaxed) {
+        SUPPORTED => true,
+        UNSUPPORTED => false,
+        _ => {
+            let is_supported = unsafe { libc::getauxval(libc::AT_HWCAP) } & HWCAP_EVTSTRM != 0;
+
+            EVENT_STREAM.store(
+                if is_supported { SUPPORTED } else { UNSUPPORTED },
+                Ordering::Relaxed,
+            );
+
+            is_supported
+        }
+    }
+}
+
+/// Wakes up the cores waiting in [`wait_hint`].
+///
+/// On aarch64, it executes `sev`. On other architectures, [`wait_hint`] doesn't sleep,
+/// so it does nothing.
+#[inline(always)]
+pub fn signal_hint() {
+    #[cfg(target_arch = "aarch64")]
+    unsafe {
+        core::arch::asm!("sev", options(nomem, nostack, preserves_flags));
+    }
+}
+
+/// Executes [`spin_loop`](core::hint::spin_loop) `n` times in a tight loop.
+///
+/// It is a building block for spin waits with a fixed budget.
+/// Use [`Backoff::spin_for`](crate::backoff::Backoff::spin_for) to spin for a duration,
+/// it converts the duration to spins with the calibrated cost of one spin.
+#[inline(always)]
+pub fn spin_loop_n(n: u32) {
+    for _ in 0..n {
+        core::hint::spin_loop();
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// An integer type that can be selected without branching.
+///
+/// It is implemented for all primitive integers and used by [`branchless_min`],
+/// [`branchless_max`] and [`branchless_clamp`].
+pub trait BranchlessInt: Copy + Ord + sealed::Sealed {
+    /// Returns `a` if `cond` is `true` and `b` otherwise using a bit mask.
+    fn select(cond: bool, a: Self, b: Self) -> Self;
+}
+
+/// Implements [`BranchlessInt`] for the provided integer types.
+macro_rules! impl_branchless_int {
+    ($($int:ty),*) => {
+        $(
+            impl sealed::Sealed for $int {}
+
+            impl BranchlessInt for $int {
+                #[inline(always)]
+                fn select(cond: bool, a: Self, b: Self) -> Self {
+                    // All ones if `cond` is `true` and all zeros otherwise.
+                    let mask = <$int>::from(cond).wrapping_neg();
+
+                    b ^ ((a ^ b) & mask)
+                }
+            }
+        )*
+    };
+}
+
+impl_branchless_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Returns the minimum of two integers without branching.
+///
+/// Use it instead of [`Ord::min`] when the comparison depends on the data
+/// and the branch predictor can't learn it.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::hints::{branchless_clamp, branchless_max, branchless_min};
+///
+/// assert_eq!(branchless_min(3u32, 7), 3);
+/// assert_eq!(branchless_max(-3i64, 7), 7);
+/// assert_eq!(branchless_clamp(12usize, 0, 7), 7);
+/// ```
+#[inline(always)]
+pub fn branchless_min<T: BranchlessInt>(a: T, b: T) -> T {
+    T::select(a < b, a, b)
+}
+
+/// Returns the maximum of two integers without branching.
+///
+/// Read [`branchless_min`] for more details.
+#[inline(always)]
+pub fn branchless_max<T: BranchlessInt>(a: T, b: T) -> T {
+    T::select(a > b, a, b)
+}
+
+/// Restricts an integer to the `[min, max]` interval without branching.
+///
+/// Read [`branchless_min`] for more details.
+///
+/// # Panics
+///
+/// It panics with `debug_assertions` if `min > max`.
+#[inline(always)]
+#[track_caller]
+pub fn branchless_clamp<T: BranchlessInt>(value: T, min: T, max: T) -> T {
+    debug_assert!(min <= max, "`branchless_clamp` requires `min <= max`");
+
+    branchless_min(branchless_max(value, min), max)
+}
+
 /// A trait that is implemented by [`Option`] and [`Result`].
 pub trait UnwrapOrPanic<T> {
     /// Unwraps a value, panicking if it is [`None`] or [`Err`].
@@ -122,3 +499,38 @@ pub fn unwrap_or_bug_message_hint<T>(item: impl UnwrapOrPanic<T>, message: &'sta
         unsafe { item.unwrap_unchecked() }
     }
 }
+
+/// Unwraps an [`Option`], panicking with the provided message if it is [`None`]
+/// with `debug_assertions`.
+///
+/// Else hints to the compiler that the value is not `None`.
+///
+/// # Panics
+///
+/// It panics with `debug_assertions` if the value is `None`.
+#[track_caller]
+pub fn expect_hint<T>(option: Option<T>, message: &'static str) -> T {
+    unwrap_or_bug_message_hint(option, message)
+}
+
+/// Unwraps a [`Result`], panicking with the provided message and the error
+/// if it is [`Err`] with `debug_assertions`.
+///
+/// Else hints to the compiler that the value is not `Err`.
+///
+/// Unlike [`unwrap_or_bug_message_hint`], the panic message contains the error.
+///
+/// # Panics
+///
+/// It panics with `debug_assertions` if the value is `Err`.
+#[track_caller]
+pub fn unwrap_result_or_bug_message_hint<T, E: core::fmt::Debug>(
+    result: Result<T, E>,
+    message: &'static str,
+) -> T {
+    if cfg!(debug_assertions) {
+        result.unwrap_or_else(|err| panic!("{message}: {err:?}"))
+    } else {
+        unsafe { result.unwrap_unchecked() }
+    }
+}
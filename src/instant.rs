@@ -1,98 +1,423 @@
 //! This module contains the [`OrengineInstant`].
-use std::fmt;
-use std::ops::{Add, AddAssign, Sub, SubAssign};
-use std::time::{Duration, Instant as StdInstant};
+use core::fmt;
+use core::ops::{Add, AddAssign, Sub, SubAssign};
+use core::time::Duration;
+#[cfg(not(feature = "no_std"))]
+use std::time::Instant as StdInstant;
+
+/// The type of instants accepted by [`OrengineInstant::duration_since`] and similar methods.
+#[cfg(not(feature = "no_std"))]
+type EarlierInstant = StdInstant;
+/// The type of instants accepted by [`OrengineInstant::duration_since`] and similar methods.
+#[cfg(feature = "no_std")]
+type EarlierInstant = OrengineInstant;
 
 /// A monotone clock. It can be converted to/from `std::time::Instant`.
 ///
-/// On Unix-like systems, it weights 8 bytes.
+/// On Unix-like systems and Windows, it weights 8 bytes.
 /// On others, it is a wrapper around `std::time::Instant`.
+///
+/// With the `no_std` feature, it weights 8 bytes on all platforms and reads the time
+/// from the [`TickSource`] registered with [`set_tick_source`](Self::set_tick_source).
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct OrengineInstant {
-    #[cfg(not(unix))]
+    #[cfg(not(any(unix, windows, feature = "no_std")))]
     instant: StdInstant,
-    #[cfg(unix)]
+    #[cfg(any(unix, windows, feature = "no_std"))]
     instant: u64,
 }
 
+#[cfg(any(unix, windows, feature = "no_std"))]
+crate::assert_size_of!(OrengineInstant, 8);
+
 impl OrengineInstant {
     /// Creates a new `OrengineInstant` from a `u64`.
-    ///
-    /// # Panics
-    ///
-    /// It panics if it is called not on `unix`.
     #[cfg(unix)]
+    #[deprecated(note = "use the portable `from_nanos_since_boot` instead")]
     pub fn from_u64(instant: u64) -> Self {
-        assert!(cfg!(unix), "`from_u64` can be called only on UNIX.");
-
         Self { instant }
     }
 
     /// Converts the `OrengineInstant` into a `u64`.
-    ///
-    /// # Panics
-    ///
-    /// It panics if it is called not on `unix`.
     #[cfg(unix)]
+    #[deprecated(note = "use the portable `as_nanos_since_boot` instead")]
     pub fn into_u64(self) -> u64 {
-        assert!(cfg!(unix), "`into_u64` can be called only on UNIX.");
-
         self.instant
     }
 
+    /// Returns monotonic nanoseconds since the boot time.
+    ///
+    /// On Unix-like systems and Windows, it is the raw value of the instant.
+    /// On others, the boot time is unknown, so it is nanoseconds since the first call
+    /// of this method or [`from_nanos_since_boot`](Self::from_nanos_since_boot)
+    /// in the process, and earlier instants saturate to 0.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::OrengineInstant;
+    /// # #[cfg(feature = "no_std")] {
+    /// #     fn ticks() -> u64 {
+    /// #         static START: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+    /// #
+    /// #         START.get_or_init(std::time::Instant::now).elapsed().as_nanos() as u64
+    /// #     }
+    /// #
+    /// #     static SOURCE: orengine_utils::TickSource = orengine_utils::TickSource::new(ticks, 1_000_000_000);
+    /// #
+    /// #     OrengineInstant::set_tick_source(&SOURCE);
+    /// # }
+    ///
+    /// let instant = OrengineInstant::now();
+    /// let nanos = instant.as_nanos_since_boot();
+    ///
+    /// assert_eq!(OrengineInstant::from_nanos_since_boot(nanos), instant);
+    /// ```
+    pub fn as_nanos_since_boot(self) -> u64 {
+        #[cfg(any(unix, windows, feature = "no_std"))]
+        {
+            self.instant
+        }
+
+        #[cfg(not(any(unix, windows, feature = "no_std")))]
+        {
+            u64::try_from(
+                self.instant
+                    .saturating_duration_since(fallback_epoch())
+                    .as_nanos(),
+            )
+            .unwrap_or(u64::MAX)
+        }
+    }
+
+    /// Creates a new `OrengineInstant` from nanoseconds returned by
+    /// [`as_nanos_since_boot`](Self::as_nanos_since_boot).
+    pub fn from_nanos_since_boot(nanos: u64) -> Self {
+        #[cfg(any(unix, windows, feature = "no_std"))]
+        {
+            Self { instant: nanos }
+        }
+
+        #[cfg(not(any(unix, windows, feature = "no_std")))]
+        {
+            Self {
+                instant: fallback_epoch() + Duration::from_nanos(nanos),
+            }
+        }
+    }
+
+    /// Registers the source of [`now`](Self::now) with the `no_std` feature.
+    ///
+    /// It must be called before the first [`now`](Self::now), usually at the start of the
+    /// program. A later registration replaces the previous one, so the instants of different
+    /// sources can't be compared with each other.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use orengine_utils::{OrengineInstant, TickSource};
+    ///
+    /// fn read_cycle_counter() -> u64 {
+    ///     // Read a hardware counter of the target, for example, `DWT.CYCCNT` on Cortex-M.
+    ///     read_dwt_cyccnt()
+    /// }
+    ///
+    /// static SOURCE: TickSource = TickSource::new(read_cycle_counter, 64_000_000);
+    ///
+    /// OrengineInstant::set_tick_source(&SOURCE);
+    ///
+    /// let start = OrengineInstant::now();
+    /// ```
+    #[cfg(feature = "no_std")]
+    pub fn set_tick_source(source: &'static TickSource) {
+        ticks::set_source(source);
+    }
+
     /// Returns the current `monotonic` instant.
+    ///
+    /// With the `no_std` feature, it reads the [`TickSource`] registered with
+    /// [`set_tick_source`](Self::set_tick_source).
+    ///
+    /// With the `test_clock` feature, it can be overridden
+    /// by [`set_clock_source`](crate::test_clock::set_clock_source).
     pub fn now() -> Self {
-        #[cfg(not(unix))]
+        #[cfg(all(feature = "test_clock", not(feature = "no_std")))]
+        if let Some(now) = crate::test_clock::overridden_now() {
+            return now;
+        }
+
+        #[cfg(not(any(unix, windows, feature = "no_std")))]
         return Self {
             instant: StdInstant::now(),
         };
 
+        #[cfg(feature = "no_std")]
+        return Self {
+            instant: ticks::now_nanos(),
+        };
+
+        #[cfg(all(unix, not(feature = "no_std")))]
+        return Self::from_clock(rustix::time::ClockId::Monotonic);
+
+        #[cfg(all(windows, not(feature = "no_std")))]
+        Self {
+            instant: windows_time::now_nanos(),
+        }
+    }
+
+    /// Returns the current `monotonic` instant read from the coarse clock.
+    ///
+    /// It is several times cheaper than [`now`](Self::now), but its granularity is
+    /// about 1-4ms (the kernel tick), so it suits timeouts, but not latency measurements.
+    ///
+    /// It uses `CLOCK_MONOTONIC_COARSE` on Linux and Android and `CLOCK_MONOTONIC_FAST`
+    /// on FreeBSD. These clocks share the timeline with [`now`](Self::now), so the instants
+    /// can be compared with each other.
+    /// On other platforms, it is the same as [`now`](Self::now).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::OrengineInstant;
+    /// use std::time::Duration;
+    /// # #[cfg(feature = "no_std")] {
+    /// #     fn ticks() -> u64 {
+    /// #         static START: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+    /// #
+    /// #         START.get_or_init(std::time::Instant::now).elapsed().as_nanos() as u64
+    /// #     }
+    /// #
+    /// #     static SOURCE: orengine_utils::TickSource = orengine_utils::TickSource::new(ticks, 1_000_000_000);
+    /// #
+    /// #     OrengineInstant::set_tick_source(&SOURCE);
+    /// # }
+    ///
+    /// let deadline = OrengineInstant::now_coarse() + Duration::from_secs(1);
+    ///
+    /// assert!(OrengineInstant::now_coarse() < deadline);
+    /// ```
+    pub fn now_coarse() -> Self {
+        #[cfg(all(feature = "test_clock", not(feature = "no_std")))]
+        if let Some(now) = crate::test_clock::overridden_now() {
+            return now;
+        }
+
+        #[cfg(all(
+            any(target_os = "linux", target_os = "android", target_os = "freebsd"),
+            not(feature = "no_std")
+        ))]
+        {
+            Self::from_clock(rustix::time::ClockId::MonotonicCoarse)
+        }
+
+        #[cfg(not(all(
+            any(target_os = "linux", target_os = "android", target_os = "freebsd"),
+            not(feature = "no_std")
+        )))]
+        {
+            Self::now()
+        }
+    }
+
+    /// Returns the current `monotonic` instant read from the CPU cycle counter.
+    ///
+    /// It reads `rdtsc` on x86 and `cntvct_el0` on aarch64 and converts cycles
+    /// to nanoseconds with the frequency that is calibrated on the first call
+    /// (it can take about 10 milliseconds, so call it at startup).
+    /// It takes a few nanoseconds, so it suits per-operation latency tracking.
+    ///
+    /// The instants share the timeline with [`now`](Self::now), but they can slowly drift
+    /// apart from it (by a few microseconds per second), because the monotonic clock is
+    /// adjusted by NTP, and the counter is not.
+    /// So, compare the instants returned by this method only with each other.
+    ///
+    /// If the counter is not invariant (it can change its frequency or stop in sleep states)
+    /// or the platform is not Unix-like, it is the same as [`now`](Self::now).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::OrengineInstant;
+    /// # #[cfg(feature = "no_std")] {
+    /// #     fn ticks() -> u64 {
+    /// #         static START: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+    /// #
+    /// #         START.get_or_init(std::time::Instant::now).elapsed().as_nanos() as u64
+    /// #     }
+    /// #
+    /// #     static SOURCE: orengine_utils::TickSource = orengine_utils::TickSource::new(ticks, 1_000_000_000);
+    /// #
+    /// #     OrengineInstant::set_tick_source(&SOURCE);
+    /// # }
+    ///
+    /// let start = OrengineInstant::now_tsc();
+    /// let elapsed = OrengineInstant::now_tsc() - start;
+    ///
+    /// assert!(elapsed.as_millis() < 1000);
+    /// ```
+    #[inline]
+    pub fn now_tsc() -> Self {
+        #[cfg(all(feature = "test_clock", not(feature = "no_std")))]
+        if let Some(now) = crate::test_clock::overridden_now() {
+            return now;
+        }
+
+        #[cfg(all(unix, not(feature = "no_std")))]
+        {
+            tsc::calibration().map_or_else(Self::now, |calibration| Self {
+                instant: calibration.nanos(crate::cycles::now()),
+            })
+        }
+
+        #[cfg(not(all(unix, not(feature = "no_std"))))]
+        {
+            Self::now()
+        }
+    }
+
+    /// Returns the approximate current instant with a single atomic load.
+    ///
+    /// It returns the instant of the last [`CachedNow::refresh`] of the global [`CachedNow`]
+    /// (read [`CachedNow`] for more details), so the runtime must refresh it periodically
+    /// with [`refresh_recent`](Self::refresh_recent)
+    /// or [`CachedNow::spawn_refresher`].
+    #[cfg(target_has_atomic = "64")]
+    #[inline]
+    pub fn recent() -> Self {
+        GLOBAL_CACHED_NOW.recent()
+    }
+
+    /// Refreshes the instant returned by [`recent`](Self::recent) and returns it.
+    #[cfg(target_has_atomic = "64")]
+    pub fn refresh_recent() -> Self {
+        GLOBAL_CACHED_NOW.refresh()
+    }
+
+    /// Returns the resolution of the clock of [`now`](Self::now).
+    ///
+    /// It reads `clock_getres` on Unix-like systems and the frequency of the performance
+    /// counter on Windows. On other platforms, it measures the smallest observable step
+    /// of the clock, so it is better to call it once at startup.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::OrengineInstant;
+    /// use std::time::Duration;
+    /// # #[cfg(feature = "no_std")] {
+    /// #     fn ticks() -> u64 {
+    /// #         static START: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+    /// #
+    /// #         START.get_or_init(std::time::Instant::now).elapsed().as_nanos() as u64
+    /// #     }
+    /// #
+    /// #     static SOURCE: orengine_utils::TickSource = orengine_utils::TickSource::new(ticks, 1_000_000_000);
+    /// #
+    /// #     OrengineInstant::set_tick_source(&SOURCE);
+    /// # }
+    ///
+    /// let resolution = OrengineInstant::resolution();
+    ///
+    /// assert!(resolution > Duration::ZERO);
+    /// assert!(resolution < Duration::from_millis(100));
+    /// ```
+    pub fn resolution() -> Duration {
+        #[cfg(feature = "no_std")]
+        {
+            Duration::from_nanos((1_000_000_000 / ticks::source().frequency).max(1))
+        }
+
+        #[cfg(all(unix, not(feature = "no_std")))]
         #[allow(clippy::cast_sign_loss, reason = "It can't be negative")]
-        #[cfg(unix)]
         {
-            let ts = rustix::time::clock_gettime(rustix::time::ClockId::Monotonic);
+            let ts = rustix::time::clock_getres(rustix::time::ClockId::Monotonic);
 
-            Self {
-                instant: ts.tv_sec as u64 * 1_000_000_000 + ts.tv_nsec as u64,
+            Duration::from_nanos(ts.tv_sec as u64 * 1_000_000_000 + ts.tv_nsec as u64)
+                .max(Duration::from_nanos(1))
+        }
+
+        #[cfg(all(windows, not(feature = "no_std")))]
+        {
+            Duration::from_nanos((1_000_000_000 / windows_time::frequency()).max(1))
+        }
+
+        #[cfg(not(any(unix, windows, feature = "no_std")))]
+        {
+            const SAMPLES: usize = 16;
+
+            let mut resolution = Duration::MAX;
+
+            for _ in 0..SAMPLES {
+                let start = StdInstant::now();
+                let mut now = StdInstant::now();
+
+                while now == start {
+                    now = StdInstant::now();
+                }
+
+                resolution = resolution.min(now - start);
             }
+
+            resolution
+        }
+    }
+
+    /// Reads the provided clock.
+    #[cfg(all(unix, not(feature = "no_std")))]
+    #[allow(clippy::cast_sign_loss, reason = "It can't be negative")]
+    fn from_clock(clock: rustix::time::ClockId) -> Self {
+        let ts = rustix::time::clock_gettime(clock);
+
+        Self {
+            instant: ts.tv_sec as u64 * 1_000_000_000 + ts.tv_nsec as u64,
         }
     }
 
     /// Returns the amount of time elapsed from another instant to this one, or `None` if that
-    /// instant is earlier than this one.
+    /// instant is later than this one.
     ///
     /// Due to `monotonicity bugs`, even under correct logical ordering of the passed `Instant`s,
     /// this method can return `None`.
-    pub fn checked_duration_since(&self, earlier: impl Into<StdInstant>) -> Option<Duration> {
-        #[cfg(not(unix))]
+    #[cfg_attr(
+        feature = "no_std",
+        allow(
+            clippy::use_self,
+            clippy::useless_conversion,
+            reason = "`EarlierInstant` is `Self` with `no_std`"
+        )
+    )]
+    pub fn checked_duration_since(&self, earlier: impl Into<EarlierInstant>) -> Option<Duration> {
+        #[cfg(not(any(unix, windows, feature = "no_std")))]
         {
             self.instant.checked_duration_since(earlier.into())
         }
 
-        #[cfg(unix)]
+        #[cfg(any(unix, windows, feature = "no_std"))]
         {
-            Some(Duration::from_nanos(
-                self.instant - Self::from(earlier.into()).instant,
-            ))
+            self.instant
+                .checked_sub(Self::from(earlier.into()).instant)
+                .map(Duration::from_nanos)
         }
     }
 
     /// Returns the amount of time elapsed from another instant to this one, or zero duration if
-    /// that instant is earlier than this one.
-    ///
-    /// Due to `monotonicity bugs`, even under correct logical ordering of the passed `Instant`s,
-    /// this method can return `None`.
-    pub fn saturating_duration_since(&self, earlier: impl Into<StdInstant>) -> Duration {
+    /// that instant is later than this one.
+    #[cfg_attr(
+        feature = "no_std",
+        allow(clippy::use_self, reason = "`EarlierInstant` is `Self` with `no_std`")
+    )]
+    pub fn saturating_duration_since(&self, earlier: impl Into<EarlierInstant>) -> Duration {
         self.checked_duration_since(earlier.into())
             .unwrap_or_default()
     }
 
     /// Returns the amount of time elapsed from another instant to this one, or zero duration if
-    /// that instant is earlier than this one.
-    ///
-    /// Due to `monotonicity bugs`, even under correct logical ordering of the passed `Instant`s,
-    /// this method can return `None`.
-    pub fn duration_since(&self, earlier: impl Into<StdInstant>) -> Duration {
+    /// that instant is later than this one.
+    #[cfg_attr(
+        feature = "no_std",
+        allow(clippy::use_self, reason = "`EarlierInstant` is `Self` with `no_std`")
+    )]
+    pub fn duration_since(&self, earlier: impl Into<EarlierInstant>) -> Duration {
         self.saturating_duration_since(earlier.into())
     }
 
@@ -101,18 +426,26 @@ impl OrengineInstant {
         Self::now() - *self
     }
 
+    /// Returns the amount of time elapsed since this instant was created
+    /// formatted for humans, such as `1.2ms` or `350ns`.
+    ///
+    /// Read [`DurationExt::human`] for more details.
+    pub fn format_elapsed(&self) -> HumanDuration {
+        self.elapsed().human()
+    }
+
     /// Returns `Some(t)` where `t` is the time `self + duration` if `t` can be represented as
     /// `Instant` (which means it's inside the bounds of the underlying data structure), `None`
     /// otherwise.
     pub fn checked_add(&self, duration: Duration) -> Option<Self> {
-        #[cfg(not(unix))]
+        #[cfg(not(any(unix, windows, feature = "no_std")))]
         {
             Some(Self {
                 instant: self.instant.checked_add(duration)?,
             })
         }
 
-        #[cfg(unix)]
+        #[cfg(any(unix, windows, feature = "no_std"))]
         {
             let total_nanos = u64::try_from(duration.as_nanos()).ok()?;
 
@@ -126,14 +459,14 @@ impl OrengineInstant {
     /// `Instant` (which means it's inside the bounds of the underlying data structure), `None`
     /// otherwise.
     pub fn checked_sub(&self, duration: Duration) -> Option<Self> {
-        #[cfg(not(unix))]
+        #[cfg(not(any(unix, windows, feature = "no_std")))]
         {
             Some(Self {
                 instant: self.instant.checked_sub(duration)?,
             })
         }
 
-        #[cfg(unix)]
+        #[cfg(any(unix, windows, feature = "no_std"))]
         {
             let total_nanos = u64::try_from(duration.as_nanos()).ok()?;
 
@@ -196,11 +529,469 @@ impl Sub<Self> for OrengineInstant {
 
 impl fmt::Debug for OrengineInstant {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.instant.fmt(f)
+        #[cfg(any(unix, windows, feature = "no_std"))]
+        {
+            f.debug_struct("OrengineInstant")
+                .field("since_boot", &Duration::from_nanos(self.instant))
+                .finish()
+        }
+
+        #[cfg(not(any(unix, windows, feature = "no_std")))]
+        {
+            self.instant.fmt(f)
+        }
     }
 }
 
-#[cfg(unix)]
+/// Formats [`Duration`]s for humans.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::DurationExt;
+/// use std::time::Duration;
+///
+/// assert_eq!(Duration::from_nanos(350).human().to_string(), "350ns");
+/// assert_eq!(Duration::from_micros(1234).human().to_string(), "1.2ms");
+/// assert_eq!(Duration::from_secs(90).human().to_string(), "1m30s");
+/// ```
+pub trait DurationExt {
+    /// Returns a wrapper that formats the duration with the largest unit
+    /// that keeps the value at least 1 and one decimal digit,
+    /// such as `350ns`, `12.5µs`, `1.2ms`, `3s`, `1m30s` or `2h5m`.
+    fn human(&self) -> HumanDuration;
+}
+
+impl DurationExt for Duration {
+    fn human(&self) -> HumanDuration {
+        HumanDuration(*self)
+    }
+}
+
+/// A [`Duration`] formatted for humans. It is returned by [`DurationExt::human`].
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HumanDuration(pub Duration);
+
+impl HumanDuration {
+    /// Writes `nanos` in the provided unit with one decimal digit,
+    /// omitting the digit if it is 0.
+    fn write_scaled(
+        f: &mut fmt::Formatter<'_>,
+        nanos: u128,
+        nanos_per_unit: u128,
+        unit: &str,
+    ) -> fmt::Result {
+        let tenths = nanos * 10 / nanos_per_unit;
+
+        if tenths.is_multiple_of(10) {
+            write!(f, "{}{unit}", tenths / 10)
+        } else {
+            write!(f, "{}.{}{unit}", tenths / 10, tenths % 10)
+        }
+    }
+}
+
+impl fmt::Display for HumanDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nanos = self.0.as_nanos();
+        let secs = self.0.as_secs();
+
+        match nanos {
+            0..1_000 => write!(f, "{nanos}ns"),
+            1_000..1_000_000 => Self::write_scaled(f, nanos, 1_000, "µs"),
+            1_000_000..1_000_000_000 => Self::write_scaled(f, nanos, 1_000_000, "ms"),
+            _ if secs < 60 => Self::write_scaled(f, nanos, 1_000_000_000, "s"),
+            _ if secs < 3600 => write!(f, "{}m{}s", secs / 60, secs % 60),
+            _ => write!(f, "{}h{}m", secs / 3600, secs % 3600 / 60),
+        }
+    }
+}
+
+impl fmt::Debug for HumanDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// The global [`CachedNow`] of [`OrengineInstant::recent`].
+#[cfg(target_has_atomic = "64")]
+static GLOBAL_CACHED_NOW: CachedNow = CachedNow::new();
+
+/// A cached current instant, that is read with a single load.
+///
+/// Reading the clock on every request can dominate tight processing loops.
+/// Instead, the runtime can [`refresh`](Self::refresh) it once per tick
+/// (or [`spawn_refresher`](Self::spawn_refresher) a background thread),
+/// and hot paths read the [`recent`](Self::recent) instant, that lags behind the real one
+/// by at most the refresh interval.
+///
+/// The global instance is used by [`OrengineInstant::recent`].
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::{CachedNow, OrengineInstant};
+/// # #[cfg(feature = "no_std")] {
+/// #     fn ticks() -> u64 {
+/// #         static START: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+/// #
+/// #         START.get_or_init(std::time::Instant::now).elapsed().as_nanos() as u64
+/// #     }
+/// #
+/// #     static SOURCE: orengine_utils::TickSource = orengine_utils::TickSource::new(ticks, 1_000_000_000);
+/// #
+/// #     OrengineInstant::set_tick_source(&SOURCE);
+/// # }
+///
+/// static NOW: CachedNow = CachedNow::new();
+///
+/// // Once per tick of the runtime
+/// NOW.refresh();
+///
+/// // On hot paths
+/// let deadline = NOW.recent() + std::time::Duration::from_secs(1);
+///
+/// assert!(OrengineInstant::now() < deadline);
+/// ```
+#[cfg(target_has_atomic = "64")]
+pub struct CachedNow {
+    nanos: crate::cache_padded::CachePaddedAtomicU64,
+}
+
+#[cfg(target_has_atomic = "64")]
+impl CachedNow {
+    /// Creates a new `CachedNow` that is refreshed on the first [`recent`](Self::recent).
+    pub const fn new() -> Self {
+        Self {
+            nanos: crate::cache_padded::CachePaddedAtomicU64::new(0),
+        }
+    }
+
+    /// Reads the clock and caches the instant.
+    pub fn refresh(&self) -> OrengineInstant {
+        let now = OrengineInstant::now();
+
+        self.nanos.store(
+            now.as_nanos_since_boot(),
+            core::sync::atomic::Ordering::Relaxed,
+        );
+
+        now
+    }
+
+    /// Returns the cached instant.
+    ///
+    /// If it has never been refreshed, it refreshes it.
+    #[inline]
+    pub fn recent(&self) -> OrengineInstant {
+        match self.nanos.load(core::sync::atomic::Ordering::Relaxed) {
+            0 => self.refresh(),
+            nanos => OrengineInstant::from_nanos_since_boot(nanos),
+        }
+    }
+
+    /// Spawns a thread that refreshes the instant every `interval` until the process exits.
+    #[cfg(not(feature = "no_std"))]
+    ///
+    /// # Panics
+    ///
+    /// Panics if the OS fails to create a thread.
+    pub fn spawn_refresher(&'static self, interval: Duration) -> std::thread::JoinHandle<()> {
+        std::thread::Builder::new()
+            .name("orengine-cached-now".into())
+            .spawn(move || loop {
+                self.refresh();
+
+                std::thread::sleep(interval);
+            })
+            .expect("failed to spawn the refresher of CachedNow")
+    }
+}
+
+#[cfg(target_has_atomic = "64")]
+impl Default for CachedNow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(target_has_atomic = "64")]
+impl fmt::Debug for CachedNow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CachedNow").field(&self.recent()).finish()
+    }
+}
+
+/// The storage of [`AtomicOrengineInstant`].
+#[cfg(all(any(unix, windows, feature = "no_std"), target_has_atomic = "64"))]
+type AtomicInstantCell = core::sync::atomic::AtomicU64;
+/// The storage of [`AtomicOrengineInstant`].
+#[cfg(all(
+    not(all(any(unix, windows, feature = "no_std"), target_has_atomic = "64")),
+    not(feature = "no_std")
+))]
+type AtomicInstantCell = std::sync::Mutex<OrengineInstant>;
+
+/// An [`OrengineInstant`] which can be safely shared between threads.
+///
+/// It is backed by an `AtomicU64` on Unix-like systems and Windows,
+/// and by a `Mutex` on others.
+///
+/// It is useful for publishing timestamps such as "last activity" from many threads.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::{AtomicOrengineInstant, OrengineInstant};
+/// use core::sync::atomic::Ordering;
+/// use std::time::Duration;
+/// # #[cfg(feature = "no_std")] {
+/// #     fn ticks() -> u64 {
+/// #         static START: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+/// #
+/// #         START.get_or_init(std::time::Instant::now).elapsed().as_nanos() as u64
+/// #     }
+/// #
+/// #     static SOURCE: orengine_utils::TickSource = orengine_utils::TickSource::new(ticks, 1_000_000_000);
+/// #
+/// #     OrengineInstant::set_tick_source(&SOURCE);
+/// # }
+///
+/// let last_activity = AtomicOrengineInstant::new(OrengineInstant::now());
+///
+/// std::thread::scope(|scope| {
+///     for _ in 0..4 {
+///         scope.spawn(|| {
+///             last_activity.fetch_max(OrengineInstant::now(), Ordering::Relaxed);
+///         });
+///     }
+/// });
+///
+/// assert!(last_activity.elapsed_since_load(Ordering::Relaxed) < Duration::from_secs(60));
+/// ```
+#[cfg(any(not(feature = "no_std"), target_has_atomic = "64"))]
+pub struct AtomicOrengineInstant {
+    cell: AtomicInstantCell,
+}
+
+#[cfg(any(not(feature = "no_std"), target_has_atomic = "64"))]
+impl AtomicOrengineInstant {
+    /// Creates a new `AtomicOrengineInstant`.
+    pub fn new(instant: OrengineInstant) -> Self {
+        #[cfg(all(any(unix, windows, feature = "no_std"), target_has_atomic = "64"))]
+        {
+            Self {
+                cell: AtomicInstantCell::new(instant.instant),
+            }
+        }
+
+        #[cfg(not(all(any(unix, windows, feature = "no_std"), target_has_atomic = "64")))]
+        {
+            Self {
+                cell: AtomicInstantCell::new(instant),
+            }
+        }
+    }
+
+    /// Loads the instant.
+    pub fn load(&self, order: core::sync::atomic::Ordering) -> OrengineInstant {
+        #[cfg(all(any(unix, windows, feature = "no_std"), target_has_atomic = "64"))]
+        {
+            OrengineInstant {
+                instant: self.cell.load(order),
+            }
+        }
+
+        #[cfg(not(all(any(unix, windows, feature = "no_std"), target_has_atomic = "64")))]
+        {
+            let _ = order;
+
+            *self
+                .cell
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+        }
+    }
+
+    /// Stores the instant.
+    pub fn store(&self, instant: OrengineInstant, order: core::sync::atomic::Ordering) {
+        self.swap(instant, order);
+    }
+
+    /// Stores the instant, returning the previous one.
+    pub fn swap(
+        &self,
+        instant: OrengineInstant,
+        order: core::sync::atomic::Ordering,
+    ) -> OrengineInstant {
+        #[cfg(all(any(unix, windows, feature = "no_std"), target_has_atomic = "64"))]
+        {
+            OrengineInstant {
+                instant: self.cell.swap(instant.instant, order),
+            }
+        }
+
+        #[cfg(not(all(any(unix, windows, feature = "no_std"), target_has_atomic = "64")))]
+        {
+            let _ = order;
+            let mut guard = self
+                .cell
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+            std::mem::replace(&mut *guard, instant)
+        }
+    }
+
+    /// Stores the maximum of the current and the provided instants, returning the previous one.
+    ///
+    /// It never moves the instant backwards, so concurrent publishers can't overwrite
+    /// a later instant with an earlier one.
+    pub fn fetch_max(
+        &self,
+        instant: OrengineInstant,
+        order: core::sync::atomic::Ordering,
+    ) -> OrengineInstant {
+        #[cfg(all(any(unix, windows, feature = "no_std"), target_has_atomic = "64"))]
+        {
+            OrengineInstant {
+                instant: self.cell.fetch_max(instant.instant, order),
+            }
+        }
+
+        #[cfg(not(all(any(unix, windows, feature = "no_std"), target_has_atomic = "64")))]
+        {
+            let _ = order;
+            let mut guard = self
+                .cell
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            let prev = *guard;
+
+            *guard = prev.max(instant);
+
+            prev
+        }
+    }
+
+    /// Returns the amount of time elapsed since the loaded instant.
+    pub fn elapsed_since_load(&self, order: core::sync::atomic::Ordering) -> Duration {
+        self.load(order).elapsed()
+    }
+
+    /// Consumes the atomic and returns the contained instant.
+    pub fn into_inner(self) -> OrengineInstant {
+        #[cfg(all(any(unix, windows, feature = "no_std"), target_has_atomic = "64"))]
+        {
+            OrengineInstant {
+                instant: self.cell.into_inner(),
+            }
+        }
+
+        #[cfg(not(all(any(unix, windows, feature = "no_std"), target_has_atomic = "64")))]
+        {
+            self.cell
+                .into_inner()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+        }
+    }
+}
+
+#[cfg(any(not(feature = "no_std"), target_has_atomic = "64"))]
+impl From<OrengineInstant> for AtomicOrengineInstant {
+    fn from(instant: OrengineInstant) -> Self {
+        Self::new(instant)
+    }
+}
+
+#[cfg(any(not(feature = "no_std"), target_has_atomic = "64"))]
+impl fmt::Debug for AtomicOrengineInstant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.load(core::sync::atomic::Ordering::Relaxed), f)
+    }
+}
+
+/// The calibration of [`OrengineInstant::now_tsc`].
+#[cfg(all(unix, not(feature = "no_std")))]
+mod tsc {
+    use std::sync::OnceLock;
+
+    /// Converts the cycle counter to nanoseconds of the monotonic clock.
+    pub(super) struct Calibration {
+        base_cycles: u64,
+        base_nanos: u64,
+        /// Nanoseconds per cycle in the 32.32 fixed-point format.
+        nanos_per_cycle: u64,
+    }
+
+    impl Calibration {
+        /// Converts the value of the counter to nanoseconds of the monotonic clock.
+        #[inline]
+        #[allow(
+            clippy::cast_possible_truncation,
+            reason = "The result fits in u64 for centuries of uptime"
+        )]
+        pub(super) fn nanos(&self, cycles: u64) -> u64 {
+            let delta = u128::from(cycles.wrapping_sub(self.base_cycles));
+
+            self.base_nanos + ((delta * u128::from(self.nanos_per_cycle)) >> 32) as u64
+        }
+    }
+
+    /// Returns `true` if the counter ticks with a constant frequency in all power states.
+    fn is_counter_invariant() -> bool {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        {
+            #[cfg(target_arch = "x86")]
+            use core::arch::x86::__cpuid;
+            #[cfg(target_arch = "x86_64")]
+            use core::arch::x86_64::__cpuid;
+
+            #[allow(unused_unsafe, reason = "cpuid is safe since Rust 1.88")]
+            unsafe {
+                // The "invariant TSC" bit of the advanced power management leaf.
+                __cpuid(0x8000_0000).eax >= 0x8000_0007 && __cpuid(0x8000_0007).edx & (1 << 8) != 0
+            }
+        }
+
+        #[cfg(not(any(target_arch = "x86_64", target_arch = "x86")))]
+        {
+            // The generic timer of aarch64 has a fixed frequency by the specification.
+            cfg!(target_arch = "aarch64")
+        }
+    }
+
+    /// Returns the calibration or `None` if the counter can't be used.
+    ///
+    /// It calibrates the counter on the first call.
+    #[inline]
+    pub(super) fn calibration() -> Option<&'static Calibration> {
+        static CALIBRATION: OnceLock<Option<Calibration>> = OnceLock::new();
+
+        CALIBRATION
+            .get_or_init(|| {
+                if !is_counter_invariant() {
+                    return None;
+                }
+
+                let frequency = crate::cycles::frequency();
+                let base_cycles = crate::cycles::now();
+                let base_nanos = super::OrengineInstant::now().instant;
+
+                Some(Calibration {
+                    base_cycles,
+                    base_nanos,
+                    nanos_per_cycle: u64::try_from(
+                        (1_000_000_000u128 << 32) / u128::from(frequency),
+                    )
+                    .unwrap_or(u64::MAX),
+                })
+            })
+            .as_ref()
+    }
+}
+
+#[cfg(all(unix, not(feature = "no_std")))]
 mod unix_time {
     // std::time::Instant is represented as
     // struct Nanoseconds(u32);
@@ -222,13 +1013,167 @@ mod unix_time {
     }
 }
 
+#[cfg(all(windows, not(feature = "no_std")))]
+mod windows_time {
+    use std::sync::OnceLock;
+    use windows_sys::Win32::System::Performance::{
+        QueryPerformanceCounter, QueryPerformanceFrequency,
+    };
+
+    // std::time::Instant is represented as
+    // struct Instant {
+    //     t: Duration,
+    // }
+    //
+    // where `t` is the performance counter converted to nanoseconds.
+
+    /// Returns the frequency of the performance counter.
+    ///
+    /// It is fixed at boot, so it is cached after the first call.
+    #[allow(clippy::cast_sign_loss, reason = "It is always positive")]
+    pub(crate) fn frequency() -> u64 {
+        static FREQUENCY: OnceLock<u64> = OnceLock::new();
+
+        *FREQUENCY.get_or_init(|| {
+            let mut frequency = 0;
+
+            unsafe { QueryPerformanceFrequency(&raw mut frequency) };
+
+            frequency as u64
+        })
+    }
+
+    /// Returns the performance counter converted to nanoseconds
+    /// the same way as `std::time::Instant` does it.
+    #[allow(
+        clippy::cast_sign_loss,
+        clippy::cast_possible_truncation,
+        reason = "The counter is never negative and fits in u64 nanoseconds for centuries"
+    )]
+    pub(crate) fn now_nanos() -> u64 {
+        let mut ticks = 0;
+
+        unsafe { QueryPerformanceCounter(&raw mut ticks) };
+
+        (u128::from(ticks as u64) * 1_000_000_000 / u128::from(frequency())) as u64
+    }
+}
+
+/// A monotonic source of time for [`OrengineInstant`] with the `no_std` feature.
+///
+/// It is registered with [`OrengineInstant::set_tick_source`].
+#[cfg(feature = "no_std")]
+#[derive(Debug, Clone, Copy)]
+pub struct TickSource {
+    /// Returns the current value of a monotonic counter, such as a cycle counter.
+    pub ticks: fn() -> u64,
+    /// Ticks of the counter per second.
+    pub frequency: u64,
+}
+
+#[cfg(feature = "no_std")]
+impl TickSource {
+    /// Creates a new `TickSource`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `frequency` is 0.
+    pub const fn new(ticks: fn() -> u64, frequency: u64) -> Self {
+        assert!(
+            frequency > 0,
+            "the frequency of a tick source must be positive"
+        );
+
+        Self { ticks, frequency }
+    }
+}
+
+/// The registered [`TickSource`] of the `no_std` feature.
+#[cfg(feature = "no_std")]
+mod ticks {
+    use super::TickSource;
+    use core::ptr;
+    use core::sync::atomic::{AtomicPtr, Ordering};
+
+    static SOURCE: AtomicPtr<TickSource> = AtomicPtr::new(ptr::null_mut());
+
+    /// Registers the source.
+    pub(super) fn set_source(source: &'static TickSource) {
+        SOURCE.store(ptr::from_ref(source).cast_mut(), Ordering::Release);
+    }
+
+    /// Returns the registered source.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no source is registered.
+    #[inline]
+    pub(super) fn source() -> &'static TickSource {
+        let source = SOURCE.load(Ordering::Acquire);
+
+        assert!(
+            !source.is_null(),
+            "no tick source is registered, call `OrengineInstant::set_tick_source` first"
+        );
+
+        unsafe { &*source }
+    }
+
+    /// Returns the ticks of the registered source converted to nanoseconds.
+    #[inline]
+    #[allow(
+        clippy::cast_possible_truncation,
+        reason = "It fits in u64 nanoseconds for centuries"
+    )]
+    pub(super) fn now_nanos() -> u64 {
+        let source = source();
+
+        (u128::from((source.ticks)()) * 1_000_000_000 / u128::from(source.frequency)) as u64
+    }
+}
+
+/// Returns the epoch of the `std::time::Instant`-based representation.
+#[cfg(not(any(unix, windows, feature = "no_std")))]
+fn fallback_epoch() -> StdInstant {
+    static EPOCH: std::sync::OnceLock<StdInstant> = std::sync::OnceLock::new();
+
+    *EPOCH.get_or_init(StdInstant::now)
+}
+
+/// Serializes the instant as monotonic nanoseconds since the boot time
+/// on Unix-like systems and Windows and since the first use of the process elsewhere.
+///
+/// So, deserialized instants are meaningful only on the same machine until its reboot
+/// (and only in the same process on other platforms).
+/// Compare them with each other, rather than with the current time of another machine.
+#[cfg(feature = "serde")]
+impl serde::Serialize for OrengineInstant {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.as_nanos_since_boot())
+    }
+}
+
+/// Deserializes the instant serialized by its `serde::Serialize` implementation.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for OrengineInstant {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u64::deserialize(deserializer).map(Self::from_nanos_since_boot)
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
 impl From<OrengineInstant> for std::time::Instant {
     fn from(val: OrengineInstant) -> Self {
-        #[cfg(not(unix))]
+        #[cfg(not(any(unix, windows)))]
         {
             val.instant
         }
 
+        #[cfg(windows)]
+        {
+            unsafe { std::mem::transmute::<Duration, Self>(Duration::from_nanos(val.instant)) }
+        }
+
         #[cfg(unix)]
         {
             let dur = Duration::from_nanos(val.instant);
@@ -245,14 +1190,24 @@ impl From<OrengineInstant> for std::time::Instant {
     }
 }
 
+#[cfg(not(feature = "no_std"))]
 impl From<std::time::Instant> for OrengineInstant {
     #[allow(clippy::cast_sign_loss, reason = "It is never negative")]
     fn from(val: std::time::Instant) -> Self {
-        #[cfg(not(unix))]
+        #[cfg(not(any(unix, windows)))]
         {
             Self { instant: val }
         }
 
+        #[cfg(windows)]
+        {
+            let dur = unsafe { std::mem::transmute::<std::time::Instant, Duration>(val) };
+
+            Self {
+                instant: u64::try_from(dur.as_nanos()).unwrap_or(u64::MAX),
+            }
+        }
+
         #[cfg(unix)]
         {
             #[allow(clippy::transmute_undefined_repr, reason = "False positive")]
@@ -265,7 +1220,7 @@ impl From<std::time::Instant> for OrengineInstant {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, not(feature = "no_std")))]
 mod tests {
     use super::OrengineInstant;
     use std::thread;
@@ -295,6 +1250,126 @@ mod tests {
         assert_eq!(std_instant, std_instant_from_instant);
     }
 
+    #[test]
+    fn test_now_coarse() {
+        let coarse = OrengineInstant::now_coarse();
+
+        thread::sleep(Duration::from_millis(20));
+
+        let now = OrengineInstant::now();
+
+        // The coarse clock lags behind by at most a few kernel ticks.
+        assert!(coarse < now);
+        assert!(now.duration_since(coarse) < Duration::from_secs(1));
+        assert!(OrengineInstant::now_coarse() > coarse);
+    }
+
+    #[test]
+    fn test_now_tsc() {
+        let start = OrengineInstant::now_tsc();
+
+        thread::sleep(Duration::from_millis(20));
+
+        let elapsed = OrengineInstant::now_tsc() - start;
+
+        assert!(elapsed >= Duration::from_millis(15), "elapsed: {elapsed:?}");
+        assert!(elapsed < Duration::from_secs(5), "elapsed: {elapsed:?}");
+    }
+
+    #[test]
+    fn test_atomic_instant() {
+        use super::AtomicOrengineInstant;
+        use core::sync::atomic::Ordering;
+
+        let start = OrengineInstant::now();
+        let atomic = AtomicOrengineInstant::new(start);
+        let later = start + Duration::from_millis(1);
+
+        assert_eq!(atomic.fetch_max(later, Ordering::Relaxed), start);
+        assert_eq!(atomic.fetch_max(start, Ordering::Relaxed), later);
+        assert_eq!(atomic.swap(start, Ordering::Relaxed), later);
+        assert_eq!(atomic.into_inner(), start);
+    }
+
+    #[test]
+    fn test_duration_since_later_instant() {
+        let earlier = OrengineInstant::now();
+        let later = earlier + Duration::from_millis(1);
+
+        assert_eq!(
+            later.checked_duration_since(earlier),
+            Some(Duration::from_millis(1))
+        );
+        assert_eq!(earlier.checked_duration_since(later), None);
+        assert_eq!(earlier.saturating_duration_since(later), Duration::ZERO);
+        assert_eq!(earlier - later, Duration::ZERO);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_instant_serde() {
+        use crate::rw_serde::{RWDeserializer, RWSerializer};
+        use serde::{Deserialize, Serialize};
+        use std::io::Cursor;
+
+        let instant = OrengineInstant::now();
+        let mut ser = RWSerializer::new(Vec::new());
+
+        instant.serialize(&mut ser).unwrap();
+
+        let restored =
+            OrengineInstant::deserialize(&mut RWDeserializer::new(Cursor::new(ser.into_inner())))
+                .unwrap();
+
+        assert_eq!(restored, instant);
+    }
+
+    #[test]
+    fn test_cached_now() {
+        use super::CachedNow;
+
+        let cached = CachedNow::new();
+        let first = cached.recent();
+
+        thread::sleep(Duration::from_millis(2));
+
+        assert_eq!(cached.recent(), first);
+
+        let refreshed = cached.refresh();
+
+        assert!(refreshed > first);
+        assert_eq!(cached.recent(), refreshed);
+        assert!(OrengineInstant::refresh_recent() >= refreshed);
+    }
+
+    #[test]
+    fn test_human_duration() {
+        use super::DurationExt;
+
+        let cases = [
+            (Duration::ZERO, "0ns"),
+            (Duration::from_nanos(999), "999ns"),
+            (Duration::from_nanos(12_540), "12.5µs"),
+            (Duration::from_micros(999_999), "999.9ms"),
+            (Duration::from_secs(3), "3s"),
+            (Duration::from_millis(59_950), "59.9s"),
+            (Duration::from_secs(3599), "59m59s"),
+            (Duration::from_secs(7500), "2h5m"),
+        ];
+
+        for (duration, expected) in cases {
+            assert_eq!(duration.human().to_string(), expected);
+        }
+
+        assert_eq!(
+            format!(
+                "{:?}",
+                OrengineInstant::from_nanos_since_boot(1_500_000_001)
+            ),
+            "OrengineInstant { since_boot: 1.500000001s }"
+        );
+    }
+
     #[test]
     fn test_instant_ordering() {
         let instant1: OrengineInstant = std::time::Instant::now().into();
@@ -0,0 +1,175 @@
+//! This module provides the [`IntervalGate`] that lets a code pass at most once per interval.
+//!
+//! It is the primitive behind "log this warning at most once per second" and
+//! periodic maintenance triggers.
+//!
+//! # Example
+//!
+//! ```rust
+//! use orengine_utils::interval_gate::IntervalGate;
+//! use std::time::Duration;
+//!
+//! static WARNING_GATE: IntervalGate = IntervalGate::from_duration(Duration::from_secs(1));
+//!
+//! let second = 1_000_000_000;
+//!
+//! assert!(WARNING_GATE.try_enter(10 * second));
+//! assert!(!WARNING_GATE.try_enter(10 * second + 1)); // Too early
+//! assert!(WARNING_GATE.try_enter(11 * second));
+//! ```
+use crate::cache_padded::CachePaddedAtomicU64;
+use core::sync::atomic::Ordering;
+use core::time::Duration;
+
+/// A value of the last entry timestamp that means that the gate has never been entered.
+const NEVER_ENTERED: u64 = u64::MAX;
+
+/// A gate that can be entered at most once per configured interval across all threads.
+///
+/// Timestamps are provided by the caller and can be in any monotonic units
+/// (nanoseconds, cycles, ticks of a runtime), but the interval must be in the same units.
+/// [`IntervalGate::from_duration`] creates a gate for nanosecond timestamps.
+///
+/// The timestamp of the last entry is stored in a cache-padded atomic,
+/// so checking the gate doesn't cause false sharing.
+///
+/// Read the [`module-level documentation`](crate::interval_gate) for an example.
+pub struct IntervalGate {
+    last_entry: CachePaddedAtomicU64,
+    interval: u64,
+}
+
+impl IntervalGate {
+    /// Creates a new `IntervalGate` with the provided interval.
+    ///
+    /// The first [`try_enter`](Self::try_enter) always succeeds.
+    pub const fn new(interval: u64) -> Self {
+        Self {
+            last_entry: CachePaddedAtomicU64::new(NEVER_ENTERED),
+            interval,
+        }
+    }
+
+    /// Creates a new `IntervalGate` with the provided interval for nanosecond timestamps.
+    ///
+    /// The first [`try_enter`](Self::try_enter) always succeeds.
+    /// Intervals longer than `u64::MAX` nanoseconds (more than 500 years) saturate.
+    pub const fn from_duration(interval: Duration) -> Self {
+        let nanos = match interval.as_secs().checked_mul(1_000_000_000) {
+            Some(nanos) => nanos.checked_add(interval.subsec_nanos() as u64),
+            None => None,
+        };
+
+        Self::new(match nanos {
+            Some(nanos) => nanos,
+            None => u64::MAX,
+        })
+    }
+
+    /// Returns the interval of the gate.
+    pub const fn interval(&self) -> u64 {
+        self.interval
+    }
+
+    /// Returns `true` if the gate is entered at `now`.
+    ///
+    /// It returns `true` at most once per interval across all threads. If several threads
+    /// try to enter the gate concurrently, only one of them succeeds.
+    ///
+    /// `u64::MAX` is reserved for a gate that has never been entered,
+    /// so `now` is saturated to `u64::MAX - 1`.
+    #[inline]
+    pub fn try_enter(&self, now: u64) -> bool {
+        let now = now.min(NEVER_ENTERED - 1);
+        let last_entry = self.last_entry.load(Ordering::Relaxed);
+
+        if last_entry != NEVER_ENTERED && now.saturating_sub(last_entry) < self.interval {
+            return false;
+        }
+
+        self.last_entry
+            .compare_exchange(last_entry, now, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    /// Resets the gate, so the next [`try_enter`](Self::try_enter) succeeds.
+    pub fn reset(&self) {
+        self.last_entry.store(NEVER_ENTERED, Ordering::Relaxed);
+    }
+}
+
+impl core::fmt::Debug for IntervalGate {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("IntervalGate")
+            .field("last_entry", &self.last_entry.load(Ordering::Relaxed))
+            .field("interval", &self.interval)
+            .finish()
+    }
+}
+
+#[cfg(all(test, not(feature = "no_std")))]
+mod tests {
+    use super::*;
+    use core::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn test_interval_gate_is_entered_once_across_threads() {
+        let gate = IntervalGate::new(100);
+        let entered = AtomicUsize::new(0);
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for now in 1_000..1_050 {
+                        if gate.try_enter(now) {
+                            entered.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                });
+            }
+        });
+
+        assert_eq!(entered.load(Ordering::Relaxed), 1);
+        assert!(!gate.try_enter(1_099));
+        assert!(gate.try_enter(1_200));
+
+        gate.reset();
+
+        assert!(gate.try_enter(1_201));
+    }
+
+    #[test]
+    fn test_interval_gate_max_timestamp() {
+        let gate = IntervalGate::new(100);
+
+        assert!(gate.try_enter(u64::MAX));
+        assert!(!gate.try_enter(u64::MAX));
+        assert!(!gate.try_enter(u64::MAX - 1));
+
+        let gate = IntervalGate::new(100);
+
+        assert!(gate.try_enter(u64::MAX - 100));
+        assert!(!gate.try_enter(u64::MAX));
+    }
+
+    #[test]
+    fn test_interval_gate_from_duration() {
+        assert_eq!(
+            IntervalGate::from_duration(Duration::from_millis(1_500)).interval(),
+            1_500_000_000
+        );
+        assert_eq!(
+            IntervalGate::from_duration(Duration::from_secs(u64::MAX / 1_000_000_000)).interval(),
+            u64::MAX / 1_000_000_000 * 1_000_000_000
+        );
+        assert_eq!(
+            IntervalGate::from_duration(Duration::from_nanos(u64::MAX) + Duration::from_nanos(1))
+                .interval(),
+            u64::MAX
+        );
+        assert_eq!(
+            IntervalGate::from_duration(Duration::MAX).interval(),
+            u64::MAX
+        );
+    }
+}
@@ -5,15 +5,31 @@
 //! - The [`backoff module`](backoff) provides the [`Backoff`](backoff::Backoff) structure.
 //! - The [`cache_padded module`](cache_padded) provides cache-padded atomics types and
 //!   the [`CachePadded`] wrapper.
+//!   The [`define_padded_struct`] macro defines structs with individually padded hot fields.
+//! - The [`aligned module`](aligned) provides cache-line-aligned heap allocations.
+//! - The `atomic128` module provides 128-bit atomic integers on x86-64.
 //! - The [`light_arc module`](light_arc) provides the [`LightArc`](light_arc::LightArc) type.
-//! - The [`OrengineInstant`] that is a monotone clock that weights 8 bytes on Unix-like systems.
+//! - The [`cycles module`](cycles) provides a portable monotonic cycle counter.
+//! - The [`OrengineInstant`] that is a monotone clock that weights 8 bytes on Unix-like systems
+//!   and Windows, and its wall-clock companion [`OrengineSystemTime`].
 //! - The [`ArrayQueue`] that is an array-based queue implementation.
 //! - The [`VecQueue`] that is a vector-based queue implementation.
+//! - The [`Queue`] trait that is implemented by both queues and the [`move_between`] helper.
 //! - The [`numa module`](numa) provides sufficient utilities for working with NUMA nodes.
+//! - The `test_clock` module allows overriding the clock of [`OrengineInstant`] in tests
+//!   (with the `test_clock` feature).
 //! - The [`treap module`](treap) provides the [`Treap`](treap::Treap) — a randomized BST
 //!   with subtree-augmented filtering for efficient filtered min/max queries.
+//! - The [`watchdog module`](watchdog) provides the [`Watchdog`](watchdog::Watchdog) that
+//!   detects stalled workers.
+//! - The [`stats module`](stats) provides the [`Reservoir`](stats::Reservoir) sampler
+//!   for tail-latency forensics.
+//! - The [`interval_gate module`](interval_gate) provides the
+//!   [`IntervalGate`](interval_gate::IntervalGate) that lets a code pass at most once per interval.
 //! - The [`cheap_random module`](cheap_random) provides fast non-cryptographic xorshift
 //!   PRNGs for `u32` and `u64`, both stateful and thread-local.
+//! - Compile-time assertion macros such as [`const_assert`], [`assert_size_of`]
+//!   and [`assert_align_of`].
 //! - Configuration macros that are used to right compile the program based on the target platform
 //!   such as [`config_target_pointer_width_64`], [`config_target_pointer_width_32`], and
 //!   [`config_target_pointer_width_16`].
@@ -65,30 +81,62 @@
 extern crate alloc;
 extern crate core;
 
+pub mod aligned;
 mod array_buffer;
 mod array_queue;
+#[cfg(target_arch = "x86_64")]
+pub mod atomic128;
 pub mod backoff;
 pub mod cache_padded;
 pub mod cheap_random;
 mod clear_with;
 mod config_macro;
-pub mod hints;
+mod const_assert;
 #[cfg(not(feature = "no_std"))]
+pub mod cycles;
+pub mod hints;
 mod instant;
+#[cfg(target_has_atomic = "64")]
+pub mod interval_gate;
 pub mod light_arc;
 pub mod numa;
+mod queue;
+mod raw_vec;
 #[cfg(not(feature = "no_std"))]
 pub mod rw_serde;
 mod small_string;
+#[cfg(not(feature = "no_std"))]
+pub mod stats;
+#[cfg(not(feature = "no_std"))]
+mod system_time;
+#[cfg(all(feature = "test_clock", not(feature = "no_std")))]
+pub mod test_clock;
 pub mod treap;
 #[cfg(not(feature = "no_std"))]
 pub mod varint;
 mod vec_queue;
+#[cfg(all(not(feature = "no_std"), target_has_atomic = "64"))]
+pub mod watchdog;
 
-pub use array_buffer::ArrayBuffer;
-pub use array_queue::ArrayQueue;
+pub use array_buffer::{
+    ArrayBuffer, ArrayBufferDrain, ArrayBufferIntoIter, ArrayBufferLen, Batched, BatchedExt,
+};
+pub use array_queue::{ArrayQueue, NotEnoughSpace};
 pub use clear_with::*;
-#[cfg(not(feature = "no_std"))]
-pub use instant::OrengineInstant;
+#[cfg(any(not(feature = "no_std"), target_has_atomic = "64"))]
+pub use instant::AtomicOrengineInstant;
+#[cfg(target_has_atomic = "64")]
+pub use instant::CachedNow;
+#[cfg(feature = "no_std")]
+pub use instant::TickSource;
+pub use instant::{DurationExt, HumanDuration, OrengineInstant};
+pub use queue::*;
 pub use small_string::*;
+#[cfg(not(feature = "no_std"))]
+pub use system_time::OrengineSystemTime;
 pub use vec_queue::VecQueue;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use paste::paste;
+}
@@ -1,38 +1,365 @@
-//! This module provides [`LightArc`].
+//! This module provides [`LightArc`] and [`LightArcSwap`].
+use crate::backoff::Backoff;
+use crate::cache_padded::CachePaddedAtomicUsize;
 use crate::hints::unlikely;
-use alloc::alloc::{dealloc, Layout};
-use alloc::boxed::Box;
+use alloc::alloc::{handle_alloc_error, Layout};
+use core::fmt;
+use core::pin::Pin;
 use core::ptr;
 use core::ptr::NonNull;
-use core::sync::atomic::{AtomicUsize, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+
+#[cfg(feature = "allocator_api2")]
+pub use allocator_api2::alloc::{Allocator, Global};
+#[cfg(not(feature = "allocator_api2"))]
+pub use fallback_alloc::{Allocator, Global};
+
+/// The allocator API that is used when the `allocator_api2` feature is disabled.
+///
+/// It has the same shape as `allocator_api2::alloc`, but only the [`Global`] allocator
+/// implements it.
+#[cfg(not(feature = "allocator_api2"))]
+mod fallback_alloc {
+    use alloc::alloc::Layout;
+    use core::ptr::NonNull;
+
+    mod sealed {
+        pub trait Sealed {}
+    }
+
+    /// The error returned when an allocation fails.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct AllocError;
+
+    /// An allocator of [`LightArc`](super::LightArc)s.
+    ///
+    /// Enable the `allocator_api2` feature to use custom allocators.
+    ///
+    /// # Safety
+    ///
+    /// Allocated memory must be valid until it is deallocated.
+    pub unsafe trait Allocator: sealed::Sealed {
+        /// Allocates memory for the layout.
+        ///
+        /// # Errors
+        ///
+        /// Returns [`AllocError`] if the memory can't be allocated.
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError>;
+
+        /// Deallocates the memory.
+        ///
+        /// # Safety
+        ///
+        /// The memory must be allocated by this allocator with the same layout.
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout);
+    }
+
+    /// The global memory allocator.
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct Global;
+
+    impl sealed::Sealed for Global {}
+
+    unsafe impl Allocator for Global {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            let ptr = NonNull::new(unsafe { alloc::alloc::alloc(layout) }).ok_or(AllocError)?;
+
+            Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            unsafe { alloc::alloc::dealloc(ptr.as_ptr(), layout) };
+        }
+    }
+}
 
 /// An inner type for [`LightArc`].
 #[repr(C)]
-struct LightArcInner<T> {
+struct LightArcInner<T: ?Sized> {
     ref_count: AtomicUsize,
     value: T,
 }
 
+/// The reference count of [`LightArc`]s created by [`LightArc::from_static`].
+///
+/// Such [`LightArc`]s don't change the reference count and are never deallocated.
+const STATIC_REF_COUNT: usize = usize::MAX;
+
+/// A value that can be shared by [`LightArc`]s without reference counting.
+///
+/// It is created in a `static` and converted to [`LightArc`]s with [`LightArc::from_static`].
+/// Cloning and dropping such [`LightArc`]s skip the atomic read-modify-write, so it is
+/// suitable for process-lifetime singletons, such as default configs or empty sentinels.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::light_arc::{LightArc, LightArcStatic};
+///
+/// static DEFAULT_NAME: LightArcStatic<&str> = LightArcStatic::new("anonymous");
+///
+/// fn name(custom: Option<LightArc<&'static str>>) -> LightArc<&'static str> {
+///     custom.unwrap_or_else(|| LightArc::from_static(&DEFAULT_NAME))
+/// }
+///
+/// assert_eq!(*name(None), "anonymous");
+/// assert_eq!(*name(Some(LightArc::new("custom"))), "custom");
+/// ```
+#[repr(transparent)]
+pub struct LightArcStatic<T> {
+    inner: LightArcInner<T>,
+}
+
+impl<T> LightArcStatic<T> {
+    /// Creates a new [`LightArcStatic`] with the provided value.
+    pub const fn new(value: T) -> Self {
+        Self {
+            inner: LightArcInner {
+                ref_count: AtomicUsize::new(STATIC_REF_COUNT),
+                value,
+            },
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for LightArcStatic<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("LightArcStatic")
+            .field(&self.inner.value)
+            .finish()
+    }
+}
+
 /// A light-weight reference-counted pointer to a value.
 ///
 /// This is similar to [`Arc`](std::sync::Arc), but is more lightweight because it contains only a strong count.
 /// Therefore, it can't provide weak references.
+///
+/// For the same reason, it doesn't provide `new_cyclic`: a value can't hold a reference
+/// to its own [`LightArc`] without leaking it. Use [`Arc::new_cyclic`](std::sync::Arc::new_cyclic)
+/// for self-referential values or break the cycle explicitly (for example, by taking
+/// a stored [`LightArc`] out of the value when it is no longer needed).
 #[repr(C)]
-pub struct LightArc<T> {
+pub struct LightArc<T: ?Sized, A: Allocator = Global> {
     inner: NonNull<LightArcInner<T>>,
+    alloc: A,
 }
 
 impl<T> LightArc<T> {
     /// Creates a new [`LightArc`] from the given value.
     pub fn new(value: T) -> Self {
-        let inner = Box::new(LightArcInner {
-            ref_count: AtomicUsize::new(1),
-            value,
-        });
+        Self::new_in(value, Global)
+    }
 
+    /// Creates a new pinned [`LightArc`] from the given value.
+    ///
+    /// The value never moves, because [`LightArc`] doesn't provide mutable access to it
+    /// when it is shared, so `T` doesn't have to be [`Unpin`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::light_arc::LightArc;
+    /// use std::future::Future;
+    /// use std::pin::Pin;
+    ///
+    /// let future: Pin<LightArc<_>> = LightArc::pin(async { 1 });
+    /// let clone = future.clone();
+    ///
+    /// fn assert_future<F: Future>(_: &Pin<LightArc<F>>) {}
+    ///
+    /// assert_future(&clone);
+    /// ```
+    pub fn pin(value: T) -> Pin<Self> {
+        Self::pin_in(value, Global)
+    }
+
+    /// Creates a new [`LightArc`] pointing to the value of the [`LightArcStatic`].
+    ///
+    /// Cloning and dropping the returned [`LightArc`] don't touch the reference count,
+    /// and the value is never dropped.
+    /// [`strong_count`](Self::strong_count) of such [`LightArc`]s returns `usize::MAX`.
+    ///
+    /// Read [`LightArcStatic`] for more details.
+    pub const fn from_static(value: &'static LightArcStatic<T>) -> Self {
         Self {
-            inner: NonNull::from(Box::leak(inner)),
+            inner: unsafe { NonNull::new_unchecked(ptr::from_ref(&value.inner).cast_mut()) },
+            alloc: Global,
+        }
+    }
+}
+
+impl<T, A: Allocator> LightArc<T, A> {
+    /// Creates a new [`LightArc`] from the given value in the provided allocator.
+    ///
+    /// Custom allocators can be used with the `allocator_api2` feature.
+    pub fn new_in(value: T, alloc: A) -> Self {
+        let layout = Layout::new::<LightArcInner<T>>();
+        let inner: NonNull<LightArcInner<T>> = alloc
+            .allocate(layout)
+            .unwrap_or_else(|_| handle_alloc_error(layout))
+            .cast();
+
+        unsafe {
+            inner.write(LightArcInner {
+                ref_count: AtomicUsize::new(1),
+                value,
+            });
+        }
+
+        Self { inner, alloc }
+    }
+
+    /// Creates a new pinned [`LightArc`] from the given value in the provided allocator.
+    ///
+    /// Read [`LightArc::pin`] for more details.
+    pub fn pin_in(value: T, alloc: A) -> Pin<Self> {
+        // The value is never moved out of the allocation while other `LightArc`s point to it,
+        // and `LightArc` doesn't implement `DerefMut`.
+        unsafe { Pin::new_unchecked(Self::new_in(value, alloc)) }
+    }
+
+    /// Returns the inner value if this is the only [`LightArc`] pointing to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same [`LightArc`] back if there are other [`LightArc`]s pointing to the value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::light_arc::LightArc;
+    ///
+    /// let arc = LightArc::new(1);
+    /// let clone = arc.clone();
+    /// let arc = arc.try_unwrap().unwrap_err();
+    ///
+    /// drop(clone);
+    ///
+    /// assert_eq!(arc.try_unwrap().ok(), Some(1));
+    /// ```
+    pub fn try_unwrap(self) -> Result<T, Self> {
+        if self
+            .inner()
+            .ref_count
+            .compare_exchange(1, 0, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            return Err(self);
         }
+
+        Ok(unsafe { self.take_and_dealloc() })
+    }
+
+    /// Returns the inner value if this is the last [`LightArc`] pointing to it.
+    ///
+    /// Unlike [`try_unwrap`](Self::try_unwrap), it always decrements the reference count,
+    /// so if several [`LightArc`]s call it concurrently, exactly one of them gets the value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::light_arc::LightArc;
+    ///
+    /// let arc = LightArc::new(1);
+    /// let clone = arc.clone();
+    ///
+    /// assert_eq!(arc.into_inner(), None);
+    /// assert_eq!(clone.into_inner(), Some(1));
+    /// ```
+    pub fn into_inner(self) -> Option<T> {
+        if self.is_static() || self.inner().ref_count.fetch_sub(1, Ordering::Release) != 1 {
+            core::mem::forget(self);
+
+            return None;
+        }
+
+        core::sync::atomic::fence(Ordering::Acquire);
+
+        Some(unsafe { self.take_and_dealloc() })
+    }
+
+    /// Reads the value and deallocates the inner without dropping the value.
+    ///
+    /// # Safety
+    ///
+    /// This function must only be called when the reference count is 0.
+    unsafe fn take_and_dealloc(self) -> T {
+        let this = core::mem::ManuallyDrop::new(self);
+
+        unsafe {
+            let value = ptr::read(&raw const this.inner.as_ref().value);
+            let alloc = ptr::read(&raw const this.alloc);
+
+            alloc.deallocate(this.inner.cast(), Layout::new::<LightArcInner<T>>());
+
+            value
+        }
+    }
+}
+
+impl<T: ?Sized, A: Allocator> LightArc<T, A> {
+    /// Returns a mutable reference to the value if this is the only [`LightArc`] pointing to it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::light_arc::LightArc;
+    ///
+    /// let mut arc = LightArc::new(1);
+    ///
+    /// *arc.get_mut().unwrap() += 1;
+    ///
+    /// let clone = arc.clone();
+    ///
+    /// assert!(arc.get_mut().is_none());
+    /// assert_eq!(*clone, 2);
+    /// ```
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        if self.inner().ref_count.load(Ordering::Acquire) == 1 {
+            Some(unsafe { &mut self.inner.as_mut().value })
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if both [`LightArc`]s point to the same value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::light_arc::LightArc;
+    ///
+    /// let arc = LightArc::new(1);
+    ///
+    /// assert!(LightArc::ptr_eq(&arc, &arc.clone()));
+    /// assert!(!LightArc::ptr_eq(&arc, &LightArc::new(1)));
+    /// ```
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        ptr::addr_eq(this.inner.as_ptr(), other.inner.as_ptr())
+    }
+
+    /// Returns the number of [`LightArc`]s pointing to the value.
+    ///
+    /// Other threads can change it concurrently, so it is suitable only
+    /// for debug assertions and heuristics.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::light_arc::LightArc;
+    ///
+    /// let arc = LightArc::new(1);
+    /// let clone = arc.clone();
+    ///
+    /// assert_eq!(arc.strong_count(), 2);
+    ///
+    /// drop(clone);
+    ///
+    /// assert_eq!(arc.strong_count(), 1);
+    /// ```
+    pub fn strong_count(&self) -> usize {
+        self.inner().ref_count.load(Ordering::Relaxed)
     }
 
     /// Returns a reference to the inner value.
@@ -40,6 +367,12 @@ impl<T> LightArc<T> {
         unsafe { self.inner.as_ref() }
     }
 
+    /// Returns `true` if the [`LightArc`] is created by [`LightArc::from_static`].
+    #[inline]
+    fn is_static(&self) -> bool {
+        self.inner().ref_count.load(Ordering::Relaxed) == STATIC_REF_COUNT
+    }
+
     /// Drops and deallocates the inner value.
     ///
     /// # Safety
@@ -52,33 +385,185 @@ impl<T> LightArc<T> {
         unsafe {
             ptr::drop_in_place(&raw mut self.inner.as_mut().value);
 
-            dealloc(
-                self.inner.as_ptr().cast(),
-                Layout::new::<LightArcInner<T>>(),
-            );
+            self.alloc
+                .deallocate(self.inner.cast(), Layout::for_value(self.inner.as_ref()));
+        }
+    }
+
+    /// Returns a reference to the allocator of the [`LightArc`].
+    pub fn allocator(this: &Self) -> &A {
+        &this.alloc
+    }
+}
+
+impl<T: ?Sized> LightArc<T> {
+    /// Consumes the [`LightArc`] and returns a raw pointer to the value
+    /// without decrementing the reference count.
+    ///
+    /// The pointer can be converted back with [`from_raw`](Self::from_raw).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::light_arc::LightArc;
+    ///
+    /// let ptr = LightArc::into_raw(LightArc::new(1));
+    ///
+    /// assert_eq!(unsafe { *ptr }, 1);
+    ///
+    /// let arc = unsafe { LightArc::from_raw(ptr) };
+    ///
+    /// assert_eq!(*arc, 1);
+    /// ```
+    pub fn into_raw(this: Self) -> *const T {
+        let this = core::mem::ManuallyDrop::new(this);
+
+        unsafe { &raw const (*this.inner.as_ptr()).value }
+    }
+
+    /// Creates a [`LightArc`] from a pointer returned by [`into_raw`](Self::into_raw).
+    ///
+    /// It takes over one reference count.
+    ///
+    /// # Safety
+    ///
+    /// The pointer must be returned by [`into_raw`](Self::into_raw) for the same `T`,
+    /// and the reference count must be greater than 0.
+    pub unsafe fn from_raw(ptr: *const T) -> Self {
+        // `LightArcInner` is `repr(C)`, so the offset depends only on the alignment of the value.
+        let offset = Layout::new::<AtomicUsize>()
+            .extend(Layout::for_value(unsafe { &*ptr }))
+            .unwrap_or_else(|_| unreachable!("the layout is valid, because the value exists"))
+            .1;
+
+        Self {
+            inner: unsafe {
+                NonNull::new_unchecked(ptr.byte_sub(offset).cast_mut() as *mut LightArcInner<T>)
+            },
+            alloc: Global,
+        }
+    }
+
+    /// Increments the reference count of the value behind a pointer
+    /// returned by [`into_raw`](Self::into_raw).
+    ///
+    /// # Safety
+    ///
+    /// The pointer must be returned by [`into_raw`](Self::into_raw) for the same `T`,
+    /// and the reference count must be greater than 0.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::light_arc::LightArc;
+    ///
+    /// let ptr = LightArc::into_raw(LightArc::new(1));
+    ///
+    /// unsafe { LightArc::increment_strong_count(ptr) };
+    ///
+    /// let first = unsafe { LightArc::from_raw(ptr) };
+    /// let second = unsafe { LightArc::from_raw(ptr) };
+    ///
+    /// assert_eq!(*first + *second, 2);
+    /// ```
+    pub unsafe fn increment_strong_count(ptr: *const T) {
+        let arc = core::mem::ManuallyDrop::new(unsafe { Self::from_raw(ptr) });
+        let _clone = core::mem::ManuallyDrop::new((*arc).clone());
+    }
+
+    /// Decrements the reference count of the value behind a pointer
+    /// returned by [`into_raw`](Self::into_raw).
+    ///
+    /// It drops the value if the reference count becomes 0.
+    ///
+    /// # Safety
+    ///
+    /// The pointer must be returned by [`into_raw`](Self::into_raw) for the same `T`,
+    /// and the reference count must be greater than 0.
+    pub unsafe fn decrement_strong_count(ptr: *const T) {
+        drop(unsafe { Self::from_raw(ptr) });
+    }
+
+    /// Returns a [`LightArcGuard`] to the value without incrementing the reference count.
+    ///
+    /// The guard borrows the [`LightArc`], so the value can't be dropped while it is alive.
+    pub fn borrow(this: &Self) -> LightArcGuard<'_, T> {
+        LightArcGuard {
+            inner: this.inner,
+            _marker: core::marker::PhantomData,
         }
     }
+
+    /// Calls `f` with a [`LightArcGuard`] to the value behind a pointer
+    /// returned by [`into_raw`](Self::into_raw) without touching the reference count.
+    ///
+    /// It is useful for short critical sections on hot paths, where even the relaxed
+    /// increment and decrement of a shared counter are noticeable.
+    /// The guard can't escape `f`, but it can be upgraded with
+    /// [`LightArcGuard::to_light_arc`] if the value is needed for longer.
+    ///
+    /// # Safety
+    ///
+    /// The pointer must be returned by [`into_raw`](Self::into_raw) for the same `T`,
+    /// and the reference count must stay greater than 0 until `f` returns.
+    /// It usually means that the caller owns the reference count of the pointer
+    /// (or knows its owner) and doesn't give it away inside `f`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::light_arc::LightArc;
+    ///
+    /// let ptr = LightArc::into_raw(LightArc::new(String::from("value")));
+    ///
+    /// let len = unsafe { LightArc::borrow_scope(ptr, |value| value.len()) };
+    /// let upgraded = unsafe { LightArc::borrow_scope(ptr, |value| value.to_light_arc()) };
+    ///
+    /// assert_eq!(len, 5);
+    /// assert_eq!(upgraded.strong_count(), 2);
+    ///
+    /// unsafe { LightArc::decrement_strong_count(ptr) };
+    ///
+    /// assert_eq!(upgraded.strong_count(), 1);
+    /// ```
+    pub unsafe fn borrow_scope<R>(ptr: *const T, f: impl FnOnce(LightArcGuard<'_, T>) -> R) -> R {
+        let arc = core::mem::ManuallyDrop::new(unsafe { Self::from_raw(ptr) });
+
+        f(LightArcGuard {
+            inner: arc.inner,
+            _marker: core::marker::PhantomData,
+        })
+    }
 }
 
-impl<T> Clone for LightArc<T> {
+impl<T: ?Sized, A: Allocator + Clone> Clone for LightArc<T, A> {
     fn clone(&self) -> Self {
-        let count = self.inner().ref_count.fetch_add(1, Ordering::Relaxed);
+        if !self.is_static() {
+            let count = self.inner().ref_count.fetch_add(1, Ordering::Relaxed);
 
-        debug_assert!(count > 0, "use after free");
+            debug_assert!(count > 0, "use after free");
+        }
 
-        Self { inner: self.inner }
+        Self {
+            inner: self.inner,
+            alloc: self.alloc.clone(),
+        }
     }
 }
 
-impl<T> Drop for LightArc<T> {
+impl<T: ?Sized, A: Allocator> Drop for LightArc<T, A> {
     fn drop(&mut self) {
+        if self.is_static() {
+            return;
+        }
+
         if unlikely(self.inner().ref_count.fetch_sub(1, Ordering::Release) == 1) {
             unsafe { self.drop_slow() };
         }
     }
 }
 
-impl<T> core::ops::Deref for LightArc<T> {
+impl<T: ?Sized, A: Allocator> core::ops::Deref for LightArc<T, A> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
@@ -86,5 +571,417 @@ impl<T> core::ops::Deref for LightArc<T> {
     }
 }
 
-unsafe impl<T: Send + Sync> Send for LightArc<T> {}
-unsafe impl<T: Send + Sync> Sync for LightArc<T> {}
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + ?Sized, A: Allocator> serde::Serialize for LightArc<T, A> {
+    /// Serializes the value transparently.
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for LightArc<T> {
+    /// Deserializes the value into a new [`LightArc`].
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self::new)
+    }
+}
+
+// Moving a `LightArc` doesn't move the value.
+impl<T: ?Sized, A: Allocator> Unpin for LightArc<T, A> {}
+
+unsafe impl<T: Send + Sync + ?Sized, A: Allocator + Send> Send for LightArc<T, A> {}
+unsafe impl<T: Send + Sync + ?Sized, A: Allocator + Sync> Sync for LightArc<T, A> {}
+
+/// Converts a [`LightArc`] to a [`LightArc`] of an unsized type, such as a trait object or a slice.
+///
+/// It is a stable replacement for the unsized coercion that `Arc` has.
+/// It compiles only if the value can be coerced to the target type.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::coerce_light_arc;
+/// use orengine_utils::light_arc::LightArc;
+/// use std::fmt::Display;
+///
+/// let handlers: [LightArc<dyn Display>; 2] = [
+///     coerce_light_arc!(LightArc::new(1) => dyn Display),
+///     coerce_light_arc!(LightArc::new("two") => dyn Display),
+/// ];
+/// let slice: LightArc<[u8]> = coerce_light_arc!(LightArc::new([1, 2, 3]) => [u8]);
+///
+/// assert_eq!(handlers[1].to_string(), "two");
+/// assert_eq!(slice.len(), 3);
+/// ```
+#[macro_export]
+macro_rules! coerce_light_arc {
+    ($arc:expr => $ty:ty) => {{
+        // It is a coercion and not a cast, so only unsizing is allowed.
+        let ptr = $crate::light_arc::LightArc::into_raw($arc);
+        let ptr: *const $ty = ptr;
+
+        unsafe { $crate::light_arc::LightArc::<$ty>::from_raw(ptr) }
+    }};
+}
+
+/// A temporary reference to the value of a [`LightArc`] that doesn't own a reference count.
+///
+/// It is created by [`LightArc::borrow`] and [`LightArc::borrow_scope`].
+/// Unlike `&LightArc<T>`, it is a single pointer that can be copied and passed around freely,
+/// and unlike `&T`, it can be upgraded to a [`LightArc`] with [`to_light_arc`](Self::to_light_arc).
+pub struct LightArcGuard<'a, T: ?Sized> {
+    inner: NonNull<LightArcInner<T>>,
+    _marker: core::marker::PhantomData<&'a T>,
+}
+
+impl<T: ?Sized> LightArcGuard<'_, T> {
+    /// Returns a new [`LightArc`] pointing to the value, incrementing the reference count.
+    pub fn to_light_arc(self) -> LightArc<T> {
+        let arc = core::mem::ManuallyDrop::new(LightArc {
+            inner: self.inner,
+            alloc: Global,
+        });
+
+        (*arc).clone()
+    }
+}
+
+impl<T: ?Sized> Clone for LightArcGuard<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for LightArcGuard<'_, T> {}
+
+impl<T: ?Sized> core::ops::Deref for LightArcGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &self.inner.as_ref().value }
+    }
+}
+
+impl<T: fmt::Debug + ?Sized> fmt::Debug for LightArcGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+// A guard can be upgraded to a `LightArc`, so it has the same bounds.
+unsafe impl<T: Send + Sync + ?Sized> Send for LightArcGuard<'_, T> {}
+unsafe impl<T: Send + Sync + ?Sized> Sync for LightArcGuard<'_, T> {}
+
+/// A cell with a [`LightArc`] that can be loaded and replaced atomically.
+///
+/// It is the standard way to share rarely-updated snapshots (such as configs) between threads:
+/// readers [`load`](Self::load) the current [`LightArc`] wait-free,
+/// and writers [`store`](Self::store) or [`compare_and_swap`](Self::compare_and_swap) a new one.
+///
+/// Writers exclude each other and wait until all readers that could see the replaced
+/// [`LightArc`] finish loading it, so writes are much slower than reads.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::light_arc::{LightArc, LightArcSwap};
+///
+/// let config = LightArcSwap::new(LightArc::new("v1"));
+/// let snapshot = config.load();
+///
+/// config.store(LightArc::new("v2"));
+///
+/// assert_eq!(*snapshot, "v1");
+/// assert_eq!(*config.load(), "v2");
+/// ```
+pub struct LightArcSwap<T> {
+    ptr: AtomicPtr<LightArcInner<T>>,
+    epoch: AtomicUsize,
+    readers: [CachePaddedAtomicUsize; 2],
+    is_writer_locked: AtomicBool,
+}
+
+impl<T> LightArcSwap<T> {
+    /// Creates a new [`LightArcSwap`] with the provided [`LightArc`].
+    pub fn new(arc: LightArc<T>) -> Self {
+        Self {
+            ptr: AtomicPtr::new(Self::into_inner_ptr(arc)),
+            epoch: AtomicUsize::new(0),
+            readers: [
+                CachePaddedAtomicUsize::new(0),
+                CachePaddedAtomicUsize::new(0),
+            ],
+            is_writer_locked: AtomicBool::new(false),
+        }
+    }
+
+    /// Converts the [`LightArc`] to a pointer to its inner without decrementing the count.
+    fn into_inner_ptr(arc: LightArc<T>) -> *mut LightArcInner<T> {
+        core::mem::ManuallyDrop::new(arc).inner.as_ptr()
+    }
+
+    /// Returns the current [`LightArc`].
+    ///
+    /// It is wait-free.
+    #[inline]
+    pub fn load(&self) -> LightArc<T> {
+        let readers = &self.readers[self.epoch.load(Ordering::SeqCst) & 1];
+
+        readers.fetch_add(1, Ordering::SeqCst);
+
+        // Writers don't drop the loaded pointer until the readers counter is decremented.
+        let arc = core::mem::ManuallyDrop::new(LightArc {
+            inner: unsafe { NonNull::new_unchecked(self.ptr.load(Ordering::SeqCst)) },
+            alloc: Global,
+        });
+        let res = (*arc).clone();
+
+        readers.fetch_sub(1, Ordering::Release);
+
+        res
+    }
+
+    /// Replaces the current [`LightArc`] with the provided one.
+    pub fn store(&self, arc: LightArc<T>) {
+        drop(self.swap(arc));
+    }
+
+    /// Replaces the current [`LightArc`] with the provided one and returns the previous one.
+    pub fn swap(&self, arc: LightArc<T>) -> LightArc<T> {
+        self.write(|_| true, arc)
+            .unwrap_or_else(|_| unreachable!("the condition is always true"))
+    }
+
+    /// Replaces the current [`LightArc`] with `new` if it points to the same value as `current`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `new` back if the current [`LightArc`] doesn't point to the same value as `current`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::light_arc::{LightArc, LightArcSwap};
+    ///
+    /// let swap = LightArcSwap::new(LightArc::new(1));
+    /// let current = swap.load();
+    ///
+    /// assert!(swap.compare_and_swap(&current, LightArc::new(2)).is_ok());
+    /// assert!(swap.compare_and_swap(&current, LightArc::new(3)).is_err());
+    /// assert_eq!(*swap.load(), 2);
+    /// ```
+    pub fn compare_and_swap(
+        &self,
+        current: &LightArc<T>,
+        new: LightArc<T>,
+    ) -> Result<LightArc<T>, LightArc<T>> {
+        self.write(|ptr| ptr == current.inner.as_ptr(), new)
+    }
+
+    /// Replaces the current [`LightArc`] with `arc` if `cond` returns `true` for the current pointer.
+    fn write(
+        &self,
+        cond: impl FnOnce(*mut LightArcInner<T>) -> bool,
+        arc: LightArc<T>,
+    ) -> Result<LightArc<T>, LightArc<T>> {
+        let backoff = Backoff::new();
+
+        while self
+            .is_writer_locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            backoff.snooze();
+        }
+
+        let prev = self.ptr.load(Ordering::Relaxed);
+
+        if !cond(prev) {
+            self.is_writer_locked.store(false, Ordering::Release);
+
+            return Err(arc);
+        }
+
+        self.ptr.swap(Self::into_inner_ptr(arc), Ordering::SeqCst);
+
+        // Readers that could load the previous pointer incremented one of the counters before
+        // the swap. Each counter is awaited after switching new readers to the other one,
+        // so new readers can't starve the writer.
+        for _ in 0..2 {
+            let epoch = self.epoch.fetch_add(1, Ordering::SeqCst);
+            let readers = &self.readers[epoch & 1];
+
+            backoff.reset();
+
+            while readers.load(Ordering::SeqCst) != 0 {
+                backoff.snooze();
+            }
+        }
+
+        self.is_writer_locked.store(false, Ordering::Release);
+
+        Ok(LightArc {
+            inner: unsafe { NonNull::new_unchecked(prev) },
+            alloc: Global,
+        })
+    }
+
+    /// Returns the current [`LightArc`] consuming the [`LightArcSwap`].
+    pub fn into_inner(self) -> LightArc<T> {
+        let this = core::mem::ManuallyDrop::new(self);
+
+        LightArc {
+            inner: unsafe { NonNull::new_unchecked(this.ptr.load(Ordering::Relaxed)) },
+            alloc: Global,
+        }
+    }
+}
+
+impl<T: Default> Default for LightArcSwap<T> {
+    fn default() -> Self {
+        Self::new(LightArc::new(T::default()))
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for LightArcSwap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("LightArcSwap").field(&*self.load()).finish()
+    }
+}
+
+impl<T> Drop for LightArcSwap<T> {
+    fn drop(&mut self) {
+        drop(LightArc {
+            inner: unsafe { NonNull::new_unchecked(*self.ptr.get_mut()) },
+            alloc: Global,
+        });
+    }
+}
+
+unsafe impl<T: Send + Sync> Send for LightArcSwap<T> {}
+unsafe impl<T: Send + Sync> Sync for LightArcSwap<T> {}
+
+#[cfg(all(test, not(feature = "no_std")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_light_arc_from_static() {
+        static VALUE: LightArcStatic<usize> = LightArcStatic::new(42);
+
+        let arc = LightArc::from_static(&VALUE);
+        let swap = LightArcSwap::new(arc.clone());
+
+        for _ in 0..10 {
+            drop(swap.load());
+        }
+
+        assert_eq!(arc.strong_count(), STATIC_REF_COUNT);
+        assert_eq!(swap.swap(LightArc::new(1)).into_inner(), None);
+
+        let arc = arc
+            .try_unwrap()
+            .expect_err("static values can't be unwrapped");
+
+        assert_eq!(*arc, 42);
+        assert_eq!(arc.into_inner(), None);
+        assert_eq!(*LightArc::from_static(&VALUE), 42);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_light_arc_serde() {
+        use crate::rw_serde::{RWDeserializer, RWSerializer};
+        use serde::{Deserialize, Serialize};
+        use std::io::Cursor;
+
+        let mut ser = RWSerializer::new(Vec::new());
+
+        LightArc::new(String::from("payload"))
+            .serialize(&mut ser)
+            .unwrap();
+
+        let restored = LightArc::<String>::deserialize(&mut RWDeserializer::new(Cursor::new(
+            ser.into_inner(),
+        )))
+        .unwrap();
+
+        assert_eq!(*restored, "payload");
+    }
+
+    #[test]
+    #[cfg(feature = "allocator_api2")]
+    fn test_light_arc_custom_allocator() {
+        use allocator_api2::alloc::AllocError;
+
+        #[derive(Clone, Copy)]
+        struct Counting<'counter>(&'counter AtomicUsize);
+
+        unsafe impl Allocator for Counting<'_> {
+            fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+                self.0.fetch_add(1, Ordering::Relaxed);
+
+                Global.allocate(layout)
+            }
+
+            unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+                self.0.fetch_sub(1, Ordering::Relaxed);
+
+                unsafe { Global.deallocate(ptr, layout) };
+            }
+        }
+
+        let allocated = AtomicUsize::new(0);
+        let arc = LightArc::new_in(String::from("value"), Counting(&allocated));
+        let clone = arc.clone();
+
+        assert_eq!(allocated.load(Ordering::Relaxed), 1);
+
+        drop(arc);
+
+        assert_eq!(clone.try_unwrap().ok().as_deref(), Some("value"));
+        assert_eq!(allocated.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_light_arc_swap_concurrent_loads_and_stores() {
+        static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+        struct Snapshot(usize);
+
+        impl Drop for Snapshot {
+            fn drop(&mut self) {
+                DROPPED.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let swap = LightArcSwap::new(LightArc::new(Snapshot(0)));
+
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    let mut last = 0;
+
+                    for _ in 0..10_000 {
+                        let current = swap.load().0;
+
+                        assert!(current >= last);
+
+                        last = current;
+                    }
+                });
+            }
+
+            scope.spawn(|| {
+                for i in 1..=1000 {
+                    swap.store(LightArc::new(Snapshot(i)));
+                }
+            });
+        });
+
+        assert_eq!(DROPPED.load(Ordering::Relaxed), 1000);
+        assert_eq!(swap.into_inner().try_unwrap().ok().map(|s| s.0), Some(1000));
+        assert_eq!(DROPPED.load(Ordering::Relaxed), 1001);
+    }
+}
@@ -0,0 +1,339 @@
+use super::{invalidate_current_node_cache, is_online_node};
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use std::io;
+
+/// A guard returned by [`bind_current_thread_to_node`].
+///
+/// It restores the previous memory policy and CPU affinity of the thread when it is dropped.
+///
+/// It is `!Send`, because the binding belongs to the thread that has created it.
+#[must_use = "the thread is unbound when the guard is dropped"]
+pub struct NumaNodeBindGuard {
+    #[cfg(all(target_os = "linux", not(miri)))]
+    prev: linux::ThreadPolicy,
+    #[cfg(all(windows, not(miri)))]
+    prev: Option<windows_sys::Win32::System::SystemInformation::GROUP_AFFINITY>,
+    node: usize,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl NumaNodeBindGuard {
+    /// Returns the NUMA node the thread is bound to.
+    pub fn node(&self) -> usize {
+        self.node
+    }
+}
+
+impl Drop for NumaNodeBindGuard {
+    fn drop(&mut self) {
+        invalidate_current_node_cache();
+
+        #[cfg(all(target_os = "linux", not(miri)))]
+        {
+            // The previous policy was valid for this thread, so it can fail only
+            // if the thread has lost the permissions, and then nothing can be done.
+            let _ = self.prev.apply();
+        }
+
+        #[cfg(all(windows, not(miri)))]
+        {
+            if let Some(prev) = &self.prev {
+                crate::numa::windows::set_current_thread_affinity(prev);
+            }
+        }
+    }
+}
+
+/// Binds the current thread to the provided NUMA node until the returned guard is dropped.
+///
+/// While the guard is alive, the thread runs only on the CPUs of the node, and
+/// its memory is allocated only from the node.
+///
+/// On Linux, it uses `set_mempolicy` with `MPOL_BIND` and `sched_setaffinity`.
+/// If the node has no CPUs (a memory-only node), only the memory policy is changed.
+///
+/// On Windows, it sets the processor group affinity of the thread to the CPUs of the node,
+/// and the memory is allocated from the node of the CPU by default.
+///
+/// On other platforms, it is best-effort: it only checks that the node exists.
+///
+/// # Errors
+///
+/// Returns an error of the [`InvalidInput`](io::ErrorKind::InvalidInput) kind
+/// if the node is not online, or the OS error if the binding fails.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::numa::{bind_current_thread_to_node, get_current_thread_numa_node};
+///
+/// let node = get_current_thread_numa_node();
+///
+/// let guard = bind_current_thread_to_node(node).unwrap();
+///
+/// assert_eq!(guard.node(), node);
+/// // Run the worker loop here; the thread stays on its node
+///
+/// drop(guard); // The previous policy is restored
+/// ```
+pub fn bind_current_thread_to_node(node: usize) -> io::Result<NumaNodeBindGuard> {
+    if !is_online_node(node) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the NUMA node is not online",
+        ));
+    }
+
+    invalidate_current_node_cache();
+
+    #[cfg(all(target_os = "linux", not(miri)))]
+    {
+        let prev = linux::ThreadPolicy::current()?;
+
+        if let Err(err) = linux::ThreadPolicy::bound_to(node).apply() {
+            let _ = prev.apply();
+
+            return Err(err);
+        }
+
+        Ok(NumaNodeBindGuard {
+            prev,
+            node,
+            _not_send: PhantomData,
+        })
+    }
+
+    #[cfg(all(windows, not(miri)))]
+    {
+        use crate::numa::windows;
+
+        let prev = match windows::node_affinity(node) {
+            Some(affinity) if affinity.Mask != 0 => Some(
+                windows::set_current_thread_affinity(&affinity)
+                    .ok_or_else(io::Error::last_os_error)?,
+            ),
+            _ => None,
+        };
+
+        Ok(NumaNodeBindGuard {
+            prev,
+            node,
+            _not_send: PhantomData,
+        })
+    }
+
+    #[cfg(any(not(any(target_os = "linux", windows)), miri))]
+    {
+        Ok(NumaNodeBindGuard {
+            node,
+            _not_send: PhantomData,
+        })
+    }
+}
+
+/// Initializes the memory with the values returned by the provided function
+/// for each index while the current thread is bound to the NUMA node.
+///
+/// The memory is placed on the node of the thread that touches it first,
+/// so it makes the node-local memory without binding it. It works only for memory
+/// that is not touched yet, such as the spare capacity of a new `Vec`.
+///
+/// The thread is bound with [`bind_current_thread_to_node`], and the previous binding
+/// is restored before returning. If the function panics, initialized values are leaked.
+///
+/// # Errors
+///
+/// Returns an error if the thread can't be bound to the node.
+/// Read [`bind_current_thread_to_node`] for details.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::numa::{first_touch_init, get_current_thread_numa_node};
+///
+/// let node = get_current_thread_numa_node();
+/// let mut arena = Vec::with_capacity(1 << 16);
+///
+/// let len = first_touch_init(arena.spare_capacity_mut(), node, |_| 0u64).unwrap().len();
+///
+/// unsafe { arena.set_len(len) };
+///
+/// assert_eq!(arena.len(), 1 << 16);
+/// ```
+pub fn first_touch_init<T>(
+    memory: &mut [MaybeUninit<T>],
+    node: usize,
+    mut f: impl FnMut(usize) -> T,
+) -> io::Result<&mut [T]> {
+    let _guard = bind_current_thread_to_node(node)?;
+
+    for (i, slot) in memory.iter_mut().enumerate() {
+        slot.write(f(i));
+    }
+
+    Ok(unsafe { &mut *(core::ptr::from_mut(memory) as *mut [T]) })
+}
+
+#[cfg(all(target_os = "linux", not(miri)))]
+mod linux {
+    use crate::numa::cpus_of_node;
+    use crate::numa::node_mask::NodeMask;
+    use core::ffi::{c_int, c_ulong, c_void};
+    use core::{mem, ptr};
+    use std::io;
+
+    /// The memory policy and the CPU affinity of a thread.
+    pub(super) struct ThreadPolicy {
+        mode: c_int,
+        nodes: NodeMask,
+        cpus: Option<libc::cpu_set_t>,
+    }
+
+    impl ThreadPolicy {
+        /// Returns the policy of the current thread.
+        pub(super) fn current() -> io::Result<Self> {
+            let mut mode: c_int = 0;
+            let mut nodes = NodeMask::empty();
+            let ret = unsafe {
+                libc::syscall(
+                    libc::SYS_get_mempolicy,
+                    &raw mut mode,
+                    nodes.as_mut_ptr(),
+                    NodeMask::MAX_NODE,
+                    ptr::null::<c_void>(),
+                    0 as c_ulong,
+                )
+            };
+
+            if ret < 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            let mut cpus: libc::cpu_set_t = unsafe { mem::zeroed() };
+            let ret =
+                unsafe { libc::sched_getaffinity(0, size_of::<libc::cpu_set_t>(), &raw mut cpus) };
+
+            if ret < 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            Ok(Self {
+                mode,
+                nodes,
+                cpus: Some(cpus),
+            })
+        }
+
+        /// Returns the policy that binds a thread to the provided node.
+        pub(super) fn bound_to(node: usize) -> Self {
+            Self {
+                mode: libc::MPOL_BIND,
+                nodes: NodeMask::of_node(node),
+                cpus: cpu_set_of_node(node),
+            }
+        }
+
+        /// Applies the policy to the current thread.
+        pub(super) fn apply(&self) -> io::Result<()> {
+            let ret = unsafe {
+                libc::syscall(
+                    libc::SYS_set_mempolicy,
+                    self.mode,
+                    self.nodes.as_ptr(),
+                    NodeMask::MAX_NODE,
+                )
+            };
+
+            if ret < 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            if let Some(cpus) = &self.cpus {
+                let ret = unsafe { libc::sched_setaffinity(0, size_of::<libc::cpu_set_t>(), cpus) };
+
+                if ret < 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Returns the CPU set of the node or `None` if the node has no CPUs.
+    fn cpu_set_of_node(node: usize) -> Option<libc::cpu_set_t> {
+        let mut cpus: libc::cpu_set_t = unsafe { mem::zeroed() };
+        let mut is_empty = true;
+
+        for cpu in cpus_of_node(node) {
+            if cpu < libc::CPU_SETSIZE as usize {
+                unsafe { libc::CPU_SET(cpu, &mut cpus) };
+
+                is_empty = false;
+            }
+        }
+
+        if is_empty {
+            None
+        } else {
+            Some(cpus)
+        }
+    }
+}
+
+#[cfg(all(test, not(miri)))]
+mod tests {
+    use super::*;
+    use crate::numa::get_current_thread_numa_node;
+
+    #[test]
+    fn test_first_touch_init() {
+        let node = get_current_thread_numa_node();
+        let mut memory = alloc::vec::Vec::with_capacity(10_000);
+        let initialized = match first_touch_init(memory.spare_capacity_mut(), node, |i| i * 2) {
+            Ok(initialized) => initialized,
+            // Containers can forbid changing the memory policy
+            Err(err) if err.kind() == io::ErrorKind::PermissionDenied => return,
+            Err(err) => panic!("failed to bind the thread: {err}"),
+        };
+
+        assert_eq!(initialized[9_999], 19_998);
+
+        assert_eq!(
+            crate::numa::node_of_memory(initialized.as_ptr().cast()).unwrap(),
+            node
+        );
+
+        assert_eq!(
+            first_touch_init(memory.spare_capacity_mut(), usize::MAX, |i| i)
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn test_bind_current_thread_to_node() {
+        assert_eq!(
+            bind_current_thread_to_node(usize::MAX)
+                .err()
+                .unwrap()
+                .kind(),
+            io::ErrorKind::InvalidInput
+        );
+
+        let node = get_current_thread_numa_node();
+        let guard = match bind_current_thread_to_node(node) {
+            Ok(guard) => guard,
+            // Containers can forbid changing the memory policy
+            Err(err) if err.kind() == io::ErrorKind::PermissionDenied => return,
+            Err(err) => panic!("failed to bind the thread: {err}"),
+        };
+
+        assert_eq!(guard.node(), node);
+        assert_eq!(get_current_thread_numa_node(), node);
+
+        drop(guard);
+    }
+}
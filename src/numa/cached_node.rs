@@ -0,0 +1,82 @@
+use super::get_current_thread_numa_node;
+use core::cell::Cell;
+
+/// The number of calls of [`current_node_cached`] after which
+/// the cached NUMA node is refreshed.
+pub const CURRENT_NODE_REFRESH_INTERVAL: u32 = 1024;
+
+thread_local! {
+    /// The cached NUMA node and the number of calls left before the refresh.
+    static CACHED_NODE: Cell<(usize, u32)> = const { Cell::new((0, 0)) };
+}
+
+/// Returns the NUMA node of the current thread, cached in a thread-local.
+///
+/// [`get_current_thread_numa_node`] is a syscall on Linux, which is too expensive
+/// for per-operation sharding decisions. This function calls it only once
+/// per [`CURRENT_NODE_REFRESH_INTERVAL`] calls, so the result can be stale
+/// for a while after the OS moves the thread to another node.
+///
+/// The cache is invalidated by [`invalidate_current_node_cache`] and when
+/// the thread is bound with [`bind_current_thread_to_node`](crate::numa::bind_current_thread_to_node).
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::numa::{current_node_cached, DataPerNUMANodeManager};
+///
+/// static SHARDS: DataPerNUMANodeManager<usize> = DataPerNUMANodeManager::from_arr([0; 64]);
+///
+/// let shard = SHARDS.get_ref_by_node(current_node_cached());
+/// # let _ = shard;
+/// ```
+pub fn current_node_cached() -> usize {
+    CACHED_NODE.with(|cached| {
+        let (node, calls_left) = cached.get();
+
+        if calls_left > 0 {
+            cached.set((node, calls_left - 1));
+
+            return node;
+        }
+
+        let node = get_current_thread_numa_node();
+
+        cached.set((node, CURRENT_NODE_REFRESH_INTERVAL - 1));
+
+        node
+    })
+}
+
+/// Invalidates the NUMA node cached by [`current_node_cached`] for the current thread,
+/// so the next call refreshes it.
+///
+/// Call it after changing the affinity of the thread by other means than
+/// [`bind_current_thread_to_node`](crate::numa::bind_current_thread_to_node).
+pub fn invalidate_current_node_cache() {
+    CACHED_NODE.with(|cached| cached.set((0, 0)));
+}
+
+#[cfg(all(test, not(miri)))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_current_node_cached() {
+        let node = get_current_thread_numa_node();
+
+        for _ in 0..CURRENT_NODE_REFRESH_INTERVAL * 2 {
+            assert_eq!(current_node_cached(), node);
+        }
+
+        CACHED_NODE.with(|cached| cached.set((usize::MAX, 1)));
+
+        assert_eq!(current_node_cached(), usize::MAX);
+        assert_eq!(current_node_cached(), node);
+
+        CACHED_NODE.with(|cached| cached.set((usize::MAX, 10)));
+        invalidate_current_node_cache();
+
+        assert_eq!(current_node_cached(), node);
+    }
+}
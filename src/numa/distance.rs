@@ -0,0 +1,176 @@
+use super::online_nodes;
+use alloc::vec::Vec;
+use std::sync::OnceLock;
+
+/// The distance from a NUMA node to itself.
+pub const LOCAL_NUMA_DISTANCE: u8 = 10;
+
+/// The distance between different NUMA nodes that is used when the real distance is unknown.
+pub const REMOTE_NUMA_DISTANCE: u8 = 20;
+
+/// The matrix of relative distances between online NUMA nodes.
+///
+/// The distance from a node to itself is [`LOCAL_NUMA_DISTANCE`], and larger distances mean
+/// slower memory access. On Linux, they are read from sysfs,
+/// otherwise all remote nodes are at [`REMOTE_NUMA_DISTANCE`].
+///
+/// Use [`distance_matrix`] to get the cached matrix.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::numa::{distance_matrix, get_current_thread_numa_node, LOCAL_NUMA_DISTANCE};
+///
+/// let node = get_current_thread_numa_node();
+/// let matrix = distance_matrix();
+///
+/// assert_eq!(matrix.distance(node, node), Some(LOCAL_NUMA_DISTANCE));
+///
+/// for victim in matrix.nodes_by_distance(node) {
+///     // Try to steal from the nearest nodes first
+/// #   let _ = victim;
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumaDistances {
+    nodes: Vec<usize>,
+    distances: Vec<u8>,
+}
+
+impl NumaDistances {
+    /// Builds the matrix from the rows of the provided online nodes.
+    ///
+    /// Rows that are missing or malformed are replaced with default distances.
+    fn from_rows(nodes: Vec<usize>, mut read_row: impl FnMut(usize) -> Option<Vec<u8>>) -> Self {
+        let mut distances = Vec::with_capacity(nodes.len() * nodes.len());
+
+        for &from in &nodes {
+            match read_row(from) {
+                Some(row) if row.len() == nodes.len() => distances.extend(row),
+                _ => distances.extend(nodes.iter().map(|&to| {
+                    if from == to {
+                        LOCAL_NUMA_DISTANCE
+                    } else {
+                        REMOTE_NUMA_DISTANCE
+                    }
+                })),
+            }
+        }
+
+        Self { nodes, distances }
+    }
+
+    /// Reads the matrix of the machine.
+    fn read() -> Self {
+        #[cfg(all(target_os = "linux", not(miri)))]
+        {
+            Self::from_rows(online_nodes(), |node| {
+                let row = std::fs::read_to_string(format!(
+                    "/sys/devices/system/node/node{node}/distance"
+                ))
+                .ok()?;
+
+                row.split_whitespace()
+                    .map(|distance| distance.parse().ok())
+                    .collect()
+            })
+        }
+
+        #[cfg(any(not(target_os = "linux"), miri))]
+        {
+            Self::from_rows(online_nodes(), |_| None)
+        }
+    }
+
+    /// Returns the online NUMA nodes of the matrix in ascending order.
+    pub fn nodes(&self) -> &[usize] {
+        &self.nodes
+    }
+
+    /// Returns the distance between two NUMA nodes or `None` if any of them is not online.
+    pub fn distance(&self, from: usize, to: usize) -> Option<u8> {
+        let from = self.nodes.binary_search(&from).ok()?;
+        let to = self.nodes.binary_search(&to).ok()?;
+
+        Some(self.distances[from * self.nodes.len() + to])
+    }
+
+    /// Returns other online NUMA nodes sorted from the nearest to the farthest from the node.
+    ///
+    /// Nodes at the same distance are sorted by their IDs.
+    /// If the node is not online, it returns an empty vector.
+    pub fn nodes_by_distance(&self, node: usize) -> Vec<usize> {
+        let Ok(from) = self.nodes.binary_search(&node) else {
+            return Vec::new();
+        };
+        let row = &self.distances[from * self.nodes.len()..][..self.nodes.len()];
+        let mut nodes: Vec<usize> = (0..self.nodes.len()).filter(|&to| to != from).collect();
+
+        nodes.sort_by_key(|&to| row[to]);
+
+        nodes.into_iter().map(|to| self.nodes[to]).collect()
+    }
+}
+
+/// Returns the matrix of distances between online NUMA nodes.
+///
+/// It is read once and cached for the lifetime of the process.
+///
+/// Read [`NumaDistances`] for more details.
+pub fn distance_matrix() -> &'static NumaDistances {
+    static MATRIX: OnceLock<NumaDistances> = OnceLock::new();
+
+    MATRIX.get_or_init(NumaDistances::read)
+}
+
+/// Returns the distance between two NUMA nodes or `None` if any of them is not online.
+///
+/// It uses the cached [`distance_matrix`].
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::numa::{distance, LOCAL_NUMA_DISTANCE};
+///
+/// assert_eq!(distance(0, 0), Some(LOCAL_NUMA_DISTANCE));
+/// ```
+pub fn distance(from: usize, to: usize) -> Option<u8> {
+    distance_matrix().distance(from, to)
+}
+
+#[cfg(all(test, not(miri)))]
+mod tests {
+    use super::*;
+    use crate::numa::MAX_NUMA_NODES_SUPPORTED;
+
+    #[test]
+    fn test_distance_matrix() {
+        let matrix = distance_matrix();
+
+        for &node in matrix.nodes() {
+            assert_eq!(distance(node, node), Some(LOCAL_NUMA_DISTANCE));
+            assert!(!matrix.nodes_by_distance(node).contains(&node));
+        }
+
+        assert_eq!(distance(0, MAX_NUMA_NODES_SUPPORTED), None);
+        assert!(matrix
+            .nodes_by_distance(MAX_NUMA_NODES_SUPPORTED)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_sparse_distances() {
+        let matrix = NumaDistances::from_rows(alloc::vec![0, 2, 5], |node| match node {
+            0 => Some(alloc::vec![10, 32, 21]),
+            2 => Some(alloc::vec![32, 10, 21]),
+            _ => None,
+        });
+
+        assert_eq!(matrix.distance(0, 2), Some(32));
+        assert_eq!(matrix.distance(2, 5), Some(21));
+        assert_eq!(matrix.distance(5, 0), Some(REMOTE_NUMA_DISTANCE));
+        assert_eq!(matrix.distance(1, 0), None);
+        assert_eq!(matrix.nodes_by_distance(0), [5, 2]);
+        assert_eq!(matrix.nodes_by_distance(5), [0, 2]);
+    }
+}
@@ -0,0 +1,250 @@
+use super::is_online_node;
+use alloc::alloc::Layout;
+use core::ptr::NonNull;
+use core::{fmt, slice};
+use std::io;
+
+/// The size of a huge page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HugePageSize {
+    /// 2 MiB pages.
+    Mb2,
+    /// 1 GiB pages.
+    Gb1,
+}
+
+impl HugePageSize {
+    /// Returns the size of the page in bytes.
+    pub const fn bytes(self) -> usize {
+        match self {
+            Self::Mb2 => 2 << 20,
+            Self::Gb1 => 1 << 30,
+        }
+    }
+}
+
+/// The pages that back a [`HugePageMapping`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HugePageBacking {
+    /// Reserved huge pages of the requested size (`MAP_HUGETLB`).
+    HugeTlb,
+    /// Transparent huge pages (`MADV_HUGEPAGE`). The kernel uses them when it can,
+    /// and they are always 2 MiB pages on `x86_64`.
+    Transparent,
+    /// Regular pages, because huge pages are unavailable.
+    Regular,
+}
+
+/// Zeroed memory allocated with [`alloc_huge`]. It is deallocated when dropped.
+pub struct HugePageMapping {
+    ptr: NonNull<u8>,
+    len: usize,
+    #[cfg(any(not(target_os = "linux"), miri))]
+    align: usize,
+    backing: HugePageBacking,
+}
+
+impl HugePageMapping {
+    /// Returns a pointer to the start of the mapping.
+    ///
+    /// It is aligned to the size of the requested huge page.
+    pub fn as_ptr(&self) -> NonNull<u8> {
+        self.ptr
+    }
+
+    /// Returns the length of the mapping in bytes. It is a multiple of the size
+    /// of the requested huge page.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the mapping is empty. It is never empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the pages that back the mapping.
+    pub fn backing(&self) -> HugePageBacking {
+        self.backing
+    }
+
+    /// Returns the memory as a byte slice.
+    pub fn as_slice(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// Returns the memory as a mutable byte slice.
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl fmt::Debug for HugePageMapping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HugePageMapping")
+            .field("ptr", &self.ptr)
+            .field("len", &self.len)
+            .field("backing", &self.backing)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for HugePageMapping {
+    fn drop(&mut self) {
+        #[cfg(all(target_os = "linux", not(miri)))]
+        if let Err(err) = super::memory::linux::unmap(self.ptr, self.len) {
+            debug_assert!(false, "failed to unmap the huge pages: {err}");
+        }
+
+        #[cfg(any(not(target_os = "linux"), miri))]
+        unsafe {
+            alloc::alloc::dealloc(
+                self.ptr.as_ptr(),
+                Layout::from_size_align_unchecked(self.len, self.align),
+            );
+        }
+    }
+}
+
+unsafe impl Send for HugePageMapping {}
+unsafe impl Sync for HugePageMapping {}
+
+/// Allocates zeroed memory for the layout backed by huge pages,
+/// optionally bound to the NUMA node.
+///
+/// Huge pages reduce TLB misses for large arrays that are accessed randomly.
+/// The size of the mapping is rounded up to a multiple of the huge page size.
+///
+/// On Linux, it maps reserved huge pages with `MAP_HUGETLB`. If they are not reserved,
+/// it maps memory aligned to the huge page size and advises the kernel to use transparent
+/// huge pages with `MADV_HUGEPAGE`. If the node is provided, the memory is bound to it
+/// with `mbind`. On other platforms, it falls back to the global allocator and ignores the node.
+///
+/// Use [`HugePageMapping::backing`] to check which pages were used.
+///
+/// # Errors
+///
+/// Returns an error of the [`InvalidInput`](io::ErrorKind::InvalidInput) kind
+/// if the node is not online or the alignment of the layout is larger than the huge page,
+/// or the OS error if the allocation fails.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::numa::{alloc_huge, HugePageSize};
+/// use std::alloc::Layout;
+///
+/// let layout = Layout::array::<u64>(1 << 20).unwrap();
+/// let mut slots = alloc_huge(layout, HugePageSize::Mb2, None).unwrap();
+///
+/// slots.as_mut_slice()[0] = 1;
+///
+/// assert!(slots.len() >= layout.size());
+/// assert_eq!(slots.len() % HugePageSize::Mb2.bytes(), 0);
+/// ```
+pub fn alloc_huge(
+    layout: Layout,
+    size: HugePageSize,
+    node: Option<usize>,
+) -> io::Result<HugePageMapping> {
+    if node.is_some_and(|node| !is_online_node(node)) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the NUMA node is not online",
+        ));
+    }
+
+    if layout.align() > size.bytes() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the alignment is larger than the huge page",
+        ));
+    }
+
+    let len = layout.size().max(1).next_multiple_of(size.bytes());
+
+    #[cfg(all(target_os = "linux", not(miri)))]
+    {
+        use super::memory::linux::{bind_memory, map, map_aligned};
+
+        /// The shift of the huge page size in the `mmap` flags.
+        const MAP_HUGE_SHIFT: u32 = 26;
+
+        let huge_tlb_flags =
+            libc::MAP_HUGETLB | (size.bytes().trailing_zeros() << MAP_HUGE_SHIFT).cast_signed();
+        // Huge pages are always aligned to their size, so they are not over-mapped
+        let (ptr, mut backing) = match map(len, huge_tlb_flags) {
+            Ok(ptr) => (ptr, HugePageBacking::HugeTlb),
+            Err(_) => (map_aligned(len, size.bytes())?, HugePageBacking::Regular),
+        };
+
+        if backing == HugePageBacking::Regular
+            && unsafe { libc::madvise(ptr.as_ptr().cast(), len, libc::MADV_HUGEPAGE) } == 0
+        {
+            backing = HugePageBacking::Transparent;
+        }
+
+        let mapping = HugePageMapping { ptr, len, backing };
+
+        if let Some(node) = node {
+            bind_memory(ptr, len, node, 0)?;
+        }
+
+        Ok(mapping)
+    }
+
+    #[cfg(any(not(target_os = "linux"), miri))]
+    {
+        let layout = Layout::from_size_align(len, size.bytes())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+        let ptr = NonNull::new(unsafe { alloc::alloc::alloc_zeroed(layout) })
+            .ok_or_else(|| io::Error::from(io::ErrorKind::OutOfMemory))?;
+
+        Ok(HugePageMapping {
+            ptr,
+            len,
+            align: size.bytes(),
+            backing: HugePageBacking::Regular,
+        })
+    }
+}
+
+#[cfg(all(test, not(miri)))]
+mod tests {
+    use super::*;
+    use crate::numa::get_current_thread_numa_node;
+
+    #[test]
+    fn test_alloc_huge() {
+        let node = get_current_thread_numa_node();
+        let mut mapping = alloc_huge(
+            Layout::from_size_align(3 << 20, 64).unwrap(),
+            HugePageSize::Mb2,
+            Some(node),
+        )
+        .unwrap();
+
+        assert_eq!(mapping.len(), 4 << 20);
+        assert!(mapping
+            .as_ptr()
+            .as_ptr()
+            .addr()
+            .is_multiple_of(HugePageSize::Mb2.bytes()));
+        assert!(mapping.as_slice().iter().all(|&byte| byte == 0));
+
+        mapping.as_mut_slice().fill(0xAB);
+
+        assert_eq!(mapping.as_slice()[(4 << 20) - 1], 0xAB);
+
+        assert_eq!(
+            alloc_huge(
+                Layout::from_size_align(1, 4 << 20).unwrap(),
+                HugePageSize::Mb2,
+                None
+            )
+            .unwrap_err()
+            .kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+}
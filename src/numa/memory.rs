@@ -0,0 +1,529 @@
+use super::is_online_node;
+use crate::raw_vec::{RawVec, RawVecAlloc};
+use alloc::alloc::Layout;
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, DerefMut};
+use core::ptr::NonNull;
+use core::{fmt, ptr};
+use std::io;
+
+/// Allocates memory for the layout on the NUMA node.
+///
+/// On Linux, it maps anonymous memory with `mmap` and binds it to the node with `mbind`,
+/// so the memory is placed on the node regardless of the thread that touches it first.
+/// On other platforms, it falls back to the global allocator.
+///
+/// For zero-sized layouts, it returns a dangling aligned pointer without allocating.
+///
+/// The memory must be deallocated with [`dealloc_on_node`].
+///
+/// # Errors
+///
+/// Returns an error of the [`InvalidInput`](io::ErrorKind::InvalidInput) kind
+/// if the node is not online, or the OS error if the allocation fails.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::numa::{alloc_on_node, dealloc_on_node, get_current_thread_numa_node};
+/// use std::alloc::Layout;
+///
+/// let layout = Layout::array::<u64>(1024).unwrap();
+/// let ptr = alloc_on_node(layout, get_current_thread_numa_node()).unwrap();
+///
+/// unsafe {
+///     ptr.cast::<u64>().write(42);
+///
+///     dealloc_on_node(ptr, layout);
+/// }
+/// ```
+pub fn alloc_on_node(layout: Layout, node: usize) -> io::Result<NonNull<u8>> {
+    if !is_online_node(node) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the NUMA node is not online",
+        ));
+    }
+
+    if layout.size() == 0 {
+        // The alignment is never zero
+        return Ok(unsafe { NonNull::new_unchecked(ptr::without_provenance_mut(layout.align())) });
+    }
+
+    #[cfg(all(target_os = "linux", not(miri)))]
+    {
+        linux::alloc_on_node(layout, node)
+    }
+
+    #[cfg(any(not(target_os = "linux"), miri))]
+    {
+        NonNull::new(unsafe { alloc::alloc::alloc(layout) })
+            .ok_or_else(|| io::Error::from(io::ErrorKind::OutOfMemory))
+    }
+}
+
+/// Deallocates memory allocated with [`alloc_on_node`].
+///
+/// # Safety
+///
+/// The memory must be allocated with [`alloc_on_node`] with the same layout.
+pub unsafe fn dealloc_on_node(ptr: NonNull<u8>, layout: Layout) {
+    if layout.size() == 0 {
+        return;
+    }
+
+    #[cfg(all(target_os = "linux", not(miri)))]
+    {
+        if let Err(err) = linux::unmap(ptr, layout.size()) {
+            debug_assert!(false, "failed to unmap the memory: {err}");
+        }
+    }
+
+    #[cfg(any(not(target_os = "linux"), miri))]
+    {
+        unsafe { alloc::alloc::dealloc(ptr.as_ptr(), layout) };
+    }
+}
+
+#[cfg(all(target_os = "linux", not(miri)))]
+pub(super) mod linux {
+    use crate::numa::node_mask::NodeMask;
+    use alloc::alloc::Layout;
+    use core::ffi::{c_int, c_uint, c_ulong};
+    use core::ptr::{self, NonNull};
+    use std::io;
+
+    /// Returns the size of a page.
+    pub(crate) fn page_size() -> usize {
+        usize::try_from(unsafe { libc::sysconf(libc::_SC_PAGESIZE) }).unwrap_or(4096)
+    }
+
+    /// Maps anonymous memory of the length with the additional `mmap` flags.
+    ///
+    /// Mappings with `MAP_HUGETLB` are aligned to the huge page size by the kernel.
+    pub(crate) fn map(len: usize, flags: c_int) -> io::Result<NonNull<u8>> {
+        let mapped = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | flags,
+                -1,
+                0,
+            )
+        };
+
+        if mapped == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(unsafe { NonNull::new_unchecked(mapped.cast()) })
+    }
+
+    /// Unmaps the memory.
+    pub(crate) fn unmap(ptr: NonNull<u8>, len: usize) -> io::Result<()> {
+        if unsafe { libc::munmap(ptr.as_ptr().cast(), len) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(())
+    }
+
+    /// Maps anonymous regular memory of the length (a multiple of the page size)
+    /// with the alignment.
+    pub(crate) fn map_aligned(len: usize, align: usize) -> io::Result<NonNull<u8>> {
+        let page_size = page_size();
+        // If the alignment is larger than a page, the mapping is extended
+        // to contain an aligned range and trimmed after it
+        let extra_len = align.max(page_size) - page_size;
+        let mapped = map(len + extra_len, 0)?;
+        let head_len = mapped.addr().get().next_multiple_of(align) - mapped.addr().get();
+        let tail_len = extra_len - head_len;
+        let ptr = unsafe { mapped.byte_add(head_len) };
+        let trimmed = (|| {
+            if head_len > 0 {
+                unmap(mapped, head_len)?;
+            }
+
+            if tail_len > 0 {
+                unmap(unsafe { ptr.byte_add(len) }, tail_len)?;
+            }
+
+            Ok(())
+        })();
+
+        if let Err(err) = trimmed {
+            let _ = unmap(mapped, len + extra_len);
+
+            return Err(err);
+        }
+
+        Ok(ptr)
+    }
+
+    /// Binds the memory to the node with the `mbind` flags.
+    pub(crate) fn bind_memory(
+        ptr: NonNull<u8>,
+        len: usize,
+        node: usize,
+        flags: c_uint,
+    ) -> io::Result<()> {
+        let ret = unsafe {
+            libc::syscall(
+                libc::SYS_mbind,
+                ptr.as_ptr(),
+                len as c_ulong,
+                libc::MPOL_BIND,
+                NodeMask::of_node(node).as_ptr(),
+                NodeMask::MAX_NODE,
+                flags,
+            )
+        };
+
+        if ret < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(())
+    }
+
+    /// Maps memory for the layout and binds it to the node.
+    pub(super) fn alloc_on_node(layout: Layout, node: usize) -> io::Result<NonNull<u8>> {
+        let len = layout.size().next_multiple_of(page_size());
+        let ptr = map_aligned(len, layout.align())?;
+
+        if let Err(err) = bind_memory(ptr, len, node, 0) {
+            let _ = unmap(ptr, len);
+
+            return Err(err);
+        }
+
+        Ok(ptr)
+    }
+}
+
+/// A pointer type that owns a value allocated on a NUMA node with [`alloc_on_node`].
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::numa::{get_current_thread_numa_node, NumaBox};
+///
+/// let node = get_current_thread_numa_node();
+/// let mut counters = NumaBox::new_on_node([0u64; 64], node).unwrap();
+///
+/// counters[0] += 1;
+///
+/// assert_eq!(NumaBox::node(&counters), node);
+/// assert_eq!(NumaBox::into_inner(counters)[0], 1);
+/// ```
+pub struct NumaBox<T> {
+    ptr: NonNull<T>,
+    node: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<T> NumaBox<T> {
+    /// Allocates memory on the NUMA node and places the value into it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the memory can't be allocated. Read [`alloc_on_node`] for details.
+    pub fn new_on_node(value: T, node: usize) -> io::Result<Self> {
+        let ptr = alloc_on_node(Layout::new::<T>(), node)?.cast::<T>();
+
+        unsafe { ptr.write(value) };
+
+        Ok(Self {
+            ptr,
+            node,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Returns the NUMA node the value is allocated on.
+    pub fn node(this: &Self) -> usize {
+        this.node
+    }
+
+    /// Consumes the box and returns the value.
+    pub fn into_inner(this: Self) -> T {
+        let this = ManuallyDrop::new(this);
+        let value = unsafe { this.ptr.read() };
+
+        unsafe { dealloc_on_node(this.ptr.cast(), Layout::new::<T>()) };
+
+        value
+    }
+}
+
+impl<T> Deref for NumaBox<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T> DerefMut for NumaBox<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for NumaBox<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T> Drop for NumaBox<T> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(self.ptr.as_ptr());
+
+            dealloc_on_node(self.ptr.cast(), Layout::new::<T>());
+        }
+    }
+}
+
+unsafe impl<T: Send> Send for NumaBox<T> {}
+unsafe impl<T: Sync> Sync for NumaBox<T> {}
+
+/// A growable vector whose buffer is allocated on a NUMA node with [`alloc_on_node`].
+///
+/// When it grows, the new buffer is allocated on the same node, even if
+/// the vector is used by a thread running on another node.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::numa::{get_current_thread_numa_node, NumaVec};
+///
+/// let mut queue = NumaVec::with_capacity_on_node(16, get_current_thread_numa_node()).unwrap();
+///
+/// queue.push(1);
+/// queue.push(2);
+///
+/// assert_eq!(&*queue, &[1, 2]);
+/// assert_eq!(queue.pop(), Some(2));
+/// ```
+pub struct NumaVec<T> {
+    raw: RawVec<T, NodeAlloc>,
+}
+
+impl<T> NumaVec<T> {
+    /// Creates a new empty `NumaVec` without allocating.
+    ///
+    /// The node is checked when the vector allocates.
+    pub const fn new_on_node(node: usize) -> Self {
+        Self {
+            raw: RawVec::new(NodeAlloc { node }),
+        }
+    }
+
+    /// Creates a new empty `NumaVec` with at least the provided capacity.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the memory can't be allocated. Read [`alloc_on_node`] for details.
+    pub fn with_capacity_on_node(capacity: usize, node: usize) -> io::Result<Self> {
+        let mut vec = Self::new_on_node(node);
+
+        vec.try_reserve(capacity)?;
+
+        Ok(vec)
+    }
+
+    /// Returns the NUMA node the buffer is allocated on.
+    pub fn node(&self) -> usize {
+        self.raw.allocator().node
+    }
+
+    /// Returns the number of elements the vector can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.raw.capacity()
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the capacity overflows or the memory can't be allocated.
+    /// Read [`alloc_on_node`] for details.
+    pub fn try_reserve(&mut self, additional: usize) -> io::Result<()> {
+        self.raw.try_reserve(additional)
+    }
+
+    /// Reserves capacity for at least `additional` more elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the capacity overflows or the memory can't be allocated.
+    pub fn reserve(&mut self, additional: usize) {
+        if let Err(err) = self.raw.try_reserve(additional) {
+            self.allocation_failed(&err);
+        }
+    }
+
+    /// Appends the value to the back of the vector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the memory can't be allocated.
+    pub fn push(&mut self, value: T) {
+        if let Err(err) = self.raw.try_push(value) {
+            self.allocation_failed(&err);
+        }
+    }
+
+    /// Removes the last element and returns it, or `None` if the vector is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        self.raw.pop()
+    }
+
+    /// Drops all elements, keeping the allocated memory.
+    pub fn clear(&mut self) {
+        self.raw.clear();
+    }
+
+    /// Panics with the error of a failed allocation.
+    #[cold]
+    fn allocation_failed(&self, err: &io::Error) -> ! {
+        panic!(
+            "failed to reserve memory on the NUMA node {}: {err}",
+            self.node()
+        );
+    }
+}
+
+impl<T> Deref for NumaVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.raw.as_slice()
+    }
+}
+
+impl<T> DerefMut for NumaVec<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.raw.as_mut_slice()
+    }
+}
+
+impl<T> Extend<T> for NumaVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+
+        self.reserve(iter.size_hint().0);
+
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for NumaVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// Allocates buffers of [`NumaVec`] on the NUMA node.
+struct NodeAlloc {
+    node: usize,
+}
+
+impl RawVecAlloc for NodeAlloc {
+    type Error = io::Error;
+
+    fn capacity_overflow() -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidInput, "capacity overflow")
+    }
+
+    fn allocate(&self, layout: Layout) -> io::Result<NonNull<u8>> {
+        alloc_on_node(layout, self.node)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { dealloc_on_node(ptr, layout) };
+    }
+}
+
+#[cfg(all(test, not(miri)))]
+mod tests {
+    use super::*;
+    use crate::numa::get_current_thread_numa_node;
+    use alloc::string::{String, ToString};
+
+    #[test]
+    fn test_alloc_on_node() {
+        let node = get_current_thread_numa_node();
+
+        for layout in [
+            Layout::new::<()>(),
+            Layout::new::<u8>(),
+            Layout::from_size_align(10_000, 8).unwrap(),
+            Layout::from_size_align(100, 1 << 16).unwrap(),
+        ] {
+            let ptr = alloc_on_node(layout, node).unwrap();
+
+            assert!(ptr.as_ptr().addr().is_multiple_of(layout.align()));
+
+            unsafe {
+                ptr::write_bytes(ptr.as_ptr(), 0xAB, layout.size());
+
+                dealloc_on_node(ptr, layout);
+            }
+        }
+
+        assert_eq!(
+            alloc_on_node(Layout::new::<u8>(), usize::MAX)
+                .err()
+                .unwrap()
+                .kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn test_numa_box() {
+        let node = get_current_thread_numa_node();
+        let mut value = NumaBox::new_on_node(String::from("numa"), node).unwrap();
+
+        value.push_str("-local");
+
+        assert_eq!(format!("{value:?}"), "\"numa-local\"");
+        assert_eq!(NumaBox::into_inner(value), "numa-local");
+
+        drop(NumaBox::new_on_node(String::from("dropped"), node).unwrap());
+    }
+
+    #[test]
+    fn test_numa_vec() {
+        let node = get_current_thread_numa_node();
+        let mut vec = NumaVec::new_on_node(node);
+
+        assert_eq!(vec.capacity(), 0);
+
+        vec.extend((0..1000).map(|i| i.to_string()));
+
+        assert_eq!(vec.len(), 1000);
+        assert!(vec.capacity() >= 1000);
+        assert_eq!(vec[999], "999");
+        assert_eq!(vec.pop().as_deref(), Some("999"));
+
+        vec.clear();
+
+        assert!(vec.is_empty());
+        assert_eq!(vec.pop(), None);
+
+        let mut zsts = NumaVec::new_on_node(node);
+
+        zsts.push(());
+
+        assert_eq!(zsts.len(), 1);
+        assert_eq!(zsts.capacity(), usize::MAX);
+    }
+}
@@ -0,0 +1,220 @@
+use super::is_online_node;
+use std::io;
+
+/// Moves the memory to the NUMA node, so long-lived buffers can follow a thread
+/// that was moved to another node.
+///
+/// It moves all pages that contain bytes of the memory and doesn't change the memory policy,
+/// so the memory that is allocated on these pages later follows the policy of the process
+/// (the node of the allocating thread by default). Pages that are shared with other processes,
+/// not touched yet or not mapped are skipped.
+///
+/// On Linux, it uses `move_pages` with `MPOL_MF_MOVE`. On other platforms with a single node,
+/// it does nothing.
+///
+/// # Errors
+///
+/// Returns an error of the [`InvalidInput`](io::ErrorKind::InvalidInput) kind
+/// if the node is not online, an error of the [`Unsupported`](io::ErrorKind::Unsupported) kind
+/// if the platform doesn't support migration, or the OS error if the migration fails.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::numa::{get_current_thread_numa_node, migrate_to_node};
+///
+/// let buffer = vec![0u8; 1 << 20];
+///
+/// // The thread was rebalanced to another node
+/// let node = get_current_thread_numa_node();
+///
+/// migrate_to_node(buffer.as_ptr(), buffer.len(), node).unwrap();
+/// ```
+pub fn migrate_to_node(ptr: *const u8, len: usize, node: usize) -> io::Result<()> {
+    if !is_online_node(node) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the NUMA node is not online",
+        ));
+    }
+
+    if len == 0 {
+        return Ok(());
+    }
+
+    #[cfg(all(target_os = "linux", not(miri)))]
+    {
+        use super::memory::linux::page_size;
+        use core::ffi::{c_int, c_ulong, c_void};
+
+        /// Moves pages that are mapped only by this process.
+        const MPOL_MF_MOVE: c_int = 1 << 1;
+        /// The number of pages moved by a single `move_pages` call.
+        const BATCH: usize = 256;
+
+        let page_size = page_size();
+        let start = ptr.addr() - ptr.addr() % page_size;
+        let pages_count = (ptr.addr() + len - start).div_ceil(page_size);
+        let node = c_int::try_from(node)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "the NUMA node is invalid"))?;
+        let nodes = [node; BATCH];
+        let mut pages = [core::ptr::null_mut::<c_void>(); BATCH];
+        let mut status: [c_int; BATCH] = [0; BATCH];
+
+        for first_page in (0..pages_count).step_by(BATCH) {
+            let count = BATCH.min(pages_count - first_page);
+
+            for (i, page) in pages[..count].iter_mut().enumerate() {
+                *page = ptr
+                    .with_addr(start + (first_page + i) * page_size)
+                    .cast_mut()
+                    .cast();
+            }
+
+            let ret = unsafe {
+                libc::syscall(
+                    libc::SYS_move_pages,
+                    0 as c_int,
+                    count as c_ulong,
+                    pages.as_ptr(),
+                    nodes.as_ptr(),
+                    status.as_mut_ptr(),
+                    MPOL_MF_MOVE,
+                )
+            };
+
+            if ret < 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            // Shared, not touched and not mapped pages are skipped
+            if let Some(&status) = status[..count].iter().find(|&&status| {
+                status < 0 && ![libc::EACCES, libc::ENOENT, libc::EFAULT].contains(&-status)
+            }) {
+                return Err(io::Error::from_raw_os_error(-status));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(any(not(target_os = "linux"), miri))]
+    {
+        let _ = ptr;
+
+        single_node_or_unsupported()
+    }
+}
+
+/// Returns the NUMA node the page that contains the byte is placed on.
+///
+/// If the page is not touched yet, it is touched.
+///
+/// On Linux, it uses `get_mempolicy` with `MPOL_F_NODE | MPOL_F_ADDR`.
+/// On other platforms with a single node, it returns `0`.
+///
+/// # Errors
+///
+/// Returns an error of the [`Unsupported`](io::ErrorKind::Unsupported) kind
+/// if the platform can't locate the memory, or the OS error if the query fails.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::numa::{
+///     alloc_on_node, dealloc_on_node, get_current_thread_numa_node, node_of_memory
+/// };
+/// use std::alloc::Layout;
+///
+/// let node = get_current_thread_numa_node();
+/// let layout = Layout::new::<u64>();
+/// let ptr = alloc_on_node(layout, node).unwrap();
+///
+/// assert_eq!(node_of_memory(ptr.as_ptr()).unwrap(), node);
+///
+/// unsafe { dealloc_on_node(ptr, layout) };
+/// ```
+#[allow(
+    clippy::not_unsafe_ptr_arg_deref,
+    reason = "The pointer is passed to the kernel, which checks it"
+)]
+pub fn node_of_memory(ptr: *const u8) -> io::Result<usize> {
+    #[cfg(all(target_os = "linux", not(miri)))]
+    {
+        use core::ffi::{c_int, c_ulong};
+
+        /// Returns the node instead of the policy.
+        const MPOL_F_NODE: c_ulong = 1 << 0;
+        /// Looks up the policy of the address.
+        const MPOL_F_ADDR: c_ulong = 1 << 1;
+
+        let mut node: c_int = 0;
+        let ret = unsafe {
+            libc::syscall(
+                libc::SYS_get_mempolicy,
+                &raw mut node,
+                core::ptr::null_mut::<c_ulong>(),
+                0 as c_ulong,
+                ptr,
+                MPOL_F_NODE | MPOL_F_ADDR,
+            )
+        };
+
+        if ret < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        usize::try_from(node).map_err(|_| io::Error::other("the kernel returned a negative node"))
+    }
+
+    #[cfg(any(not(target_os = "linux"), miri))]
+    {
+        let _ = ptr;
+
+        single_node_or_unsupported().map(|()| 0)
+    }
+}
+
+/// Returns `Ok` if the machine has a single NUMA node, so the memory is always on it.
+#[cfg(any(not(target_os = "linux"), miri))]
+fn single_node_or_unsupported() -> io::Result<()> {
+    if super::nodes_count() == 1 {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "the platform doesn't support NUMA memory migration",
+        ))
+    }
+}
+
+#[cfg(all(test, not(miri)))]
+mod tests {
+    use super::*;
+    use crate::numa::{get_current_thread_numa_node, NumaVec, MAX_NUMA_NODES_SUPPORTED};
+
+    #[test]
+    fn test_migrate_to_node() {
+        let node = get_current_thread_numa_node();
+        let mut buffer = NumaVec::with_capacity_on_node(10_000, node).unwrap();
+
+        buffer.extend(0..10_000u32);
+
+        migrate_to_node(buffer.as_ptr().cast(), size_of_val(&*buffer), node).unwrap();
+
+        assert_eq!(node_of_memory(buffer.as_ptr().cast()).unwrap(), node);
+        assert_eq!(buffer[9_999], 9_999);
+
+        let on_heap = alloc::vec![1u8; 100];
+
+        migrate_to_node(on_heap.as_ptr().wrapping_add(10), 50, node).unwrap();
+        migrate_to_node(on_heap.as_ptr(), 0, node).unwrap();
+
+        assert_eq!(
+            migrate_to_node(on_heap.as_ptr(), 1, MAX_NUMA_NODES_SUPPORTED)
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+}
@@ -0,0 +1,372 @@
+//! Provides utilities for working with NUMA (Non-Uniform Memory Access) nodes.
+//!
+//! This module offers functionality to:
+//! - Manage data per NUMA node with [`DataPerNUMANodeManager`]
+//! - Reuse objects with a free list per NUMA node with [`NumaLocalPool`]
+//! - Find the NUMA nodes of worker threads with [`ThreadNodeRegistry`]
+//! - Get the NUMA node of the current thread cheaply with [`current_node_cached`]
+//! - Get information about available NUMA nodes with [`nodes_count`], [`online_nodes`]
+//!   and [`cpus_of_node`]
+//! - Get distances between NUMA nodes with [`distance`] and [`distance_matrix`]
+//! - Bind threads to specific NUMA nodes with [`bind_current_thread_to_node`]
+//!   and initialize node-local memory with [`first_touch_init`]
+//! - Allocate memory on specific NUMA nodes with [`alloc_on_node`], [`NumaBox`] and [`NumaVec`]
+//! - Allocate memory backed by huge pages with [`alloc_huge`]
+//! - Move memory between NUMA nodes with [`migrate_to_node`]
+//!
+//! NUMA is supported on Linux and Windows. Other platforms, such as macOS, don't expose
+//! the NUMA topology, so the machine is considered a single node `0`.
+//!
+//! The rest needs syscalls, so it is gated behind the `numa` feature that is enabled
+//! by default and requires `std`. With the `no_std` feature or without the `numa` feature,
+//! only [`DataPerNUMANodeManager`] and [`ThreadNodeRegistry`] are available. Without the topology,
+//! only the node `0` is considered online.
+//!
+//! # Example
+//!
+//! ```
+//! use orengine_utils::numa::{DataPerNUMANodeManager, MAX_NUMA_NODES_SUPPORTED};
+//!
+//! // Create a manager with data for each NUMA node
+//! static MANAGER: DataPerNUMANodeManager<usize> =
+//!     DataPerNUMANodeManager::from_arr([0; MAX_NUMA_NODES_SUPPORTED]);
+//!
+//! #[cfg(all(feature = "numa", not(feature = "no_std")))]
+//! let numa_node = orengine_utils::numa::get_current_thread_numa_node();
+//! // Without the `numa` feature, the runtime provides the node of the thread
+//! #[cfg(any(not(feature = "numa"), feature = "no_std"))]
+//! let numa_node = 0;
+//!
+//! println!("Memory by {numa_node} NUMA node contains {}", MANAGER.get_ref_by_node(numa_node));
+//! ```
+
+#[cfg(all(feature = "numa", not(feature = "no_std")))]
+mod bind;
+#[cfg(all(feature = "numa", not(feature = "no_std")))]
+mod cached_node;
+#[cfg(all(feature = "numa", not(feature = "no_std")))]
+mod distance;
+#[cfg(all(feature = "numa", not(feature = "no_std")))]
+mod huge_pages;
+#[cfg(all(feature = "numa", not(feature = "no_std")))]
+mod memory;
+#[cfg(all(feature = "numa", not(feature = "no_std")))]
+mod migrate;
+#[cfg(all(
+    target_os = "linux",
+    feature = "numa",
+    not(feature = "no_std"),
+    not(miri)
+))]
+mod node_mask;
+#[cfg(all(feature = "numa", not(feature = "no_std")))]
+mod pool;
+mod registry;
+#[cfg(all(feature = "numa", not(feature = "no_std")))]
+mod topology;
+#[cfg(all(windows, feature = "numa", not(feature = "no_std"), not(miri)))]
+mod windows;
+
+#[cfg(all(feature = "numa", not(feature = "no_std")))]
+pub use bind::*;
+#[cfg(all(feature = "numa", not(feature = "no_std")))]
+pub use cached_node::*;
+#[cfg(all(feature = "numa", not(feature = "no_std")))]
+pub use distance::*;
+#[cfg(all(feature = "numa", not(feature = "no_std")))]
+pub use huge_pages::*;
+#[cfg(all(feature = "numa", not(feature = "no_std")))]
+pub use memory::*;
+#[cfg(all(feature = "numa", not(feature = "no_std")))]
+pub use migrate::*;
+#[cfg(all(feature = "numa", not(feature = "no_std")))]
+pub use pool::*;
+pub use registry::*;
+#[cfg(all(feature = "numa", not(feature = "no_std")))]
+pub use topology::*;
+
+use crate::hints::unwrap_or_bug_message_hint;
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt;
+use core::iter::Iterator;
+#[cfg(all(feature = "numa", not(feature = "no_std")))]
+use topology::{for_each_online_node, is_online_node};
+
+#[cfg(not(feature = "more_numa_nodes"))]
+pub const MAX_NUMA_NODES_SUPPORTED_: usize = 64;
+#[cfg(feature = "more_numa_nodes")]
+pub const MAX_NUMA_NODES_SUPPORTED_: usize = 1024;
+
+/// The maximum number of NUMA nodes supported by the library.
+///
+/// If a machine supports more NUMA nodes than this, panics may occur with `debug_assertions`
+/// or UB otherwise.
+pub const MAX_NUMA_NODES_SUPPORTED: usize = MAX_NUMA_NODES_SUPPORTED_;
+
+const NUMA_NODE_TOO_LARGE: &str = "this hardware supports more NUMA-nodes than expected, use the `more_numa_nodes` feature to increase the limit";
+
+/// Manages data per NUMA node.
+/// It allows storing data for each NUMA node and accessing it by the NUMA node ID.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::numa::{DataPerNUMANodeManager, MAX_NUMA_NODES_SUPPORTED};
+///
+/// // Create a manager with data for each NUMA node
+/// static MANAGER: DataPerNUMANodeManager<usize> =
+///     DataPerNUMANodeManager::from_arr([0; MAX_NUMA_NODES_SUPPORTED]);
+///
+/// fn run_worker(numa_node: usize) {
+///     println!("Memory by {numa_node} NUMA node contains {}", MANAGER.get_ref_by_node(numa_node));
+/// }
+///
+/// run_worker(0);
+/// ```
+pub struct DataPerNUMANodeManager<T>([T; MAX_NUMA_NODES_SUPPORTED]);
+
+impl<T> DataPerNUMANodeManager<T> {
+    /// Creates a new manager from an array of data for each NUMA node.
+    pub const fn from_arr(inner: [T; MAX_NUMA_NODES_SUPPORTED]) -> Self {
+        Self(inner)
+    }
+
+    /// Gets a reference to the data for the specified NUMA node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the NUMA node ID is out of bounds.
+    pub fn get_ref_by_node(&self, numa_node: usize) -> &T {
+        unwrap_or_bug_message_hint(self.0.get(numa_node), NUMA_NODE_TOO_LARGE)
+    }
+
+    /// Gets a mutable reference to the data for the specified NUMA node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the NUMA node ID is out of bounds.
+    pub fn get_mut_by_node(&mut self, numa_node: usize) -> &mut T {
+        unwrap_or_bug_message_hint(self.0.get_mut(numa_node), NUMA_NODE_TOO_LARGE)
+    }
+
+    /// Returns an iterator over references to the data for all NUMA nodes.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.0.iter()
+    }
+
+    /// Returns an iterator over mutable references to the data for all NUMA nodes.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.0.iter_mut()
+    }
+
+    /// Returns a pointer to the inner array.
+    pub fn as_ptr(&self) -> *const [T; MAX_NUMA_NODES_SUPPORTED] {
+        self.0.as_ptr().cast()
+    }
+}
+
+impl<T> DataPerNUMANodeManager<Option<T>> {
+    /// Creates a new manager by initializing an entry for each online NUMA node
+    /// with the provided function.
+    ///
+    /// Entries of offline nodes are `None`, so `T` doesn't need a placeholder value,
+    /// and it can be a resource such as a bound socket.
+    ///
+    /// # Errors
+    ///
+    /// If the function fails for some nodes, all entries are dropped and
+    /// the [`PerNodeInitError`] with all errors is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::numa::DataPerNUMANodeManager;
+    ///
+    /// let manager = DataPerNUMANodeManager::try_new_per_node(|node| Ok::<_, ()>(node + 1)).unwrap();
+    ///
+    /// assert_eq!(*manager.get_ref_by_node(0), Some(1));
+    ///
+    /// let err = DataPerNUMANodeManager::<Option<usize>>::try_new_per_node(|_| Err("unavailable"))
+    ///     .err()
+    ///     .unwrap();
+    ///
+    /// assert!(err.failed_nodes().any(|node| node == 0));
+    /// ```
+    pub fn try_new_per_node<E>(
+        init: impl Fn(usize) -> Result<T, E>,
+    ) -> Result<Self, PerNodeInitError<E>> {
+        let mut online = [false; MAX_NUMA_NODES_SUPPORTED];
+
+        for_each_online_node(|node| {
+            if let Some(is_online) = online.get_mut(node) {
+                *is_online = true;
+            }
+        });
+
+        let mut errors = Vec::new();
+        let entries = core::array::from_fn(|node| {
+            if !online[node] {
+                return None;
+            }
+
+            init(node).map_err(|err| errors.push((node, err))).ok()
+        });
+
+        if errors.is_empty() {
+            Ok(Self(entries))
+        } else {
+            Err(PerNodeInitError { errors })
+        }
+    }
+}
+
+impl<T: Default> Default for DataPerNUMANodeManager<T> {
+    fn default() -> Self {
+        Self(core::array::from_fn(|_| T::default()))
+    }
+}
+
+/// Error returned by [`DataPerNUMANodeManager::try_new_per_node`]
+/// when the initialization fails for some NUMA nodes.
+#[derive(Debug)]
+pub struct PerNodeInitError<E> {
+    errors: Vec<(usize, E)>,
+}
+
+impl<E> PerNodeInitError<E> {
+    /// Returns the NUMA nodes for which the initialization failed.
+    pub fn failed_nodes(&self) -> impl Iterator<Item = usize> + '_ {
+        self.errors.iter().map(|(node, _)| *node)
+    }
+
+    /// Returns the errors with the NUMA nodes they occurred for.
+    pub fn errors(&self) -> &[(usize, E)] {
+        &self.errors
+    }
+
+    /// Returns the errors with the NUMA nodes they occurred for.
+    pub fn into_errors(self) -> Vec<(usize, E)> {
+        self.errors
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for PerNodeInitError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to initialize data for NUMA nodes:")?;
+
+        for (node, err) in &self.errors {
+            write!(f, " [{node}: {err}]")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> Error for PerNodeInitError<E> {}
+
+/// Calls the provided function for node `0`, because the topology is unknown without `std`.
+#[cfg(any(not(feature = "numa"), feature = "no_std"))]
+fn for_each_online_node(mut f: impl FnMut(usize)) {
+    f(0);
+}
+
+#[cfg(all(test, not(miri)))]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_data_per_numa_node_manager_iterators() {
+        let mut arr = [1i32; MAX_NUMA_NODES_SUPPORTED];
+        for (i, item) in arr.iter_mut().enumerate().take(8) {
+            *item = i32::try_from(i + 1).unwrap();
+        }
+        let mut manager = DataPerNUMANodeManager::from_arr(arr);
+
+        // Test iter()
+        let values: Vec<i32> = manager.iter().copied().collect();
+        assert_eq!(values[0], 1);
+        assert_eq!(values[7], 8);
+        assert_eq!(values[8], 1); // rest should be default (1)
+
+        // Test iter_mut()
+        for val in manager.iter_mut().take(4) {
+            *val *= 2;
+        }
+        assert_eq!(*manager.get_ref_by_node(0), 2);
+        assert_eq!(*manager.get_ref_by_node(3), 8);
+        assert_eq!(*manager.get_ref_by_node(4), 5);
+
+        // Test iter_enumerated()
+        let enumerated: Vec<(usize, &i32)> = manager.iter().enumerate().collect();
+        assert_eq!(enumerated[0], (0, &2));
+        assert_eq!(enumerated[3], (3, &8));
+        assert_eq!(enumerated[4], (4, &5));
+
+        // Test iter_enumerated_mut()
+        for (node_id, val) in manager.iter_mut().enumerate() {
+            if node_id % 2 == 0 {
+                *val += 10;
+            }
+        }
+        assert_eq!(*manager.get_ref_by_node(0), 12);
+        assert_eq!(*manager.get_ref_by_node(1), 4);
+        assert_eq!(*manager.get_ref_by_node(2), 16);
+    }
+
+    #[test]
+    fn test_data_per_numa_node_manager_bounds() {
+        let manager = DataPerNUMANodeManager::from_arr([0u8; MAX_NUMA_NODES_SUPPORTED]);
+
+        // Should work for valid indices
+        for i in 0..MAX_NUMA_NODES_SUPPORTED {
+            let _ref = manager.get_ref_by_node(i);
+        }
+    }
+
+    #[test]
+    fn test_try_new_per_node() {
+        // `T` doesn't need to implement `Default`
+        struct Socket(usize);
+
+        let manager =
+            DataPerNUMANodeManager::try_new_per_node(|node| Ok::<_, ()>(node + 1)).unwrap();
+
+        assert_eq!(*manager.get_ref_by_node(0), Some(1));
+        assert_eq!(*manager.get_ref_by_node(MAX_NUMA_NODES_SUPPORTED - 1), None);
+
+        let sockets =
+            DataPerNUMANodeManager::try_new_per_node(|node| Ok::<_, ()>(Socket(node))).unwrap();
+
+        assert_eq!(
+            sockets.get_ref_by_node(0).as_ref().map(|socket| socket.0),
+            Some(0)
+        );
+
+        let err = DataPerNUMANodeManager::<Option<usize>>::try_new_per_node(|node| {
+            if node == 0 {
+                Err("hugepages are unavailable")
+            } else {
+                Ok(node)
+            }
+        })
+        .err()
+        .unwrap();
+
+        assert_eq!(err.failed_nodes().collect::<Vec<_>>(), [0]);
+        assert_eq!(
+            alloc::format!("{err}"),
+            "failed to initialize data for NUMA nodes: [0: hugepages are unavailable]"
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "numa", not(feature = "no_std")))]
+    fn test_common_case() {
+        let numa_node = get_current_thread_numa_node();
+        let manager = DataPerNUMANodeManager::from_arr([0u8; MAX_NUMA_NODES_SUPPORTED]);
+
+        assert_eq!(*manager.get_ref_by_node(numa_node), 0);
+    }
+}
@@ -0,0 +1,43 @@
+use super::MAX_NUMA_NODES_SUPPORTED;
+use core::ffi::c_ulong;
+
+/// The number of words in a node mask. The kernel reads `maxnode - 1` bits,
+/// so the mask has an extra word to keep the last supported node.
+const NODE_MASK_WORDS: usize = MAX_NUMA_NODES_SUPPORTED / c_ulong::BITS as usize + 1;
+
+/// A node mask of the Linux memory policy syscalls.
+#[derive(Clone, Copy)]
+pub(super) struct NodeMask([c_ulong; NODE_MASK_WORDS]);
+
+impl NodeMask {
+    /// The `maxnode` argument of the memory policy syscalls.
+    pub(super) const MAX_NODE: c_ulong = (NODE_MASK_WORDS * c_ulong::BITS as usize) as c_ulong;
+
+    /// Returns a mask without nodes.
+    pub(super) const fn empty() -> Self {
+        Self([0; NODE_MASK_WORDS])
+    }
+
+    /// Returns a mask with only the provided node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the node is not less than [`MAX_NUMA_NODES_SUPPORTED`].
+    pub(super) fn of_node(node: usize) -> Self {
+        let mut mask = Self::empty();
+
+        mask.0[node / c_ulong::BITS as usize] |= 1 << (node % c_ulong::BITS as usize);
+
+        mask
+    }
+
+    /// Returns a pointer to the mask.
+    pub(super) fn as_ptr(&self) -> *const c_ulong {
+        self.0.as_ptr()
+    }
+
+    /// Returns a mutable pointer to the mask.
+    pub(super) fn as_mut_ptr(&mut self) -> *mut c_ulong {
+        self.0.as_mut_ptr()
+    }
+}
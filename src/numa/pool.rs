@@ -0,0 +1,175 @@
+use super::{current_node_cached, DataPerNUMANodeManager, NumaVec};
+use crate::cache_padded::CachePadded;
+use core::fmt;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// A pool of reusable objects with a free list per NUMA node.
+///
+/// Objects are taken from and returned to the free list of the NUMA node
+/// the caller is running on (read [`current_node_cached`]), so a reused object
+/// is likely to be allocated on the local node, and threads of different nodes
+/// don't contend. Each free list is cache-padded, and its buffer is allocated on its node.
+///
+/// When the free list of the node is full, returned objects spill to a global list,
+/// which is used when the free list of the node is empty.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::numa::NumaLocalPool;
+///
+/// let pool = NumaLocalPool::new(128);
+///
+/// let mut buffer = pool.pop_or_else(|| Vec::<u8>::with_capacity(4096));
+///
+/// buffer.extend_from_slice(b"request");
+/// buffer.clear();
+///
+/// pool.push(buffer);
+///
+/// assert_eq!(pool.pop().unwrap().capacity(), 4096);
+/// ```
+pub struct NumaLocalPool<T> {
+    nodes: DataPerNUMANodeManager<CachePadded<Mutex<NumaVec<T>>>>,
+    overflow: Mutex<Vec<T>>,
+    node_capacity: usize,
+}
+
+impl<T> NumaLocalPool<T> {
+    /// Creates a new empty pool, where the free list of each NUMA node can hold
+    /// at most `node_capacity` objects.
+    ///
+    /// It doesn't allocate until objects are returned.
+    pub fn new(node_capacity: usize) -> Self {
+        Self {
+            nodes: DataPerNUMANodeManager::from_arr(core::array::from_fn(|node| {
+                CachePadded::new(Mutex::new(NumaVec::new_on_node(node)))
+            })),
+            overflow: Mutex::new(Vec::new()),
+            node_capacity,
+        }
+    }
+
+    /// Returns the maximum number of objects in the free list of each NUMA node.
+    pub fn node_capacity(&self) -> usize {
+        self.node_capacity
+    }
+
+    /// Takes an object from the free list of the current NUMA node or from the global list
+    /// if the former is empty. Returns `None` if both are empty.
+    pub fn pop(&self) -> Option<T> {
+        lock(self.nodes.get_ref_by_node(current_node_cached()))
+            .pop()
+            .or_else(|| lock(&self.overflow).pop())
+    }
+
+    /// Takes an object like [`pop`](Self::pop) or creates a new one with the provided function
+    /// if the pool is empty.
+    pub fn pop_or_else(&self, f: impl FnOnce() -> T) -> T {
+        self.pop().unwrap_or_else(f)
+    }
+
+    /// Returns the object to the free list of the current NUMA node
+    /// or to the global list if the former is full.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the memory for the free list can't be allocated.
+    pub fn push(&self, value: T) {
+        let mut local = lock(self.nodes.get_ref_by_node(current_node_cached()));
+
+        if local.len() < self.node_capacity {
+            local.push(value);
+
+            return;
+        }
+
+        drop(local);
+
+        lock(&self.overflow).push(value);
+    }
+
+    /// Returns the number of objects in the free list of the NUMA node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the NUMA node ID is out of bounds.
+    pub fn len_on_node(&self, node: usize) -> usize {
+        lock(self.nodes.get_ref_by_node(node)).len()
+    }
+
+    /// Returns the number of objects in the global list.
+    pub fn overflow_len(&self) -> usize {
+        lock(&self.overflow).len()
+    }
+}
+
+impl<T> fmt::Debug for NumaLocalPool<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NumaLocalPool")
+            .field("node_capacity", &self.node_capacity)
+            .field("overflow_len", &self.overflow_len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Locks the mutex, ignoring the poisoning, because a free list is always valid.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(all(test, not(miri)))]
+mod tests {
+    use super::*;
+    use crate::numa::get_current_thread_numa_node;
+    use alloc::sync::Arc;
+
+    #[test]
+    fn test_numa_local_pool_spills_to_overflow() {
+        let pool = NumaLocalPool::new(2);
+        let node = get_current_thread_numa_node();
+
+        for i in 0..5 {
+            pool.push(i);
+        }
+
+        assert_eq!(pool.len_on_node(node), 2);
+        assert_eq!(pool.overflow_len(), 3);
+
+        let mut popped: Vec<_> = core::iter::from_fn(|| pool.pop()).collect();
+
+        popped.sort_unstable();
+
+        assert_eq!(popped, [0, 1, 2, 3, 4]);
+        assert_eq!(pool.pop_or_else(|| 10), 10);
+    }
+
+    #[test]
+    fn test_numa_local_pool_concurrent() {
+        let pool = Arc::new(NumaLocalPool::new(16));
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let pool = pool.clone();
+
+                std::thread::spawn(move || {
+                    for i in 0..1000 {
+                        let value = pool.pop_or_else(|| i);
+
+                        pool.push(value);
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let total: usize = (0..crate::numa::MAX_NUMA_NODES_SUPPORTED)
+            .map(|node| pool.len_on_node(node))
+            .sum::<usize>()
+            + pool.overflow_len();
+
+        assert!((1..=4).contains(&total), "total: {total}");
+    }
+}
@@ -0,0 +1,151 @@
+#[cfg(all(feature = "numa", not(feature = "no_std")))]
+use super::get_current_thread_numa_node;
+use core::fmt;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// The value of an unregistered worker.
+const UNREGISTERED: usize = usize::MAX;
+
+/// A table of NUMA nodes of at most `N` workers.
+///
+/// Each worker registers once with its index, usually with [`register`](Self::register)
+/// after it is bound to its node, and any thread can query the node of a worker
+/// without locks, for example, to route a message to the queue of the right NUMA node.
+///
+/// Reads are a single atomic load. Registrations are expected to be rare,
+/// so entries are not cache-padded.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::numa::ThreadNodeRegistry;
+///
+/// static WORKERS: ThreadNodeRegistry<64> = ThreadNodeRegistry::new();
+///
+/// for (worker, node) in [(0, 0), (1, 0), (2, 1), (3, 1)] {
+///     WORKERS.register_on_node(worker, node);
+/// }
+///
+/// // Route a message to the worker 2
+/// assert_eq!(WORKERS.node_of(2), Some(1));
+/// assert_eq!(WORKERS.workers_on_node(1).collect::<Vec<_>>(), [2, 3]);
+/// ```
+pub struct ThreadNodeRegistry<const N: usize> {
+    nodes: [AtomicUsize; N],
+}
+
+impl<const N: usize> ThreadNodeRegistry<N> {
+    /// Creates a new registry without registered workers.
+    pub const fn new() -> Self {
+        Self {
+            nodes: [const { AtomicUsize::new(UNREGISTERED) }; N],
+        }
+    }
+
+    /// Returns the maximum number of workers.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Registers the worker on the NUMA node of the current thread and returns the node.
+    ///
+    /// It is available only with the `numa` feature and without the `no_std` feature,
+    /// otherwise use [`register_on_node`](Self::register_on_node).
+    ///
+    /// Call it after the thread is bound to its node, otherwise the OS can move
+    /// the thread to another node, and the registration becomes stale.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the worker is not less than `N`.
+    #[cfg(all(feature = "numa", not(feature = "no_std")))]
+    pub fn register(&self, worker: usize) -> usize {
+        let node = get_current_thread_numa_node();
+
+        self.register_on_node(worker, node);
+
+        node
+    }
+
+    /// Registers the worker on the provided NUMA node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the worker is not less than `N`.
+    pub fn register_on_node(&self, worker: usize, node: usize) {
+        debug_assert_ne!(node, UNREGISTERED, "the NUMA node is invalid");
+
+        self.nodes[worker].store(node, Ordering::Release);
+    }
+
+    /// Unregisters the worker.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the worker is not less than `N`.
+    pub fn unregister(&self, worker: usize) {
+        self.nodes[worker].store(UNREGISTERED, Ordering::Release);
+    }
+
+    /// Returns the NUMA node of the worker or `None` if it is not registered
+    /// or not less than `N`.
+    pub fn node_of(&self, worker: usize) -> Option<usize> {
+        let node = self.nodes.get(worker)?.load(Ordering::Acquire);
+
+        (node != UNREGISTERED).then_some(node)
+    }
+
+    /// Returns an iterator over the workers registered on the NUMA node.
+    pub fn workers_on_node(&self, node: usize) -> impl Iterator<Item = usize> + '_ {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter(move |(_, worker_node)| worker_node.load(Ordering::Acquire) == node)
+            .map(|(worker, _)| worker)
+    }
+}
+
+impl<const N: usize> Default for ThreadNodeRegistry<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> fmt::Debug for ThreadNodeRegistry<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries((0..N).filter_map(|worker| Some((worker, self.node_of(worker)?))))
+            .finish()
+    }
+}
+
+#[cfg(all(test, not(miri), feature = "numa", not(feature = "no_std")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thread_node_registry() {
+        let registry = ThreadNodeRegistry::<8>::new();
+
+        std::thread::scope(|scope| {
+            for worker in 0..4 {
+                let registry = &registry;
+
+                scope.spawn(move || registry.register(worker));
+            }
+        });
+
+        for worker in 0..4 {
+            assert!(registry.node_of(worker).is_some());
+        }
+
+        registry.register_on_node(5, 3);
+        registry.unregister(0);
+
+        assert_eq!(registry.node_of(0), None);
+        assert_eq!(registry.node_of(5), Some(3));
+        assert_eq!(registry.node_of(8), None);
+        assert_eq!(registry.workers_on_node(3).collect::<Vec<_>>(), [5]);
+        assert_eq!(registry.capacity(), 8);
+    }
+}
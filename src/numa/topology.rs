@@ -0,0 +1,286 @@
+use super::MAX_NUMA_NODES_SUPPORTED;
+use alloc::vec::Vec;
+use std::sync::OnceLock;
+
+/// Calls the provided function for each number in a sysfs list like `0-3,8,10-11`.
+#[cfg_attr(
+    any(not(target_os = "linux"), miri),
+    allow(dead_code, reason = "It is used only with sysfs")
+)]
+fn for_each_in_sysfs_list(list: &str, mut f: impl FnMut(usize)) {
+    for part in list.trim().split(',').filter(|part| !part.is_empty()) {
+        let (start, end) = part.split_once('-').unwrap_or((part, part));
+
+        if let (Ok(start), Ok(end)) = (start.parse::<usize>(), end.parse::<usize>()) {
+            for number in start..=end {
+                f(number);
+            }
+        }
+    }
+}
+
+/// Calls the provided function for each online NUMA node.
+///
+/// If the topology is unknown, only node `0` is considered online.
+pub(super) fn for_each_online_node(mut f: impl FnMut(usize)) {
+    #[cfg(all(target_os = "linux", not(miri)))]
+    {
+        if let Ok(list) = std::fs::read_to_string("/sys/devices/system/node/online") {
+            for_each_in_sysfs_list(&list, f);
+
+            return;
+        }
+    }
+
+    #[cfg(all(windows, not(miri)))]
+    {
+        if super::windows::for_each_node(&mut f) {
+            return;
+        }
+    }
+
+    f(0);
+}
+
+/// Returns the number of online NUMA nodes.
+///
+/// Node IDs can be sparse, so use [`online_nodes`] to get them.
+/// On Linux, it reads sysfs on every call, and on Windows, it queries the system
+/// on every call. If the topology is unknown,
+/// it returns `1`.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::numa::{nodes_count, online_nodes};
+///
+/// assert!(nodes_count() >= 1);
+/// assert_eq!(nodes_count(), online_nodes().len());
+/// ```
+pub fn nodes_count() -> usize {
+    let mut count = 0;
+
+    for_each_online_node(|_| count += 1);
+
+    count
+}
+
+/// Returns the IDs of online NUMA nodes in ascending order.
+///
+/// On Linux, it reads sysfs on every call, and on Windows, it queries the system
+/// on every call. If the topology is unknown,
+/// it returns `[0]`.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::numa::online_nodes;
+///
+/// assert!(online_nodes().contains(&0));
+/// ```
+pub fn online_nodes() -> Vec<usize> {
+    let mut nodes = Vec::new();
+
+    for_each_online_node(|node| nodes.push(node));
+
+    nodes
+}
+
+/// Returns the IDs of CPUs of the NUMA node in ascending order.
+///
+/// On Linux, it reads sysfs on every call. On Windows, it returns the CPUs
+/// of the primary processor group of the node, and the CPU ID is `group * 64 + number`.
+/// If the topology is unknown, all CPUs belong to the node `0`.
+/// It returns an empty vector for memory-only and offline nodes.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::numa::{cpus_of_node, get_current_thread_numa_node};
+///
+/// assert!(!cpus_of_node(get_current_thread_numa_node()).is_empty());
+/// ```
+pub fn cpus_of_node(node: usize) -> Vec<usize> {
+    let mut cpus = Vec::new();
+
+    #[cfg(all(target_os = "linux", not(miri)))]
+    {
+        if let Ok(list) =
+            std::fs::read_to_string(format!("/sys/devices/system/node/node{node}/cpulist"))
+        {
+            for_each_in_sysfs_list(&list, |cpu| cpus.push(cpu));
+
+            return cpus;
+        }
+    }
+
+    #[cfg(all(windows, not(miri)))]
+    {
+        if let Some(affinity) = super::windows::node_affinity(node) {
+            let first_cpu = usize::from(affinity.Group) * super::windows::PROCESSORS_PER_GROUP;
+
+            cpus.extend(
+                (0..super::windows::PROCESSORS_PER_GROUP)
+                    .filter(|number| affinity.Mask & (1 << number) != 0)
+                    .map(|number| first_cpu + number),
+            );
+
+            return cpus;
+        }
+    }
+
+    if node == 0 {
+        let parallelism = std::thread::available_parallelism().map_or(1, usize::from);
+
+        cpus.extend(0..parallelism);
+    }
+
+    cpus
+}
+
+/// Returns whether the NUMA node is online.
+///
+/// The online nodes are read once and cached, because it is called on allocation paths,
+/// and nodes are rarely brought online or offline.
+pub(super) fn is_online_node(node: usize) -> bool {
+    static ONLINE: OnceLock<[bool; MAX_NUMA_NODES_SUPPORTED]> = OnceLock::new();
+
+    let online = ONLINE.get_or_init(|| {
+        let mut online = [false; MAX_NUMA_NODES_SUPPORTED];
+
+        for_each_online_node(|node| {
+            if let Some(is_online) = online.get_mut(node) {
+                *is_online = true;
+            }
+        });
+
+        online
+    });
+
+    online.get(node).copied().unwrap_or(false)
+}
+
+/// Gets the NUMA node ID for the current thread.
+///
+/// Returns the NUMA node that the current thread is running on.
+/// If NUMA is not supported, returns 0.
+///
+/// # Examples
+///
+/// ```
+/// use orengine_utils::numa::get_current_thread_numa_node;
+///
+/// let node_id = get_current_thread_numa_node();
+/// println!("Current thread is on NUMA node {}", node_id);
+/// ```
+pub fn get_current_thread_numa_node() -> usize {
+    #[cfg(all(target_os = "linux", not(miri)))]
+    {
+        use core::mem::MaybeUninit;
+
+        let mut numa_node: MaybeUninit<u32> = MaybeUninit::uninit();
+
+        unsafe {
+            libc::syscall(
+                libc::SYS_getcpu,
+                core::ptr::null::<libc::c_void>(),
+                numa_node.as_mut_ptr(),
+                core::ptr::null::<libc::c_void>(),
+            );
+        }
+
+        unsafe { numa_node.assume_init() as usize }
+    }
+
+    #[cfg(all(windows, not(miri)))]
+    {
+        super::windows::current_node()
+    }
+
+    #[cfg(any(not(any(target_os = "linux", windows)), miri))]
+    {
+        0
+    }
+}
+
+/// Gets the ID of the CPU the current thread is running on.
+///
+/// The thread can be moved to another CPU right after the call, unless it is bound.
+/// On Windows, the CPU ID is `group * 64 + number`.
+/// If it is not supported, returns 0.
+///
+/// # Examples
+///
+/// ```
+/// use orengine_utils::numa::current_cpu;
+///
+/// let cpu = current_cpu();
+/// println!("Current thread is on CPU {cpu}");
+/// ```
+pub fn current_cpu() -> usize {
+    #[cfg(all(target_os = "linux", not(miri)))]
+    {
+        use core::mem::MaybeUninit;
+
+        let mut cpu: MaybeUninit<u32> = MaybeUninit::uninit();
+
+        unsafe {
+            libc::syscall(
+                libc::SYS_getcpu,
+                cpu.as_mut_ptr(),
+                core::ptr::null::<libc::c_void>(),
+                core::ptr::null::<libc::c_void>(),
+            );
+        }
+
+        unsafe { cpu.assume_init() as usize }
+    }
+
+    #[cfg(all(windows, not(miri)))]
+    {
+        super::windows::current_cpu()
+    }
+
+    #[cfg(any(not(any(target_os = "linux", windows)), miri))]
+    {
+        0
+    }
+}
+
+#[cfg(all(test, not(miri)))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_current_thread_numa_node() {
+        let node_id = get_current_thread_numa_node();
+        assert!(node_id < 1024, "node: {node_id}");
+    }
+
+    #[test]
+    fn test_current_cpu() {
+        let cpu = current_cpu();
+        let parallelism = std::thread::available_parallelism().map_or(1, usize::from);
+
+        assert!(cpu < parallelism.max(1024), "cpu: {cpu}");
+    }
+
+    #[test]
+    fn test_for_each_in_sysfs_list() {
+        let mut numbers = Vec::new();
+
+        for_each_in_sysfs_list("0-2,5,7-8\n", |number| numbers.push(number));
+
+        assert_eq!(numbers, [0, 1, 2, 5, 7, 8]);
+    }
+
+    #[test]
+    fn test_topology() {
+        let nodes = online_nodes();
+
+        assert_eq!(nodes.len(), nodes_count());
+        assert!(nodes.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(nodes.iter().any(|&node| !cpus_of_node(node).is_empty()));
+        assert!(cpus_of_node(MAX_NUMA_NODES_SUPPORTED).is_empty());
+    }
+}
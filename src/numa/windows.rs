@@ -0,0 +1,79 @@
+use core::mem;
+use windows_sys::Win32::System::Kernel::PROCESSOR_NUMBER;
+use windows_sys::Win32::System::SystemInformation::GROUP_AFFINITY;
+use windows_sys::Win32::System::Threading::{
+    GetCurrentProcessorNumberEx, GetNumaHighestNodeNumber, GetNumaNodeProcessorMaskEx,
+    GetNumaProcessorNodeEx,
+};
+
+/// The number of processors in a processor group.
+pub(super) const PROCESSORS_PER_GROUP: usize = usize::BITS as usize;
+
+/// Returns the ID of the processor the current thread is running on.
+pub(super) fn current_cpu() -> usize {
+    let mut processor: PROCESSOR_NUMBER = unsafe { mem::zeroed() };
+
+    unsafe { GetCurrentProcessorNumberEx(&raw mut processor) };
+
+    usize::from(processor.Group) * PROCESSORS_PER_GROUP + usize::from(processor.Number)
+}
+
+/// Returns the NUMA node of the processor the current thread is running on.
+pub(super) fn current_node() -> usize {
+    let mut processor: PROCESSOR_NUMBER = unsafe { mem::zeroed() };
+    let mut node: u16 = 0;
+
+    unsafe { GetCurrentProcessorNumberEx(&raw mut processor) };
+
+    if unsafe { GetNumaProcessorNodeEx(&raw const processor, &raw mut node) } == 0 {
+        return 0;
+    }
+
+    usize::from(node)
+}
+
+/// Calls the provided function for each NUMA node. Returns `false` if the topology is unknown.
+pub(super) fn for_each_node(mut f: impl FnMut(usize)) -> bool {
+    let mut highest_node: u32 = 0;
+
+    if unsafe { GetNumaHighestNodeNumber(&raw mut highest_node) } == 0 {
+        return false;
+    }
+
+    for node in 0..=highest_node {
+        if let Ok(node) = u16::try_from(node) {
+            if node_affinity(usize::from(node)).is_some() {
+                f(usize::from(node));
+            }
+        }
+    }
+
+    true
+}
+
+/// Returns the processors of the NUMA node in its primary processor group
+/// or `None` if the node doesn't exist.
+pub(super) fn node_affinity(node: usize) -> Option<GROUP_AFFINITY> {
+    let node = u16::try_from(node).ok()?;
+    let mut affinity: GROUP_AFFINITY = unsafe { mem::zeroed() };
+
+    if unsafe { GetNumaNodeProcessorMaskEx(node, &raw mut affinity) } == 0 {
+        return None;
+    }
+
+    Some(affinity)
+}
+
+/// Sets the affinity of the current thread and returns the previous one,
+/// or `None` if it fails.
+pub(super) fn set_current_thread_affinity(affinity: &GROUP_AFFINITY) -> Option<GROUP_AFFINITY> {
+    use windows_sys::Win32::System::Threading::{GetCurrentThread, SetThreadGroupAffinity};
+
+    let mut prev: GROUP_AFFINITY = unsafe { mem::zeroed() };
+
+    if unsafe { SetThreadGroupAffinity(GetCurrentThread(), affinity, &raw mut prev) } == 0 {
+        return None;
+    }
+
+    Some(prev)
+}
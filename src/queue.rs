@@ -0,0 +1,234 @@
+//! This module contains the [`Queue`] trait that is implemented by the queues of this crate
+//! and the [`move_between`] helper built on it.
+use crate::{ArrayQueue, VecQueue};
+use core::error::Error;
+use core::fmt;
+use core::fmt::{Display, Formatter};
+
+/// A common interface of [`ArrayQueue`] and [`VecQueue`].
+///
+/// It allows writing logic (like rebalancing) that works with any of them.
+pub trait Queue<T> {
+    /// Returns the number of elements in the queue.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the queue is empty.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns how many elements can be pushed to the queue without failing.
+    fn remaining_capacity(&self) -> usize;
+
+    /// Appends an element to the back of the queue or returns `Err(value)` if the queue is full.
+    fn push(&mut self, value: T) -> Result<(), T>;
+
+    /// Removes the first element and returns it, or `None` if the queue is empty.
+    fn pop(&mut self) -> Option<T>;
+
+    /// Pops up to `limit` elements and calls the provided function with them as two slices
+    /// in logical order.
+    ///
+    /// # Safety
+    ///
+    /// The function should copy all elements from the provided slices.
+    unsafe fn take_batch<R, F: FnOnce(&[T], &[T]) -> R>(&mut self, f: F, limit: usize) -> R;
+
+    /// Pushes a slice to the queue by copying it.
+    ///
+    /// # Safety
+    ///
+    /// - The caller must ensure that `slice.len()` is not more than
+    ///   [`remaining_capacity`](Self::remaining_capacity).
+    /// - It `T` is not `Copy`, the caller should [`forget`](core::mem::forget) the values.
+    unsafe fn extend_from_slice_unchecked(&mut self, slice: &[T]);
+}
+
+impl<T, const N: usize> Queue<T> for ArrayQueue<T, N> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn remaining_capacity(&self) -> usize {
+        N - self.len()
+    }
+
+    #[inline]
+    fn push(&mut self, value: T) -> Result<(), T> {
+        self.push(value)
+    }
+
+    #[inline]
+    fn pop(&mut self) -> Option<T> {
+        self.pop()
+    }
+
+    #[inline]
+    unsafe fn take_batch<R, F: FnOnce(&[T], &[T]) -> R>(&mut self, f: F, limit: usize) -> R {
+        unsafe { self.take_batch(f, limit) }
+    }
+
+    #[inline]
+    unsafe fn extend_from_slice_unchecked(&mut self, slice: &[T]) {
+        let res = unsafe { self.extend_from_slice(slice) };
+
+        debug_assert!(res.is_ok(), "Tried to extend a full array queue");
+    }
+}
+
+impl<T> Queue<T> for VecQueue<T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn remaining_capacity(&self) -> usize {
+        self.max_len().unwrap_or(usize::MAX) - self.len()
+    }
+
+    #[inline]
+    fn push(&mut self, value: T) -> Result<(), T> {
+        self.try_push(value)
+    }
+
+    #[inline]
+    fn pop(&mut self) -> Option<T> {
+        self.pop()
+    }
+
+    #[inline]
+    unsafe fn take_batch<R, F: FnOnce(&[T], &[T]) -> R>(&mut self, f: F, limit: usize) -> R {
+        unsafe { self.take_batch(f, limit) }
+    }
+
+    #[inline]
+    unsafe fn extend_from_slice_unchecked(&mut self, slice: &[T]) {
+        unsafe { self.extend_from_slice(slice) };
+    }
+}
+
+/// Error returned by [`move_between`] when it can't move the requested number of elements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveBetweenError {
+    /// The source queue contains fewer elements than requested.
+    NotEnoughItems,
+    /// The destination queue does not have enough space.
+    NotEnoughSpace,
+}
+
+impl Display for MoveBetweenError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotEnoughItems => write!(f, "Not enough items in the source queue"),
+            Self::NotEnoughSpace => write!(f, "Not enough space in the destination queue"),
+        }
+    }
+}
+
+impl Error for MoveBetweenError {}
+
+/// Moves exactly `n` elements from the front of `src` to the back of `dst` preserving their order,
+/// or moves nothing and returns an error.
+///
+/// Both preconditions are checked before moving, and the elements are moved with
+/// a single batch copy, so an element can't be lost or duplicated.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::{move_between, ArrayQueue, MoveBetweenError, VecQueue};
+///
+/// let mut src = VecQueue::new();
+/// let mut dst = ArrayQueue::<u32, 4>::new();
+///
+/// for i in 0..6 {
+///     src.push(i);
+/// }
+///
+/// assert_eq!(move_between(&mut src, &mut dst, 5), Err(MoveBetweenError::NotEnoughSpace));
+/// assert_eq!(src.len(), 6);
+///
+/// move_between(&mut src, &mut dst, 3).unwrap();
+///
+/// assert_eq!(src.len(), 3);
+/// assert_eq!(dst.pop(), Some(0));
+/// assert_eq!(dst.pop(), Some(1));
+/// assert_eq!(dst.pop(), Some(2));
+/// ```
+pub fn move_between<T, Src, Dst>(
+    src: &mut Src,
+    dst: &mut Dst,
+    n: usize,
+) -> Result<(), MoveBetweenError>
+where
+    Src: Queue<T> + ?Sized,
+    Dst: Queue<T> + ?Sized,
+{
+    if src.len() < n {
+        return Err(MoveBetweenError::NotEnoughItems);
+    }
+
+    if dst.remaining_capacity() < n {
+        return Err(MoveBetweenError::NotEnoughSpace);
+    }
+
+    unsafe {
+        src.take_batch(
+            |first, second| {
+                dst.extend_from_slice_unchecked(first);
+                dst.extend_from_slice_unchecked(second);
+            },
+            n,
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::{String, ToString};
+
+    #[test]
+    fn test_move_between_wrapped_queues() {
+        let mut src = ArrayQueue::<String, 4>::new();
+        let mut dst = VecQueue::new();
+
+        for i in 0..4 {
+            src.push(i.to_string()).unwrap();
+        }
+
+        src.pop().unwrap();
+        src.pop().unwrap();
+        src.push("4".to_string()).unwrap();
+        src.push("5".to_string()).unwrap();
+
+        assert_eq!(
+            move_between(&mut src, &mut dst, 5),
+            Err(MoveBetweenError::NotEnoughItems)
+        );
+
+        move_between(&mut src, &mut dst, 3).unwrap();
+
+        assert_eq!(src.len(), 1);
+        assert_eq!(&*dst.snapshot(), &["2", "3", "4"]);
+
+        move_between(&mut dst, &mut src, 3).unwrap();
+
+        assert!(dst.is_empty());
+        assert_eq!(src.len(), 4);
+        assert_eq!(
+            move_between(&mut dst, &mut src, 0),
+            Ok(()),
+            "moving zero elements always succeeds"
+        );
+
+        let collected: alloc::vec::Vec<_> = src.iter().cloned().collect();
+
+        assert_eq!(collected, ["5", "2", "3", "4"]);
+    }
+}
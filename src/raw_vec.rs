@@ -0,0 +1,174 @@
+//! This module contains [`RawVec`], the growable buffer of [`AlignedVec`](crate::aligned::AlignedVec)
+//! and [`NumaVec`](crate::numa::NumaVec), which differ only in how they allocate memory.
+use alloc::alloc::Layout;
+use core::marker::PhantomData;
+use core::ptr::{self, NonNull};
+use core::slice;
+
+/// Allocates buffers of a [`RawVec`].
+pub(crate) trait RawVecAlloc {
+    /// The error of a failed allocation.
+    type Error;
+
+    /// Returns the error of a capacity that overflows `isize::MAX` bytes.
+    fn capacity_overflow() -> Self::Error;
+
+    /// Allocates memory for the non-zero-sized layout.
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, Self::Error>;
+
+    /// Deallocates memory allocated by [`allocate`](Self::allocate).
+    ///
+    /// # Safety
+    ///
+    /// The memory must be allocated by [`allocate`](Self::allocate) with the same layout.
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout);
+}
+
+/// A growable array which buffer is allocated with the provided [`RawVecAlloc`].
+pub(crate) struct RawVec<T, A: RawVecAlloc> {
+    ptr: NonNull<T>,
+    len: usize,
+    cap: usize,
+    alloc: A,
+    _marker: PhantomData<T>,
+}
+
+impl<T, A: RawVecAlloc> RawVec<T, A> {
+    /// Creates a new empty `RawVec` without allocating.
+    pub(crate) const fn new(alloc: A) -> Self {
+        Self {
+            ptr: NonNull::dangling(),
+            len: 0,
+            cap: if size_of::<T>() == 0 { usize::MAX } else { 0 },
+            alloc,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the allocator.
+    #[cfg_attr(
+        any(not(feature = "numa"), feature = "no_std"),
+        allow(dead_code, reason = "It is used only by NumaVec")
+    )]
+    pub(crate) const fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Returns the number of elements.
+    pub(crate) const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the number of elements that can be stored without reallocation.
+    pub(crate) const fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    /// Returns a raw pointer to the buffer.
+    pub(crate) const fn as_ptr(&self) -> *const T {
+        self.ptr.as_ptr()
+    }
+
+    /// Returns the elements as a slice.
+    pub(crate) const fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// Returns the elements as a mutable slice.
+    pub(crate) const fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// Tries to reserve the capacity for at least `additional` more elements.
+    pub(crate) fn try_reserve(&mut self, additional: usize) -> Result<(), A::Error> {
+        let required = self
+            .len
+            .checked_add(additional)
+            .ok_or_else(A::capacity_overflow)?;
+
+        if required <= self.cap {
+            return Ok(());
+        }
+
+        let new_cap = required.max(self.cap.saturating_mul(2)).max(4);
+        let new_layout = Layout::array::<T>(new_cap).map_err(|_| A::capacity_overflow())?;
+        let new_ptr = self.alloc.allocate(new_layout)?.cast::<T>();
+
+        if self.cap > 0 {
+            unsafe {
+                ptr::copy_nonoverlapping(self.ptr.as_ptr(), new_ptr.as_ptr(), self.len);
+
+                self.deallocate();
+            }
+        }
+
+        self.ptr = new_ptr;
+        self.cap = new_cap;
+
+        Ok(())
+    }
+
+    /// Appends an element to the back.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the buffer is full and can't grow.
+    pub(crate) fn try_push(&mut self, value: T) -> Result<(), A::Error> {
+        if self.len == self.cap {
+            self.try_reserve(1)?;
+        }
+
+        unsafe { self.ptr.add(self.len).write(value) };
+
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Removes the last element and returns it, or `None` if it is empty.
+    pub(crate) fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+
+        Some(unsafe { self.ptr.add(self.len).read() })
+    }
+
+    /// Drops all elements, keeping the capacity.
+    pub(crate) fn clear(&mut self) {
+        let elements = ptr::slice_from_raw_parts_mut(self.ptr.as_ptr(), self.len);
+
+        self.len = 0;
+
+        unsafe { ptr::drop_in_place(elements) };
+    }
+
+    /// Deallocates the buffer.
+    ///
+    /// # Safety
+    ///
+    /// The buffer must be allocated, and it must not be used after this call.
+    unsafe fn deallocate(&mut self) {
+        unsafe {
+            self.alloc.deallocate(
+                self.ptr.cast(),
+                Layout::array::<T>(self.cap).unwrap_unchecked(),
+            );
+        }
+    }
+}
+
+impl<T, A: RawVecAlloc> Drop for RawVec<T, A> {
+    fn drop(&mut self) {
+        self.clear();
+
+        if size_of::<T>() != 0 && self.cap > 0 {
+            unsafe { self.deallocate() };
+        }
+    }
+}
+
+unsafe impl<T: Send, A: RawVecAlloc + Send> Send for RawVec<T, A> {}
+unsafe impl<T: Sync, A: RawVecAlloc + Sync> Sync for RawVec<T, A> {}
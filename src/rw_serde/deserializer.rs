@@ -284,7 +284,7 @@ impl<'de, Src: Read> de::Deserializer<'de> for &mut RWDeserializer<Src> {
 
         #[allow(clippy::uninit_vec, reason = "We will imediatly fill it.")]
         unsafe {
-            buf.set_len(buf.capacity())
+            buf.set_len(buf.capacity());
         };
 
         #[allow(clippy::read_zero_byte_vec, reason = "False positive.")]
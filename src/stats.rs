@@ -0,0 +1,193 @@
+//! This module provides statistical primitives for latency tracking.
+//!
+//! - The [`Reservoir`] keeps a bounded, recency-biased sample of durations.
+use crate::cheap_random::cheap_random_with_current_u64;
+use crate::{ArrayBuffer, OrengineInstant};
+use core::num::NonZeroU64;
+use std::time::Duration;
+
+/// An exponentially-decaying reservoir sampler of durations.
+///
+/// It keeps at most `K` samples inline (without allocations) and is suitable for
+/// tail-latency forensics: every recorded duration has a chance to be kept,
+/// but recent durations are more likely to be in the reservoir than old ones.
+///
+/// It works as the classic "Algorithm R", but the number of seen samples decays
+/// exponentially with the configured half-life. Therefore, after a quiet period,
+/// new samples replace old ones with a higher probability.
+///
+/// Each [`record`](Self::record) is O(1).
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::stats::Reservoir;
+/// use std::time::Duration;
+///
+/// let mut reservoir = Reservoir::<128>::new(Duration::from_secs(60));
+///
+/// for i in 0..1000 {
+///     reservoir.record(Duration::from_micros(i));
+/// }
+///
+/// assert_eq!(reservoir.len(), 128);
+/// assert!(reservoir.percentile(0.99).unwrap() >= reservoir.percentile(0.5).unwrap());
+/// ```
+pub struct Reservoir<const K: usize> {
+    samples: ArrayBuffer<Duration, K>,
+    /// The number of seen samples with exponential decay applied.
+    decayed_count: f64,
+    half_life_secs: f64,
+    last_update: OrengineInstant,
+    rng_state: NonZeroU64,
+}
+
+impl<const K: usize> Reservoir<K> {
+    /// Creates a new empty `Reservoir` with the provided half-life of the seen samples count.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `half_life` is zero.
+    pub fn new(half_life: Duration) -> Self {
+        assert!(!half_life.is_zero(), "half-life must be non-zero");
+
+        Self {
+            samples: ArrayBuffer::new(),
+            decayed_count: 0.0,
+            half_life_secs: half_life.as_secs_f64(),
+            last_update: OrengineInstant::now(),
+            rng_state: NonZeroU64::new(0x9E37_79B9_7F4A_7C15).unwrap(),
+        }
+    }
+
+    /// Records the provided sample using the current time.
+    #[inline]
+    pub fn record(&mut self, sample: Duration) {
+        self.record_at(sample, OrengineInstant::now());
+    }
+
+    /// Records the provided sample as if it was recorded at `now`.
+    ///
+    /// It allows reusing the already read time.
+    pub fn record_at(&mut self, sample: Duration, now: OrengineInstant) {
+        let elapsed = now.saturating_duration_since(self.last_update);
+
+        if !elapsed.is_zero() {
+            self.decayed_count *= (-elapsed.as_secs_f64() / self.half_life_secs).exp2();
+            self.last_update = now;
+        }
+
+        self.decayed_count += 1.0;
+
+        if self.samples.push(sample).is_ok() {
+            return;
+        }
+
+        #[allow(
+            clippy::cast_precision_loss,
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            reason = "Only an approximately uniform index is needed"
+        )]
+        let idx = {
+            let random =
+                cheap_random_with_current_u64(&mut self.rng_state) as f64 / u64::MAX as f64;
+
+            (random * self.decayed_count) as usize
+        };
+
+        if idx < K {
+            self.samples[idx] = sample;
+        }
+    }
+
+    /// Returns the number of samples in the reservoir.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Returns `true` if the reservoir is empty.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Returns the samples in the reservoir in an unspecified order.
+    pub fn samples(&self) -> &[Duration] {
+        &self.samples
+    }
+
+    /// Returns the maximum sample in the reservoir or `None` if it is empty.
+    pub fn max(&self) -> Option<Duration> {
+        self.samples.iter().max().copied()
+    }
+
+    /// Returns the sample at the provided quantile (from `0.0` to `1.0`)
+    /// or `None` if the reservoir is empty.
+    pub fn percentile(&self, quantile: f64) -> Option<Duration> {
+        if self.samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted = self.samples.clone();
+
+        sorted.sort_unstable();
+
+        #[allow(
+            clippy::cast_precision_loss,
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            reason = "The index is clamped to the bounds"
+        )]
+        let idx = ((quantile.clamp(0.0, 1.0) * (sorted.len() - 1) as f64).round() as usize)
+            .min(sorted.len() - 1);
+
+        Some(sorted[idx])
+    }
+
+    /// Removes all samples and resets the decayed count.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        self.decayed_count = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reservoir_prefers_recent_samples() {
+        let mut reservoir = Reservoir::<16>::new(Duration::from_secs(1));
+        let start = OrengineInstant::now();
+
+        assert_eq!(reservoir.percentile(0.5), None);
+
+        for _ in 0..10_000 {
+            reservoir.record_at(Duration::from_millis(1), start);
+        }
+
+        assert_eq!(reservoir.len(), 16);
+        assert_eq!(reservoir.max(), Some(Duration::from_millis(1)));
+
+        // After a long quiet period, the decayed count is small, so new samples
+        // replace almost all old ones.
+        let later = start + Duration::from_secs(60);
+
+        for _ in 0..1_000 {
+            reservoir.record_at(Duration::from_millis(5), later);
+        }
+
+        let recent = reservoir
+            .samples()
+            .iter()
+            .filter(|sample| **sample == Duration::from_millis(5))
+            .count();
+
+        assert!(recent > 8, "recent: {recent}");
+        assert_eq!(reservoir.percentile(1.0), Some(Duration::from_millis(5)));
+
+        reservoir.clear();
+
+        assert!(reservoir.is_empty());
+    }
+}
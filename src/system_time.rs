@@ -0,0 +1,206 @@
+//! This module contains the [`OrengineSystemTime`].
+use std::fmt;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+use std::time::{Duration, SystemTime};
+
+/// A wall clock. It can be converted to/from `std::time::SystemTime`.
+///
+/// It is a companion of [`OrengineInstant`](crate::OrengineInstant) with the same API,
+/// it stores nanoseconds since [`UNIX_EPOCH`](Self::UNIX_EPOCH) in a `u64`,
+/// so it weights 8 bytes on all platforms and can represent times until the year 2554.
+///
+/// Unlike [`OrengineInstant`](crate::OrengineInstant), it is not monotonic,
+/// so it is suitable for logs and TTLs, but not for measuring durations.
+///
+/// # Example
+///
+/// ```rust
+/// use orengine_utils::OrengineSystemTime;
+/// use std::time::{Duration, SystemTime};
+///
+/// let expires_at = OrengineSystemTime::now() + Duration::from_secs(60);
+///
+/// assert!(expires_at > OrengineSystemTime::now());
+/// assert!(SystemTime::from(expires_at) > SystemTime::now());
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OrengineSystemTime {
+    nanos: u64,
+}
+
+impl OrengineSystemTime {
+    /// An anchor in time which is "1970-01-01 00:00:00 UTC".
+    pub const UNIX_EPOCH: Self = Self { nanos: 0 };
+
+    /// Creates a new `OrengineSystemTime` from nanoseconds since [`UNIX_EPOCH`](Self::UNIX_EPOCH).
+    pub const fn from_nanos_since_epoch(nanos: u64) -> Self {
+        Self { nanos }
+    }
+
+    /// Returns nanoseconds since [`UNIX_EPOCH`](Self::UNIX_EPOCH).
+    pub const fn as_nanos_since_epoch(self) -> u64 {
+        self.nanos
+    }
+
+    /// Returns the current time of the `realtime` clock.
+    pub fn now() -> Self {
+        #[cfg(unix)]
+        #[allow(clippy::cast_sign_loss, reason = "It is after the UNIX epoch")]
+        {
+            let ts = rustix::time::clock_gettime(rustix::time::ClockId::Realtime);
+
+            Self {
+                nanos: ts.tv_sec as u64 * 1_000_000_000 + ts.tv_nsec as u64,
+            }
+        }
+
+        #[cfg(not(unix))]
+        {
+            Self::from(SystemTime::now())
+        }
+    }
+
+    /// Returns the amount of time elapsed from another time to this one, or `None` if that
+    /// time is later than this one.
+    ///
+    /// The wall clock can be adjusted backwards, so it can return `None`
+    /// even if `earlier` was created before `self`.
+    pub fn checked_duration_since(&self, earlier: impl Into<Self>) -> Option<Duration> {
+        self.nanos
+            .checked_sub(earlier.into().nanos)
+            .map(Duration::from_nanos)
+    }
+
+    /// Returns the amount of time elapsed from another time to this one, or zero duration if
+    /// that time is later than this one.
+    pub fn saturating_duration_since(&self, earlier: impl Into<Self>) -> Duration {
+        self.checked_duration_since(earlier).unwrap_or_default()
+    }
+
+    /// Returns the amount of time elapsed from another time to this one, or zero duration if
+    /// that time is later than this one.
+    pub fn duration_since(&self, earlier: impl Into<Self>) -> Duration {
+        self.saturating_duration_since(earlier)
+    }
+
+    /// Returns the amount of time elapsed since this time, or zero duration if
+    /// the wall clock was adjusted to an earlier time.
+    pub fn elapsed(&self) -> Duration {
+        Self::now() - *self
+    }
+
+    /// Returns `Some(t)` where `t` is the time `self + duration` if `t` can be represented,
+    /// `None` otherwise.
+    pub fn checked_add(&self, duration: Duration) -> Option<Self> {
+        let total_nanos = u64::try_from(duration.as_nanos()).ok()?;
+
+        Some(Self {
+            nanos: self.nanos.checked_add(total_nanos)?,
+        })
+    }
+
+    /// Returns `Some(t)` where `t` is the time `self - duration` if `t` can be represented,
+    /// `None` otherwise.
+    pub fn checked_sub(&self, duration: Duration) -> Option<Self> {
+        let total_nanos = u64::try_from(duration.as_nanos()).ok()?;
+
+        Some(Self {
+            nanos: self.nanos.checked_sub(total_nanos)?,
+        })
+    }
+}
+
+impl Add<Duration> for OrengineSystemTime {
+    type Output = Self;
+
+    /// # Panics
+    ///
+    /// This function may panic if the resulting point in time cannot be represented.
+    /// See [`OrengineSystemTime::checked_add`] for a version without a panic.
+    fn add(self, other: Duration) -> Self {
+        self.checked_add(other)
+            .expect("overflow when adding duration to system time")
+    }
+}
+
+impl AddAssign<Duration> for OrengineSystemTime {
+    fn add_assign(&mut self, other: Duration) {
+        *self = *self + other;
+    }
+}
+
+impl Sub<Duration> for OrengineSystemTime {
+    type Output = Self;
+
+    fn sub(self, other: Duration) -> Self {
+        self.checked_sub(other)
+            .expect("overflow when subtracting duration from system time")
+    }
+}
+
+impl SubAssign<Duration> for OrengineSystemTime {
+    fn sub_assign(&mut self, other: Duration) {
+        *self = *self - other;
+    }
+}
+
+impl Sub<Self> for OrengineSystemTime {
+    type Output = Duration;
+
+    /// Returns the amount of time elapsed from another time to this one,
+    /// or zero duration if that time is later than this one.
+    fn sub(self, other: Self) -> Duration {
+        self.duration_since(other)
+    }
+}
+
+impl fmt::Debug for OrengineSystemTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OrengineSystemTime")
+            .field("nanos_since_epoch", &self.nanos)
+            .finish()
+    }
+}
+
+impl From<OrengineSystemTime> for SystemTime {
+    fn from(val: OrengineSystemTime) -> Self {
+        Self::UNIX_EPOCH + Duration::from_nanos(val.nanos)
+    }
+}
+
+impl From<SystemTime> for OrengineSystemTime {
+    /// Converts the `SystemTime` saturating it to the representable range,
+    /// so times before the UNIX epoch become [`UNIX_EPOCH`](Self::UNIX_EPOCH).
+    fn from(val: SystemTime) -> Self {
+        let nanos = val
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |dur| u64::try_from(dur.as_nanos()).unwrap_or(u64::MAX));
+
+        Self { nanos }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OrengineSystemTime;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_system_time_conversions() {
+        let system_time = SystemTime::now();
+        let time = OrengineSystemTime::from(system_time);
+
+        assert_eq!(SystemTime::from(time), system_time);
+        assert!(OrengineSystemTime::now().duration_since(time) < Duration::from_secs(60));
+
+        let later = time + Duration::from_millis(1);
+
+        assert_eq!(later - time, Duration::from_millis(1));
+        assert_eq!(time.checked_duration_since(later), None);
+        assert_eq!(time - later, Duration::ZERO);
+        assert_eq!(
+            OrengineSystemTime::from(SystemTime::UNIX_EPOCH - Duration::from_secs(1)),
+            OrengineSystemTime::UNIX_EPOCH
+        );
+    }
+}
@@ -0,0 +1,167 @@
+//! This module allows overriding the clock of [`OrengineInstant`] in tests.
+//!
+//! It is available with the `test_clock` feature.
+//!
+//! [`set_clock_source`] overrides the clock of the current thread:
+//! [`OrengineInstant::now`], [`OrengineInstant::now_coarse`] and [`OrengineInstant::now_tsc`]
+//! return the time of the provided [`ClockSource`] until the returned [`ClockSourceGuard`]
+//! is dropped. The override is thread-local, so tests running in parallel don't affect
+//! each other, but threads spawned by a test use the real clock.
+//!
+//! [`MockClock`] is a [`ClockSource`] that is frozen until it is advanced explicitly,
+//! so timeout logic can be tested deterministically without real sleeps.
+//!
+//! # Example
+//!
+//! ```rust
+//! use orengine_utils::test_clock::{set_clock_source, MockClock};
+//! use orengine_utils::OrengineInstant;
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! let clock = Arc::new(MockClock::new());
+//! let _guard = set_clock_source(clock.clone());
+//!
+//! let deadline = OrengineInstant::now() + Duration::from_secs(30);
+//!
+//! assert!(OrengineInstant::now() < deadline);
+//!
+//! clock.advance(Duration::from_secs(30));
+//!
+//! assert_eq!(OrengineInstant::now(), deadline);
+//! ```
+use crate::{AtomicOrengineInstant, OrengineInstant};
+use alloc::sync::Arc;
+use core::cell::RefCell;
+use core::sync::atomic::Ordering;
+use std::time::Duration;
+
+/// A source of the current time for [`OrengineInstant`].
+pub trait ClockSource: Send + Sync {
+    /// Returns the current instant.
+    fn now(&self) -> OrengineInstant;
+}
+
+/// A [`ClockSource`] that is frozen until it is advanced explicitly.
+///
+/// Read the [`module-level documentation`](crate::test_clock) for more details.
+#[derive(Debug)]
+pub struct MockClock {
+    now: AtomicOrengineInstant,
+}
+
+impl MockClock {
+    /// Creates a new `MockClock` frozen at the current real instant.
+    pub fn new() -> Self {
+        Self::starting_at(real_now())
+    }
+
+    /// Creates a new `MockClock` frozen at the provided instant.
+    pub fn starting_at(instant: OrengineInstant) -> Self {
+        Self {
+            now: AtomicOrengineInstant::new(instant),
+        }
+    }
+
+    /// Moves the clock forward by the provided duration.
+    pub fn advance(&self, duration: Duration) {
+        self.set(self.now() + duration);
+    }
+
+    /// Sets the current instant of the clock.
+    pub fn set(&self, instant: OrengineInstant) {
+        self.now.store(instant, Ordering::Relaxed);
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClockSource for MockClock {
+    fn now(&self) -> OrengineInstant {
+        self.now.load(Ordering::Relaxed)
+    }
+}
+
+thread_local! {
+    static CLOCK_SOURCE: RefCell<Option<Arc<dyn ClockSource>>> = const { RefCell::new(None) };
+}
+
+/// A guard returned by [`set_clock_source`].
+///
+/// It restores the previous clock of the thread when it is dropped.
+#[must_use = "the clock source is reset when the guard is dropped"]
+pub struct ClockSourceGuard {
+    prev: Option<Arc<dyn ClockSource>>,
+}
+
+impl Drop for ClockSourceGuard {
+    fn drop(&mut self) {
+        let prev = self.prev.take();
+
+        CLOCK_SOURCE.with(|source| *source.borrow_mut() = prev);
+    }
+}
+
+/// Overrides the clock of [`OrengineInstant`] for the current thread
+/// until the returned guard is dropped.
+///
+/// Read the [`module-level documentation`](crate::test_clock) for more details.
+pub fn set_clock_source(source: Arc<dyn ClockSource>) -> ClockSourceGuard {
+    let prev = CLOCK_SOURCE.with(|cell| cell.borrow_mut().replace(source));
+
+    ClockSourceGuard { prev }
+}
+
+/// Returns the instant of the overridden clock of the current thread
+/// or `None` if it is not overridden.
+#[inline]
+pub(crate) fn overridden_now() -> Option<OrengineInstant> {
+    CLOCK_SOURCE.with(|source| source.borrow().as_ref().map(|source| source.now()))
+}
+
+/// Returns the current instant of the real clock.
+fn real_now() -> OrengineInstant {
+    let _guard = ClockSourceGuard {
+        prev: CLOCK_SOURCE.with(|source| source.borrow_mut().take()),
+    };
+
+    OrengineInstant::now()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_clock_is_thread_local() {
+        let start = OrengineInstant::now();
+        let clock = Arc::new(MockClock::starting_at(start));
+
+        {
+            let _guard = set_clock_source(clock.clone());
+
+            clock.advance(Duration::from_secs(3600));
+
+            assert_eq!(
+                OrengineInstant::now_coarse() - start,
+                Duration::from_secs(3600)
+            );
+            assert!(std::thread::spawn(OrengineInstant::now).join().unwrap() < clock.now());
+
+            let nested = Arc::new(MockClock::starting_at(start));
+            let nested_guard = set_clock_source(nested);
+
+            assert_eq!(OrengineInstant::now_tsc(), start);
+
+            drop(nested_guard);
+
+            assert_eq!(OrengineInstant::now(), clock.now());
+        }
+
+        assert!(OrengineInstant::now() < clock.now());
+    }
+}
@@ -95,9 +95,9 @@ pub struct Node<E: TreapEntry> {
     max_filter: E::FilteringKey,
 
     priority: u32,
-    left: Option<NonNull<Node<E>>>,
-    right: Option<NonNull<Node<E>>>,
-    parent: Option<NonNull<Node<E>>>,
+    left: Option<NonNull<Self>>,
+    right: Option<NonNull<Self>>,
+    parent: Option<NonNull<Self>>,
 }
 
 impl<E: TreapEntry> Node<E> {
@@ -2116,9 +2116,10 @@ mod tests {
     fn insert_and_remove_rand() {
         const N: usize = if !cfg!(miri) { 2000 } else { 20 };
 
-        let mut state =
-            NonZeroU32::new((crate::instant::OrengineInstant::now().into_u64() % 1000) as u32 + 1)
-                .unwrap();
+        let mut state = NonZeroU32::new(
+            (crate::instant::OrengineInstant::now().as_nanos_since_boot() % 1000) as u32 + 1,
+        )
+        .unwrap();
 
         for _ in 0..10 {
             let mut key_value_pairs = std::collections::HashMap::with_capacity(N);
@@ -1,9 +1,25 @@
 //! This module provides the [`VecQueue`] an vector-based queue implementation.
 
 use crate::hints::unlikely;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
 use core::ptr::slice_from_raw_parts;
 use core::{mem, ptr};
 
+/// The capacity that is allocated by the first push to an empty [`VecQueue`].
+/// The queue is never shrunk below it.
+const DEFAULT_CAPACITY: usize = 16;
+
+/// Panics because a value is pushed to a full bounded [`VecQueue`].
+#[cold]
+#[inline(never)]
+#[track_caller]
+fn panic_full_bounded_queue<T>(value: T) -> ! {
+    drop(value);
+
+    panic!("Tried to push to a full bounded VecQueue, use `try_push` instead");
+}
+
 /// A queue that uses a vector to store the elements.
 ///
 /// It is similar to [`std::collections::VecDeque`], but it provides a few additional methods
@@ -16,6 +32,11 @@ pub struct VecQueue<T> {
     tail: usize,
     capacity: usize,
     mask: usize,
+    max_len: usize,
+    shrink_after_pops: usize,
+    shrink_denominator: usize,
+    shrink_floor: usize,
+    low_occupancy_pops: usize,
 }
 
 impl<T> VecQueue<T> {
@@ -33,6 +54,10 @@ impl<T> VecQueue<T> {
     /// Deallocates a vector with the given capacity.
     #[cold]
     fn deallocate(ptr: *mut T, capacity: usize) {
+        if capacity == 0 {
+            return;
+        }
+
         unsafe {
             alloc::alloc::dealloc(
                 ptr.cast(),
@@ -51,33 +76,84 @@ impl<T> VecQueue<T> {
     /// Returns the physical index for the given index.
     #[inline(always)]
     fn get_physical_index(&self, index: usize) -> usize {
-        debug_assert!(self.capacity.is_power_of_two());
+        debug_assert!(self.capacity == 0 || self.capacity.is_power_of_two());
 
         index & self.mask
     }
 
-    /// Creates a new `VecQueue` without any capacity.
-    pub const fn new_const() -> Self {
+    /// Creates a new empty `VecQueue`.
+    ///
+    /// It doesn't allocate until the first push, then it allocates the default capacity.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::VecQueue;
+    ///
+    /// let mut queue = VecQueue::new();
+    ///
+    /// assert_eq!(queue.capacity(), 0);
+    ///
+    /// queue.push(1);
+    ///
+    /// assert_eq!(queue.capacity(), 16);
+    /// assert_eq!(queue.pop(), Some(1));
+    /// ```
+    pub const fn new() -> Self {
         Self {
             ptr: ptr::null_mut(),
             head: 0,
             tail: 0,
             capacity: 0,
             mask: 0,
+            max_len: usize::MAX,
+            shrink_after_pops: 0,
+            shrink_denominator: 4,
+            shrink_floor: DEFAULT_CAPACITY,
+            low_occupancy_pops: 0,
         }
     }
 
-    /// Creates a new `VecQueue` with the default capacity.
-    pub fn new() -> Self {
-        const DEFAULT_CAPACITY: usize = 16;
+    /// Creates a new empty `VecQueue` that can't contain more than `max_len` elements.
+    ///
+    /// When it is full, [`try_push`](Self::try_push) and
+    /// [`try_push_priority_value`](Self::try_push_priority_value) return an error,
+    /// and [`push`](Self::push) and [`push_priority_value`](Self::push_priority_value) panic.
+    ///
+    /// Like [`new`](Self::new), it doesn't allocate until the first push,
+    /// and the allocated capacity never exceeds `max_len.next_power_of_two()`
+    /// unless more is requested with [`reserve`](Self::reserve) or [`extend_to`](Self::extend_to).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::VecQueue;
+    ///
+    /// let mut queue = VecQueue::bounded(2);
+    ///
+    /// assert_eq!(queue.try_push(1), Ok(()));
+    /// assert_eq!(queue.try_push(2), Ok(()));
+    /// assert_eq!(queue.try_push(3), Err(3));
+    /// assert!(queue.is_full());
+    ///
+    /// assert_eq!(queue.pop(), Some(1));
+    /// assert_eq!(queue.try_push_priority_value(0), Ok(()));
+    /// assert_eq!(&*queue.snapshot(), &[0, 2]);
+    /// assert_eq!(queue.capacity(), 2);
+    /// ```
+    pub const fn bounded(max_len: usize) -> Self {
+        let mut queue = Self::new();
 
-        Self {
-            ptr: Self::allocate(DEFAULT_CAPACITY),
-            head: 0,
-            tail: 0,
-            capacity: DEFAULT_CAPACITY,
-            mask: Self::get_mask_for_capacity(DEFAULT_CAPACITY),
-        }
+        queue.max_len = max_len;
+
+        queue
+    }
+
+    /// Creates a new `VecQueue` without any capacity.
+    ///
+    /// It is the same as [`new`](Self::new).
+    pub const fn new_const() -> Self {
+        Self::new()
     }
 
     /// Returns the number of elements in the queue.
@@ -90,25 +166,50 @@ impl<T> VecQueue<T> {
         self.head == self.tail
     }
 
+    /// Returns the maximum number of elements if the queue is [`bounded`](Self::bounded).
+    pub fn max_len(&self) -> Option<usize> {
+        if self.max_len == usize::MAX {
+            None
+        } else {
+            Some(self.max_len)
+        }
+    }
+
+    /// Returns whether the queue is [`bounded`](Self::bounded) and contains
+    /// the maximum number of elements.
+    pub fn is_full(&self) -> bool {
+        self.len() == self.max_len
+    }
+
     /// Reserves capacity for at least additional more elements to be inserted in the given `VecQueue`.
     ///
     /// The collection may reserve more space to speculatively avoid frequent reallocations.
     /// After calling reserve, capacity will be greater than or equal to `self.len() + additional`.
     ///
     /// Does nothing if capacity is already sufficient.
+    ///
+    /// The [shrink policy](Self::set_shrink_threshold) never shrinks the queue
+    /// below the reserved capacity.
     pub fn reserve(&mut self, additional: usize) {
+        self.shrink_floor = self.shrink_floor.max(self.len() + additional);
+
+        self.grow_to_fit(additional);
+    }
+
+    /// Grows the queue to fit `additional` more elements if it is necessary.
+    fn grow_to_fit(&mut self, additional: usize) {
         let needed = self.len() + additional;
         if needed <= self.capacity {
             return;
         }
 
-        let mut new_capacity = self.capacity * 2;
+        let mut new_capacity = (self.capacity * 2).max(self.min_capacity());
 
         while unlikely(needed > new_capacity) {
             new_capacity *= 2;
         }
 
-        self.extend_to(new_capacity);
+        self.reallocate(new_capacity);
     }
 
     /// Extends the vector to the given capacity.
@@ -116,20 +217,33 @@ impl<T> VecQueue<T> {
     /// # Panics
     ///
     /// Panics if the provided capacity is not a power of two or is less than the current capacity.
+    /// If the queue is not allocated yet, zero capacity allocates the same capacity
+    /// as the first push instead.
+    ///
+    /// The [shrink policy](Self::set_shrink_threshold) never shrinks the queue
+    /// below the provided capacity.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::VecQueue;
+    ///
+    /// let mut queue = VecQueue::<u32>::new();
+    ///
+    /// queue.extend_to(0);
+    ///
+    /// assert!(queue.capacity() > 0);
+    ///
+    /// queue.extend_to(64);
+    ///
+    /// assert_eq!(queue.capacity(), 64);
+    /// ```
     #[inline(never)]
     #[cold]
     #[track_caller]
     pub fn extend_to(&mut self, capacity: usize) {
-        #[inline(never)]
-        #[cold]
-        fn extend_from_zero<T>(queue: &mut VecQueue<T>, capacity: usize) {
-            queue.mask = VecQueue::<T>::get_mask_for_capacity(capacity);
-            queue.ptr = VecQueue::<T>::allocate(capacity);
-            queue.capacity = capacity;
-        }
-
         if unlikely(self.capacity == 0 && capacity == 0) {
-            extend_from_zero(self, 4);
+            self.grow();
 
             return;
         }
@@ -140,28 +254,48 @@ impl<T> VecQueue<T> {
         );
         assert!(capacity > self.capacity);
 
-        let new_ptr = Self::allocate(capacity);
-        let len = self.len();
+        self.shrink_floor = self.shrink_floor.max(capacity);
+        self.reallocate(capacity);
+    }
 
-        unsafe {
-            let phys_head = self.get_physical_index(self.head);
-            let phys_tail = self.get_physical_index(self.tail);
-            let src = self.ptr.add(phys_head);
-            let dst = new_ptr;
+    /// Doubles the capacity or allocates the minimum capacity if the queue is not allocated yet.
+    #[inline(never)]
+    #[cold]
+    fn grow(&mut self) {
+        self.reallocate((self.capacity * 2).max(self.min_capacity()));
+    }
 
-            if phys_head < phys_tail {
-                ptr::copy(src, dst, len);
-            } else {
-                ptr::copy(src, dst, self.capacity - phys_head);
+    /// Returns the capacity of the first allocation.
+    ///
+    /// It is [`DEFAULT_CAPACITY`], but a bounded queue never allocates more than it can contain.
+    fn min_capacity(&self) -> usize {
+        self.max_len
+            .checked_next_power_of_two()
+            .map_or(DEFAULT_CAPACITY, |max_capacity| {
+                max_capacity.min(DEFAULT_CAPACITY)
+            })
+    }
 
-                let src = self.ptr;
-                let dst = new_ptr.add(self.capacity - phys_head);
+    /// Moves the elements to a newly allocated vector with the given capacity.
+    ///
+    /// The provided capacity must be a power of two and must fit all elements.
+    fn reallocate(&mut self, capacity: usize) {
+        debug_assert!(capacity.is_power_of_two() && capacity >= self.len());
 
-                ptr::copy(src, dst, phys_tail);
+        let new_ptr = Self::allocate(capacity);
+        let len = self.len();
+
+        if self.capacity != 0 {
+            unsafe {
+                let phys_head = self.get_physical_index(self.head);
+                let first_part = len.min(self.capacity - phys_head);
+
+                ptr::copy_nonoverlapping(self.ptr.add(phys_head), new_ptr, first_part);
+                ptr::copy_nonoverlapping(self.ptr, new_ptr.add(first_part), len - first_part);
             }
-        }
 
-        Self::deallocate(self.ptr, self.capacity);
+            Self::deallocate(self.ptr, self.capacity);
+        }
 
         self.head = 0;
         self.tail = len;
@@ -170,11 +304,123 @@ impl<T> VecQueue<T> {
         self.mask = Self::get_mask_for_capacity(capacity);
     }
 
+    /// Sets the automatic shrink policy.
+    ///
+    /// When the queue stays less than `1 / denominator` full for `pops` consecutive pops,
+    /// its capacity is halved. It is never shrunk below the default capacity
+    /// and the capacity requested with [`reserve`](Self::reserve) or
+    /// [`extend_to`](Self::extend_to).
+    /// After shrinking, the queue is less than `2 / denominator` full, so the next growth
+    /// happens only if the occupancy grows `denominator / 2` times, that prevents thrashing.
+    ///
+    /// `0` pops disables shrinking, it is the default. The default denominator is `4`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `denominator` is less than `2`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::VecQueue;
+    ///
+    /// let mut queue = VecQueue::new();
+    ///
+    /// queue.set_shrink_threshold(4, 8);
+    ///
+    /// for i in 0..1024 {
+    ///     queue.push(i);
+    /// }
+    ///
+    /// while queue.pop().is_some() {}
+    ///
+    /// for _ in 0..100 {
+    ///     queue.push(1);
+    ///     queue.pop();
+    /// }
+    ///
+    /// assert_eq!(queue.capacity(), 16);
+    /// ```
+    pub fn set_shrink_threshold(&mut self, denominator: usize, pops: usize) {
+        assert!(
+            denominator >= 2,
+            "The shrink denominator must be at least 2, provided {denominator}"
+        );
+
+        self.shrink_after_pops = pops;
+        self.shrink_denominator = denominator;
+        self.low_occupancy_pops = 0;
+    }
+
+    /// Returns the capacity of the queue.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Updates the shrink policy state after a pop.
+    #[inline(always)]
+    fn after_pop(&mut self) {
+        if unlikely(self.shrink_after_pops != 0) {
+            self.track_occupancy_for_shrink();
+        }
+    }
+
+    /// Counts pops with low occupancy and halves the capacity when the threshold is reached.
+    #[inline(never)]
+    fn track_occupancy_for_shrink(&mut self) {
+        if self.capacity / 2 < self.shrink_floor
+            || self.len() >= self.capacity / self.shrink_denominator
+        {
+            self.low_occupancy_pops = 0;
+
+            return;
+        }
+
+        self.low_occupancy_pops += 1;
+
+        if self.low_occupancy_pops >= self.shrink_after_pops {
+            self.low_occupancy_pops = 0;
+            self.reallocate(self.capacity / 2);
+        }
+    }
+
+    /// Grows the queue if it is necessary to push one more element.
+    ///
+    /// Returns `false` if the queue is [`bounded`](Self::bounded) and full.
+    #[inline(always)]
+    fn prepare_push(&mut self) -> bool {
+        let len = self.len();
+
+        if unlikely(len == self.capacity || len == self.max_len) {
+            if len == self.max_len {
+                return false;
+            }
+
+            self.grow();
+        }
+
+        true
+    }
+
     /// Pushes a value to the queue.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the queue is [`bounded`](Self::bounded) and full.
+    /// Use [`try_push`](Self::try_push) for bounded queues.
     #[inline]
     pub fn push(&mut self, value: T) {
-        if unlikely(self.len() == self.capacity) {
-            self.extend_to(self.capacity * 2);
+        if let Err(value) = self.try_push(value) {
+            panic_full_bounded_queue(value);
+        }
+    }
+
+    /// Pushes a value to the queue or returns `Err(value)`
+    /// if the queue is [`bounded`](Self::bounded) and full.
+    #[inline]
+    pub fn try_push(&mut self, value: T) -> Result<(), T> {
+        if unlikely(!self.prepare_push()) {
+            return Err(value);
         }
 
         unsafe {
@@ -184,6 +430,8 @@ impl<T> VecQueue<T> {
         }
 
         self.tail = self.tail.wrapping_add(1);
+
+        Ok(())
     }
 
     /// Pushes the provided value to the front of the queue.
@@ -203,9 +451,22 @@ impl<T> VecQueue<T> {
     /// assert_eq!(queue.pop(), Some(1));
     /// assert_eq!(queue.pop(), Some(2));
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the queue is [`bounded`](Self::bounded) and full.
+    /// Use [`try_push_priority_value`](Self::try_push_priority_value) for bounded queues.
     pub fn push_priority_value(&mut self, value: T) {
-        if unlikely(self.len() == self.capacity) {
-            self.extend_to(self.capacity * 2);
+        if let Err(value) = self.try_push_priority_value(value) {
+            panic_full_bounded_queue(value);
+        }
+    }
+
+    /// Pushes the provided value to the front of the queue or returns `Err(value)`
+    /// if the queue is [`bounded`](Self::bounded) and full.
+    pub fn try_push_priority_value(&mut self, value: T) -> Result<(), T> {
+        if unlikely(!self.prepare_push()) {
+            return Err(value);
         }
 
         self.head = self.head.wrapping_sub(1);
@@ -215,6 +476,8 @@ impl<T> VecQueue<T> {
 
             self.ptr.add(index).write(value);
         }
+
+        Ok(())
     }
 
     /// Pops a value from the queue.
@@ -229,6 +492,8 @@ impl<T> VecQueue<T> {
 
         self.head = self.head.wrapping_add(1);
 
+        self.after_pop();
+
         Some(value)
     }
 
@@ -260,6 +525,90 @@ impl<T> VecQueue<T> {
         let index = self.get_physical_index(self.tail);
         let value = unsafe { self.ptr.add(index).read() };
 
+        self.after_pop();
+
+        Some(value)
+    }
+
+    /// Removes the element at the provided logical index and replaces it with the last element.
+    ///
+    /// It doesn't preserve ordering, but it is O(1).
+    /// Returns `None` if `index` is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::VecQueue;
+    ///
+    /// let mut queue = VecQueue::new();
+    ///
+    /// queue.push(1);
+    /// queue.push(2);
+    /// queue.push(3);
+    /// queue.push(4);
+    ///
+    /// assert_eq!(queue.swap_remove_back(1), Some(2));
+    /// assert_eq!(queue.swap_remove_back(3), None);
+    /// assert_eq!(&*queue.snapshot(), &[1, 4, 3]);
+    /// ```
+    pub fn swap_remove_back(&mut self, index: usize) -> Option<T> {
+        if unlikely(index >= self.len()) {
+            return None;
+        }
+
+        self.tail = self.tail.wrapping_sub(1);
+
+        let removed = self.get_physical_index(self.head.wrapping_add(index));
+        let last = self.get_physical_index(self.tail);
+        let value = unsafe { self.ptr.add(removed).read() };
+
+        if removed != last {
+            unsafe { ptr::copy_nonoverlapping(self.ptr.add(last), self.ptr.add(removed), 1) };
+        }
+
+        self.after_pop();
+
+        Some(value)
+    }
+
+    /// Removes the element at the provided logical index and replaces it with the first element.
+    ///
+    /// It doesn't preserve ordering, but it is O(1).
+    /// Returns `None` if `index` is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::VecQueue;
+    ///
+    /// let mut queue = VecQueue::new();
+    ///
+    /// queue.push(1);
+    /// queue.push(2);
+    /// queue.push(3);
+    /// queue.push(4);
+    ///
+    /// assert_eq!(queue.swap_remove_front(2), Some(3));
+    /// assert_eq!(queue.swap_remove_front(3), None);
+    /// assert_eq!(&*queue.snapshot(), &[2, 1, 4]);
+    /// ```
+    pub fn swap_remove_front(&mut self, index: usize) -> Option<T> {
+        if unlikely(index >= self.len()) {
+            return None;
+        }
+
+        let removed = self.get_physical_index(self.head.wrapping_add(index));
+        let first = self.get_physical_index(self.head);
+        let value = unsafe { self.ptr.add(removed).read() };
+
+        if removed != first {
+            unsafe { ptr::copy_nonoverlapping(self.ptr.add(first), self.ptr.add(removed), 1) };
+        }
+
+        self.head = self.head.wrapping_add(1);
+
+        self.after_pop();
+
         Some(value)
     }
 
@@ -268,9 +617,22 @@ impl<T> VecQueue<T> {
     /// # Safety
     ///
     /// It `T` is not `Copy`, the caller should [`forget`](mem::forget) the values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the queue is [`bounded`](Self::bounded) and the slice doesn't fit.
     #[inline]
     pub unsafe fn extend_from_slice(&mut self, slice: &[T]) {
-        self.reserve(slice.len());
+        if unlikely(slice.is_empty()) {
+            return;
+        }
+
+        assert!(
+            slice.len() <= self.max_len - self.len(),
+            "Tried to extend a bounded VecQueue over its maximum length"
+        );
+
+        self.grow_to_fit(slice.len());
 
         let phys_tail = self.get_physical_index(self.tail);
         let right_space = self.capacity - phys_tail;
@@ -336,6 +698,10 @@ impl<T> VecQueue<T> {
     ) -> R {
         limit = self.len().min(limit);
 
+        if unlikely(limit == 0) {
+            return f(&[], &[]);
+        }
+
         let phys_head = self.get_physical_index(self.head);
         let right_occupied = self.capacity - phys_head;
 
@@ -358,6 +724,230 @@ impl<T> VecQueue<T> {
         // The head is already updated.
     }
 
+    /// Returns a pair of slices that represent the occupied region of the queue in logical order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::VecQueue;
+    ///
+    /// let mut queue = VecQueue::new();
+    ///
+    /// queue.push(1);
+    /// queue.push(2);
+    /// queue.push_priority_value(0);
+    ///
+    /// let should_be: (&[u32], &[u32]) = (&[0], &[1, 2]);
+    ///
+    /// assert_eq!(queue.as_slices(), should_be);
+    /// ```
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        if self.is_empty() {
+            return (&[], &[]);
+        }
+
+        let len = self.len();
+        let phys_head = self.get_physical_index(self.head);
+        let first_part = len.min(self.capacity - phys_head);
+
+        unsafe {
+            (
+                &*slice_from_raw_parts(self.ptr.add(phys_head), first_part),
+                &*slice_from_raw_parts(self.ptr, len - first_part),
+            )
+        }
+    }
+
+    /// Returns a boxed slice with clones of the elements of the queue in logical order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::VecQueue;
+    ///
+    /// let mut queue = VecQueue::new();
+    ///
+    /// queue.push(1);
+    /// queue.push(2);
+    /// queue.push_priority_value(0);
+    ///
+    /// assert_eq!(&*queue.snapshot(), &[0, 1, 2]);
+    /// assert_eq!(queue.len(), 3);
+    /// ```
+    pub fn snapshot(&self) -> Box<[T]>
+    where
+        T: Clone,
+    {
+        let (first, second) = self.as_slices();
+        let mut vec = Vec::with_capacity(self.len());
+
+        vec.extend_from_slice(first);
+        vec.extend_from_slice(second);
+
+        vec.into_boxed_slice()
+    }
+
+    /// Converts the queue into a boxed slice with the elements in logical order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::VecQueue;
+    ///
+    /// let mut queue = VecQueue::new();
+    ///
+    /// queue.push(1);
+    /// queue.push(2);
+    /// queue.push_priority_value(0);
+    ///
+    /// assert_eq!(&*queue.into_boxed_slice(), &[0, 1, 2]);
+    /// ```
+    pub fn into_boxed_slice(self) -> Box<[T]> {
+        let this = mem::ManuallyDrop::new(self);
+        let (first, second) = this.as_slices();
+        let mut vec = Vec::with_capacity(this.len());
+
+        unsafe {
+            ptr::copy_nonoverlapping(first.as_ptr(), vec.as_mut_ptr(), first.len());
+            ptr::copy_nonoverlapping(
+                second.as_ptr(),
+                vec.as_mut_ptr().add(first.len()),
+                second.len(),
+            );
+
+            vec.set_len(this.len());
+        }
+
+        Self::deallocate(this.ptr, this.capacity);
+
+        vec.into_boxed_slice()
+    }
+
+    /// Returns `true` if the queue contains an element equal to the provided value.
+    pub fn contains(&self, value: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        let (first, second) = self.as_slices();
+
+        first.contains(value) || second.contains(value)
+    }
+
+    /// Removes all but the first of consecutive elements in the queue
+    /// that resolve to the same key.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::VecQueue;
+    ///
+    /// let mut queue = VecQueue::new();
+    ///
+    /// for i in [20, 21, 30, 20] {
+    ///     queue.push(i);
+    /// }
+    ///
+    /// queue.push_priority_value(10); // the queue is wrapped now
+    ///
+    /// assert!(queue.contains(&21));
+    ///
+    /// queue.dedup_by_key(|i| *i / 10);
+    ///
+    /// assert!(!queue.contains(&21));
+    ///
+    /// assert_eq!(&*queue.snapshot(), &[10, 20, 30, 20]);
+    /// ```
+    pub fn dedup_by_key<K, F>(&mut self, mut key: F)
+    where
+        F: FnMut(&mut T) -> K,
+        K: PartialEq,
+    {
+        self.dedup_by(|a, b| key(a) == key(b));
+    }
+
+    /// Removes all but the first of consecutive elements in the queue
+    /// satisfying a given equality relation.
+    ///
+    /// The `same_bucket` function is passed references to two elements from the queue:
+    /// the checked element and the last retained one. If it returns `true`,
+    /// the checked element is removed.
+    pub fn dedup_by<F>(&mut self, mut same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        /// Closes the gap between retained and not yet checked elements
+        /// even if `same_bucket` or a destructor panics.
+        struct Guard<'queue, T> {
+            queue: &'queue mut VecQueue<T>,
+            len: usize,
+            read: usize,
+            write: usize,
+        }
+
+        impl<T> Guard<'_, T> {
+            fn ptr_at(&self, logical: usize) -> *mut T {
+                let queue = &*self.queue;
+
+                unsafe {
+                    queue
+                        .ptr
+                        .add(queue.get_physical_index(queue.head.wrapping_add(logical)))
+                }
+            }
+        }
+
+        impl<T> Drop for Guard<'_, T> {
+            fn drop(&mut self) {
+                if self.read != self.write {
+                    for i in 0..self.len - self.read {
+                        unsafe {
+                            ptr::copy_nonoverlapping(
+                                self.ptr_at(self.read + i),
+                                self.ptr_at(self.write + i),
+                                1,
+                            );
+                        }
+                    }
+                }
+
+                let len = self.write + (self.len - self.read);
+
+                self.queue.tail = self.queue.head.wrapping_add(len);
+            }
+        }
+
+        let len = self.len();
+
+        if len <= 1 {
+            return;
+        }
+
+        let mut guard = Guard {
+            queue: self,
+            len,
+            read: 1,
+            write: 1,
+        };
+
+        while guard.read < len {
+            let current = guard.ptr_at(guard.read);
+            let last_retained = guard.ptr_at(guard.write - 1);
+
+            if same_bucket(unsafe { &mut *current }, unsafe { &mut *last_retained }) {
+                guard.read += 1;
+
+                unsafe { ptr::drop_in_place(current) };
+            } else {
+                if guard.read != guard.write {
+                    unsafe { ptr::copy_nonoverlapping(current, guard.ptr_at(guard.write), 1) };
+                }
+
+                guard.read += 1;
+                guard.write += 1;
+            }
+        }
+    }
+
     /// Clears the queue by calling the provided function on each element.
     pub fn clear_with<F: Fn(T)>(&mut self, f: F) {
         for i in 0..self.len() {
@@ -383,17 +973,40 @@ impl<T> VecQueue<T> {
     }
 
     /// Returns an iterator over the queue.
-    pub fn iter(&self) -> impl Iterator<Item = &T> {
+    ///
+    /// It can be iterated from both ends.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use orengine_utils::VecQueue;
+    ///
+    /// let mut queue = VecQueue::new();
+    ///
+    /// queue.push(1);
+    /// queue.push(2);
+    /// queue.push(3);
+    ///
+    /// let mut iter = queue.iter();
+    ///
+    /// assert_eq!(iter.len(), 3);
+    /// assert_eq!(iter.next_back(), Some(&3));
+    /// assert_eq!(iter.next(), Some(&1));
+    /// assert_eq!(iter.next_back(), Some(&2));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
         struct Iter<'queue, T> {
             queue: &'queue VecQueue<T>,
             current_head: usize,
+            current_tail: usize,
         }
 
         impl<'queue, T> Iterator for Iter<'queue, T> {
             type Item = &'queue T;
 
             fn next(&mut self) -> Option<Self::Item> {
-                if unlikely(self.current_head == self.queue.tail) {
+                if unlikely(self.current_head == self.current_tail) {
                     return None;
                 }
 
@@ -403,42 +1016,101 @@ impl<T> VecQueue<T> {
 
                 Some(unsafe { &*self.queue.ptr.add(index) })
             }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                let size = self.current_tail.wrapping_sub(self.current_head);
+
+                (size, Some(size))
+            }
+        }
+
+        impl<T> DoubleEndedIterator for Iter<'_, T> {
+            fn next_back(&mut self) -> Option<Self::Item> {
+                if unlikely(self.current_head == self.current_tail) {
+                    return None;
+                }
+
+                self.current_tail = self.current_tail.wrapping_sub(1);
+
+                let index = self.queue.get_physical_index(self.current_tail);
+
+                Some(unsafe { &*self.queue.ptr.add(index) })
+            }
+        }
+
+        impl<T> ExactSizeIterator for Iter<'_, T> {
+            fn len(&self) -> usize {
+                self.current_tail.wrapping_sub(self.current_head)
+            }
         }
 
         Iter {
             queue: self,
             current_head: self.head,
+            current_tail: self.tail,
         }
     }
 
     /// Returns a mutable iterator over the queue.
-    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
-        struct Iter<'queue, T> {
-            queue: &'queue mut VecQueue<T>,
+    ///
+    /// It can be iterated from both ends.
+    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut T> + ExactSizeIterator {
+        struct IterMut<'queue, T> {
+            ptr: *mut T,
+            mask: usize,
             current_head: usize,
+            current_tail: usize,
+            _marker: core::marker::PhantomData<&'queue mut T>,
         }
 
-        impl<'queue, T> Iterator for Iter<'queue, T> {
+        impl<'queue, T> Iterator for IterMut<'queue, T> {
             type Item = &'queue mut T;
 
             fn next(&mut self) -> Option<Self::Item> {
-                if unlikely(self.current_head == self.queue.tail) {
+                if unlikely(self.current_head == self.current_tail) {
                     return None;
                 }
 
-                let index = self.queue.get_physical_index(self.current_head);
+                let index = self.current_head & self.mask;
 
                 self.current_head = self.current_head.wrapping_add(1);
 
-                Some(unsafe { &mut *self.queue.ptr.add(index) })
+                Some(unsafe { &mut *self.ptr.add(index) })
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                let size = self.current_tail.wrapping_sub(self.current_head);
+
+                (size, Some(size))
             }
         }
 
-        let head = self.head;
+        impl<T> DoubleEndedIterator for IterMut<'_, T> {
+            fn next_back(&mut self) -> Option<Self::Item> {
+                if unlikely(self.current_head == self.current_tail) {
+                    return None;
+                }
 
-        Iter {
-            queue: self,
-            current_head: head,
+                self.current_tail = self.current_tail.wrapping_sub(1);
+
+                let index = self.current_tail & self.mask;
+
+                Some(unsafe { &mut *self.ptr.add(index) })
+            }
+        }
+
+        impl<T> ExactSizeIterator for IterMut<'_, T> {
+            fn len(&self) -> usize {
+                self.current_tail.wrapping_sub(self.current_head)
+            }
+        }
+
+        IterMut {
+            ptr: self.ptr,
+            mask: self.mask,
+            current_head: self.head,
+            current_tail: self.tail,
+            _marker: core::marker::PhantomData,
         }
     }
 }
@@ -447,7 +1119,11 @@ impl<T: Clone> Clone for VecQueue<T> {
     fn clone(&self) -> Self {
         let mut new = Self::new();
 
-        new.extend_to(new.capacity);
+        new.grow_to_fit(self.len());
+        new.max_len = self.max_len;
+        new.shrink_after_pops = self.shrink_after_pops;
+        new.shrink_denominator = self.shrink_denominator;
+        new.shrink_floor = self.shrink_floor;
 
         for i in 0..self.len() {
             let elem = unsafe { &*self.ptr.add(self.get_physical_index(self.head + i)) };
@@ -467,12 +1143,135 @@ impl<T> Default for VecQueue<T> {
 
 impl<T> Drop for VecQueue<T> {
     fn drop(&mut self) {
-        if mem::needs_drop::<T>() {
-            while let Some(val) = self.pop() {
-                drop(val);
+        self.clear();
+
+        Self::deallocate(self.ptr, self.capacity);
+    }
+}
+
+#[cfg(all(test, not(miri)))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounded_first_allocation() {
+        for max_len in 1..4 {
+            let mut queue = VecQueue::<u32>::bounded(max_len);
+
+            queue.extend_to(0);
+
+            assert_eq!(queue.capacity(), max_len.next_power_of_two());
+
+            let mut queue = VecQueue::bounded(max_len);
+
+            while queue.try_push(0).is_ok() {}
+
+            assert_eq!(queue.len(), max_len);
+            assert_eq!(queue.capacity(), max_len.next_power_of_two());
+        }
+
+        let mut queue = VecQueue::<u32>::new();
+
+        queue.extend_to(0);
+
+        assert_eq!(queue.capacity(), DEFAULT_CAPACITY);
+    }
+
+    /// Creates a queue with the provided shrink policy and `len` elements.
+    fn shrinking_queue(denominator: usize, pops: usize, len: usize) -> VecQueue<usize> {
+        let mut queue = VecQueue::new();
+
+        queue.set_shrink_threshold(denominator, pops);
+
+        for i in 0..len {
+            queue.push(i);
+        }
+
+        queue
+    }
+
+    #[test]
+    fn test_shrink_at_occupancy_boundary() {
+        for denominator in [2, 4, 8] {
+            let mut queue = shrinking_queue(denominator, 1, 64);
+            let boundary = 64 / denominator;
+
+            while queue.len() > boundary {
+                queue.pop();
             }
+
+            assert_eq!(queue.capacity(), 64);
+
+            queue.pop();
+
+            assert_eq!(queue.capacity(), 32);
+            assert!(queue.iter().copied().eq(64 - boundary + 1..64));
         }
+    }
 
-        Self::deallocate(self.ptr, self.capacity);
+    #[test]
+    fn test_shrink_pops_threshold() {
+        let mut queue = shrinking_queue(4, 3, 64);
+
+        while queue.len() > 15 {
+            queue.pop();
+        }
+
+        queue.pop();
+
+        assert_eq!(queue.capacity(), 64);
+
+        queue.pop();
+
+        assert_eq!(queue.capacity(), 32);
+    }
+
+    #[test]
+    fn test_shrink_floor() {
+        let mut queue = shrinking_queue(4, 1, 1024);
+
+        while queue.pop().is_some() {}
+
+        assert_eq!(queue.capacity(), DEFAULT_CAPACITY);
+
+        let mut queue = shrinking_queue(4, 1, 0);
+
+        queue.reserve(100);
+
+        assert_eq!(queue.capacity(), 128);
+
+        queue.extend_to(256);
+
+        for i in 0..1024 {
+            queue.push(i);
+        }
+
+        while queue.pop().is_some() {}
+
+        assert_eq!(queue.capacity(), 256);
+    }
+
+    #[test]
+    fn test_shrink_does_not_thrash() {
+        let mut queue = shrinking_queue(4, 1, 64);
+
+        while queue.len() > 15 {
+            queue.pop();
+        }
+
+        assert_eq!(queue.capacity(), 32);
+
+        for i in 0..1000 {
+            queue.push(i);
+            queue.pop();
+
+            assert_eq!(queue.capacity(), 32);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "The shrink denominator must be at least 2")]
+    fn test_shrink_denominator_too_small() {
+        VecQueue::<u32>::new().set_shrink_threshold(1, 8);
     }
 }
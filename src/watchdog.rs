@@ -0,0 +1,170 @@
+//! This module provides the [`Watchdog`] that detects stalled workers.
+//!
+//! Workers periodically call [`Watchdog::heartbeat`], and a monitor thread calls
+//! [`Watchdog::check`] to find workers that have not done it for too long.
+//!
+//! # Example
+//!
+//! ```rust
+//! use orengine_utils::watchdog::Watchdog;
+//! use std::time::Duration;
+//!
+//! let watchdog = Watchdog::new(2);
+//!
+//! std::thread::sleep(Duration::from_millis(20));
+//!
+//! watchdog.heartbeat(0);
+//!
+//! assert_eq!(watchdog.check(Duration::from_millis(10)), vec![1]);
+//! ```
+use crate::cache_padded::CachePaddedAtomicU64;
+use crate::OrengineInstant;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+use core::sync::atomic::Ordering;
+use std::time::Duration;
+
+/// An identifier of a worker monitored by the [`Watchdog`].
+///
+/// It is an index in `0..workers_count`.
+pub type WorkerId = usize;
+
+/// A callback that is called by [`Watchdog::check`] for each stalled worker
+/// with the time elapsed since its last heartbeat.
+type StallCallback = Box<dyn Fn(WorkerId, Duration) + Send + Sync>;
+
+/// Detects stalled workers.
+///
+/// Each worker has its own cache-padded timestamp of the last heartbeat,
+/// so heartbeats of different workers don't contend with each other.
+///
+/// Read the [`module-level documentation`](crate::watchdog) for more details.
+pub struct Watchdog {
+    start: OrengineInstant,
+    /// Nanoseconds since `start` of the last heartbeat of each worker.
+    last_heartbeats: Box<[CachePaddedAtomicU64]>,
+    on_stall: Option<StallCallback>,
+}
+
+impl Watchdog {
+    /// Creates a new `Watchdog` for `workers_count` workers.
+    ///
+    /// All workers are considered to have sent a heartbeat at the moment of creation.
+    pub fn new(workers_count: usize) -> Self {
+        Self {
+            start: OrengineInstant::now(),
+            last_heartbeats: (0..workers_count)
+                .map(|_| CachePaddedAtomicU64::new(0))
+                .collect(),
+            on_stall: None,
+        }
+    }
+
+    /// Sets the callback that is called by [`check`](Self::check) for each stalled worker
+    /// with the time elapsed since its last heartbeat.
+    #[must_use]
+    pub fn with_callback<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(WorkerId, Duration) + Send + Sync + 'static,
+    {
+        self.on_stall = Some(Box::new(callback));
+
+        self
+    }
+
+    /// Returns the number of monitored workers.
+    pub fn workers_count(&self) -> usize {
+        self.last_heartbeats.len()
+    }
+
+    /// Returns the number of nanoseconds elapsed since the creation of the watchdog.
+    fn now_nanos(&self) -> u64 {
+        u64::try_from(self.start.elapsed().as_nanos()).unwrap_or(u64::MAX)
+    }
+
+    /// Records a heartbeat of the provided worker.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `worker` is not less than [`workers_count`](Self::workers_count).
+    #[inline]
+    pub fn heartbeat(&self, worker: WorkerId) {
+        self.last_heartbeats[worker].store(self.now_nanos(), Ordering::Relaxed);
+    }
+
+    /// Returns the time elapsed since the last heartbeat of the provided worker.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `worker` is not less than [`workers_count`](Self::workers_count).
+    pub fn since_last_heartbeat(&self, worker: WorkerId) -> Duration {
+        let last = self.last_heartbeats[worker].load(Ordering::Relaxed);
+
+        Duration::from_nanos(self.now_nanos().saturating_sub(last))
+    }
+
+    /// Returns the workers that have not sent a heartbeat for more than `max_stall`.
+    ///
+    /// If the callback is set, it is called for each of them.
+    pub fn check(&self, max_stall: Duration) -> Vec<WorkerId> {
+        let now = self.now_nanos();
+        let mut stalled = Vec::new();
+
+        for (worker, last_heartbeat) in self.last_heartbeats.iter().enumerate() {
+            let stall =
+                Duration::from_nanos(now.saturating_sub(last_heartbeat.load(Ordering::Relaxed)));
+
+            if stall > max_stall {
+                if let Some(on_stall) = &self.on_stall {
+                    on_stall(worker, stall);
+                }
+
+                stalled.push(worker);
+            }
+        }
+
+        stalled
+    }
+}
+
+impl fmt::Debug for Watchdog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Watchdog")
+            .field("workers_count", &self.workers_count())
+            .field("has_callback", &self.on_stall.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::sync::Arc;
+    use alloc::vec;
+    use core::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn test_watchdog_check() {
+        let stalls = Arc::new(AtomicUsize::new(0));
+        let stalls_clone = stalls.clone();
+        let watchdog = Watchdog::new(3).with_callback(move |worker, stall| {
+            assert_ne!(worker, 1);
+            assert!(stall > Duration::from_millis(10));
+
+            stalls_clone.fetch_add(1, Ordering::Relaxed);
+        });
+
+        assert!(watchdog.check(Duration::from_secs(10)).is_empty());
+
+        std::thread::sleep(Duration::from_millis(20));
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| watchdog.heartbeat(1));
+        });
+
+        assert!(watchdog.since_last_heartbeat(1) < watchdog.since_last_heartbeat(0));
+        assert_eq!(watchdog.check(Duration::from_millis(10)), vec![0, 2]);
+        assert_eq!(stalls.load(Ordering::Relaxed), 2);
+    }
+}